@@ -74,6 +74,8 @@ fn rust_main() -> Result<(), i32> {
             DisconnectReason::TargetExited(_) => print_str("Target exited"),
             DisconnectReason::TargetTerminated(_) => print_str("Target halted"),
             DisconnectReason::Kill => print_str("GDB sent a kill command"),
+            DisconnectReason::ProtocolError(_) => print_str("Protocol error"),
+            _ => print_str("GDB session ended"),
         },
         Err(e) => {
             if e.is_target_error() {
@@ -1,6 +1,6 @@
-use super::copy_range_to_buf;
 use crate::emu::Emu;
 use gdbstub::target;
+use gdbstub::target::ext::copy_range_to_buf;
 use gdbstub::target::TargetResult;
 
 impl target::ext::auxv::Auxv for Emu {
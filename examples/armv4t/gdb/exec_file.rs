@@ -2,16 +2,22 @@ use super::copy_range_to_buf;
 use crate::emu::Emu;
 use gdbstub::common::Pid;
 use gdbstub::target;
+use gdbstub::target::TargetError;
 use gdbstub::target::TargetResult;
 
 impl target::ext::exec_file::ExecFile for Emu {
     fn get_exec_file(
         &self,
-        _pid: Option<Pid>,
+        pid: Option<Pid>,
         offset: u64,
         length: usize,
         buf: &mut [u8],
     ) -> TargetResult<usize, Self> {
+        // the emulator only ever runs a single process
+        if matches!(pid, Some(pid) if pid != self.reported_pid) {
+            return Err(TargetError::NonFatal);
+        }
+
         let filename = b"/test.elf";
         Ok(copy_range_to_buf(filename, offset, length, buf))
     }
@@ -110,8 +110,8 @@ impl target::ext::extended_mode::ExtendedMode for Emu {
 }
 
 impl target::ext::extended_mode::ConfigureAslr for Emu {
-    fn cfg_aslr(&mut self, enabled: bool) -> TargetResult<(), Self> {
-        eprintln!("GDB {} ASLR", if enabled { "enabled" } else { "disabled" });
+    fn cfg_disable_randomization(&mut self, disabled: bool) -> TargetResult<(), Self> {
+        eprintln!("GDB {} ASLR", if disabled { "disabled" } else { "enabled" });
         Ok(())
     }
 }
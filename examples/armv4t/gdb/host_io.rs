@@ -241,6 +241,7 @@ impl target::ext::host_io::HostIoUnlink for Emu {
     fn unlink(&mut self, filename: &[u8]) -> HostIoResult<(), Self> {
         let path =
             std::str::from_utf8(filename).map_err(|_| HostIoError::Errno(HostIoErrno::ENOENT))?;
+        // a missing file is reported to GDB as `ENOENT` via `From<std::io::Error>`
         std::fs::remove_file(path)?;
         Ok(())
     }
@@ -276,7 +277,13 @@ impl target::ext::host_io::HostIoReadlink for Emu {
 }
 
 impl target::ext::host_io::HostIoSetfs for Emu {
-    fn setfs(&mut self, _fs: FsKind) -> HostIoResult<(), Self> {
-        Ok(())
+    fn setfs(&mut self, fs: FsKind) -> HostIoResult<(), Self> {
+        // The emulator only has a single process, sharing the stub's filesystem
+        match fs {
+            FsKind::Pid(pid) if pid != self.reported_pid => {
+                Err(HostIoError::Errno(HostIoErrno::EINVAL))
+            }
+            _ => Ok(()),
+        }
     }
 }
@@ -0,0 +1,26 @@
+use crate::emu::Emu;
+use armv4t_emu::Memory;
+use gdbstub::target;
+use gdbstub::target::TargetResult;
+
+impl target::ext::memory_search::MemorySearch for Emu {
+    fn search_memory(
+        &mut self,
+        start_addr: u32,
+        len: u32,
+        pattern: &[u8],
+    ) -> TargetResult<Option<u32>, Self> {
+        if pattern.is_empty() || (pattern.len() as u64) > len as u64 {
+            return Ok(None);
+        }
+
+        let last = start_addr.wrapping_add(len - pattern.len() as u32);
+        let hit = (start_addr..=last).find(|&addr| {
+            (addr..)
+                .zip(pattern.iter())
+                .all(|(addr, &b)| self.mem.r8(addr) == b)
+        });
+
+        Ok(hit)
+    }
+}
@@ -0,0 +1,41 @@
+use crate::emu::Emu;
+use gdbstub::target;
+use gdbstub::target::TargetResult;
+
+// This implementation is for illustrative purposes only. The armv4t doesn't
+// support memory tagging, so every 16-byte granule reports a tag of zero.
+
+const GRANULE_SIZE: usize = 16;
+
+impl target::ext::memory_tagging::MemoryTagging for Emu {
+    fn read_memory_tags(
+        &mut self,
+        start_addr: u32,
+        len: usize,
+        ty: i32,
+        buf: &mut [u8],
+    ) -> TargetResult<usize, Self> {
+        eprintln!(
+            "Reading memory tags: addr={:#x?} len={:#x?} type={}",
+            start_addr, len, ty
+        );
+
+        let granules = len.div_ceil(GRANULE_SIZE).min(buf.len());
+        buf[..granules].fill(0);
+        Ok(granules)
+    }
+
+    fn write_memory_tags(
+        &mut self,
+        start_addr: u32,
+        len: usize,
+        ty: i32,
+        tags: &[u8],
+    ) -> TargetResult<(), Self> {
+        eprintln!(
+            "Writing memory tags: addr={:#x?} len={:#x?} type={} tags={:x?}",
+            start_addr, len, ty, tags
+        );
+        Ok(())
+    }
+}
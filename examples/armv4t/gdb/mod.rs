@@ -23,7 +23,11 @@ mod host_io;
 mod libraries;
 mod lldb_register_info_override;
 mod memory_map;
+mod memory_search;
+mod memory_tagging;
 mod monitor_cmd;
+mod pass_signals;
+mod program_signals;
 mod section_offsets;
 mod target_description_xml_override;
 
@@ -140,6 +144,20 @@ impl Target for Emu {
         Some(self)
     }
 
+    #[inline(always)]
+    fn support_pass_signals(
+        &mut self,
+    ) -> Option<target::ext::pass_signals::PassSignalsOps<'_, Self>> {
+        Some(self)
+    }
+
+    #[inline(always)]
+    fn support_program_signals(
+        &mut self,
+    ) -> Option<target::ext::program_signals::ProgramSignalsOps<'_, Self>> {
+        Some(self)
+    }
+
     #[inline(always)]
     fn support_host_io(&mut self) -> Option<target::ext::host_io::HostIoOps<'_, Self>> {
         Some(self)
@@ -161,6 +179,20 @@ impl Target for Emu {
     ) -> Option<target::ext::libraries::LibrariesSvr4Ops<'_, Self>> {
         Some(self)
     }
+
+    #[inline(always)]
+    fn support_memory_search(
+        &mut self,
+    ) -> Option<target::ext::memory_search::MemorySearchOps<'_, Self>> {
+        Some(self)
+    }
+
+    #[inline(always)]
+    fn support_memory_tagging(
+        &mut self,
+    ) -> Option<target::ext::memory_tagging::MemoryTaggingOps<'_, Self>> {
+        Some(self)
+    }
 }
 
 impl SingleThreadBase for Emu {
@@ -2,13 +2,14 @@ use crate::gdb::Emu;
 use gdbstub::target;
 use gdbstub::target::ext::monitor_cmd::outputln;
 use gdbstub::target::ext::monitor_cmd::ConsoleOutput;
+use gdbstub::target::TargetResult;
 
 impl target::ext::monitor_cmd::MonitorCmd for Emu {
     fn handle_monitor_cmd(
         &mut self,
         cmd: &[u8],
         mut out: ConsoleOutput<'_>,
-    ) -> Result<(), Self::Error> {
+    ) -> TargetResult<(), Self> {
         let cmd = match core::str::from_utf8(cmd) {
             Ok(cmd) => cmd,
             Err(_) => {
@@ -0,0 +1,13 @@
+use crate::gdb::Emu;
+use gdbstub::common::Signal;
+use gdbstub::target;
+
+// This implementation is for illustrative purposes only. The armv4t emulator
+// doesn't deliver any signals, so there is nothing to pass through.
+
+impl target::ext::pass_signals::PassSignals for Emu {
+    fn pass_signals(&mut self, signals: &[Signal]) -> target::TargetResult<(), Self> {
+        eprintln!("Passing signals: {:?}", signals);
+        Ok(())
+    }
+}
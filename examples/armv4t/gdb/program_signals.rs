@@ -0,0 +1,13 @@
+use crate::gdb::Emu;
+use gdbstub::common::Signal;
+use gdbstub::target;
+
+// This implementation is for illustrative purposes only. The armv4t emulator
+// doesn't deliver any signals, so there is nothing to filter.
+
+impl target::ext::program_signals::ProgramSignals for Emu {
+    fn program_signals(&mut self, signals: &[Signal]) -> target::TargetResult<(), Self> {
+        eprintln!("Program signals: {:?}", signals);
+        Ok(())
+    }
+}
@@ -6,7 +6,7 @@ use gdbstub::target::TargetResult;
 
 impl target::ext::target_description_xml_override::TargetDescriptionXmlOverride for Emu {
     fn target_description_xml(
-        &self,
+        &mut self,
         annex: &[u8],
         offset: u64,
         length: usize,
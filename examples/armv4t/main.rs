@@ -180,6 +180,10 @@ fn main() -> DynResult<()> {
                 println!("Target terminated with signal {}!", sig)
             }
             DisconnectReason::Kill => println!("GDB sent a kill command!"),
+            DisconnectReason::ProtocolError(e) => {
+                println!("Session aborted due to a protocol error: {:?}", e)
+            }
+            reason => println!("GDB session ended: {:?}", reason),
         },
         Err(e) => {
             if e.is_target_error() {
@@ -138,6 +138,13 @@ impl MultiThreadBase for Emu {
     ) -> Option<gdbstub::target::ext::thread_extra_info::ThreadExtraInfoOps<'_, Self>> {
         Some(self)
     }
+
+    #[inline(always)]
+    fn support_thread_list(
+        &mut self,
+    ) -> Option<gdbstub::target::ext::thread_list::ThreadListOps<'_, Self>> {
+        Some(self)
+    }
 }
 
 impl MultiThreadResume for Emu {
@@ -294,6 +301,27 @@ impl target::ext::thread_extra_info::ThreadExtraInfo for Emu {
     }
 }
 
+impl target::ext::thread_list::ThreadList for Emu {
+    fn list_threads(
+        &mut self,
+        report: &mut dyn FnMut(target::ext::thread_list::ThreadInfo<'_>),
+    ) -> Result<(), Self::Error> {
+        use target::ext::thread_list::ThreadInfo;
+
+        report(ThreadInfo {
+            tid: cpuid_to_tid(CpuId::Cpu),
+            name: Some(b"cpu"),
+            core: Some(0),
+        });
+        report(ThreadInfo {
+            tid: cpuid_to_tid(CpuId::Cop),
+            name: Some(b"cop"),
+            core: Some(1),
+        });
+        Ok(())
+    }
+}
+
 /// Copy all bytes of `data` to `buf`.
 /// Return the size of data copied.
 pub fn copy_to_buf(data: &[u8], buf: &mut [u8]) -> usize {
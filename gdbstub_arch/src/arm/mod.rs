@@ -43,3 +43,44 @@ impl Arch for Armv4t {
         Some(r#"<target version="1.0"><architecture>armv4t</architecture></target>"#)
     }
 }
+
+/// Implements `Arch` for ARM Cortex-M (M-profile) cores, such as the ARMv7E-M
+/// based Cortex-M4 and Cortex-M7.
+///
+/// When `FPU` is `true`, the target XML also advertises the single precision
+/// floating point registers (as the `d0`-`d15` double precision registers, from
+/// which GDB derives `s0`-`s31`) and `fpscr`.
+///
+/// See [`ArmV7em`] and [`ArmV7emf`] for convenient type aliases.
+pub enum ArmCortexM<const FPU: bool> {}
+
+/// ARMv7E-M core without an FPU (e.g: Cortex-M4).
+pub type ArmV7em = ArmCortexM<false>;
+
+/// ARMv7E-M core with an FPU (e.g: Cortex-M4F).
+pub type ArmV7emf = ArmCortexM<true>;
+
+impl<const FPU: bool> Arch for ArmCortexM<FPU> {
+    type Usize = u32;
+    type Registers = reg::ArmCortexMRegs<FPU>;
+    type RegId = reg::id::ArmCortexMRegId;
+    type BreakpointKind = ArmBreakpointKind;
+
+    fn target_description_xml() -> Option<&'static str> {
+        static NO_FPU_XML: &str = concat!(
+            r#"<target version="1.0">"#,
+            "<architecture>armv7e-m</architecture>",
+            include_str!("m_profile.xml"), // feature "org.gnu.gdb.arm.m-profile"
+            "</target>",
+        );
+        static FPU_XML: &str = concat!(
+            r#"<target version="1.0">"#,
+            "<architecture>armv7e-m</architecture>",
+            include_str!("m_profile.xml"), // feature "org.gnu.gdb.arm.m-profile"
+            include_str!("vfpv2.xml"),     // feature "org.gnu.gdb.arm.vfp"
+            "</target>",
+        );
+
+        Some(if FPU { FPU_XML } else { NO_FPU_XML })
+    }
+}
@@ -0,0 +1,161 @@
+use gdbstub::arch::Registers;
+
+/// ARM Cortex-M (M-profile) registers.
+///
+/// The floating point registers are only (de)serialized when `FPU` is `true`.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/arm/arm-m-profile.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/arm/arm-vfpv2.xml>
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ArmCortexMRegs<const FPU: bool> {
+    /// General purpose registers (R0-R12)
+    pub r: [u32; 13],
+    /// Stack Pointer (R13)
+    pub sp: u32,
+    /// Link Register (R14)
+    pub lr: u32,
+    /// Program Counter (R15)
+    pub pc: u32,
+    /// Combined Program Status Register (xpsr)
+    pub xpsr: u32,
+    /// Single precision floating point registers (S0-S31)
+    pub s: [u32; 32],
+    /// Floating Point Status and Control Register
+    pub fpscr: u32,
+}
+
+impl<const FPU: bool> Registers for ArmCortexMRegs<FPU> {
+    type ProgramCounter = u32;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($bytes:expr) => {
+                for b in $bytes {
+                    write_byte(Some(*b))
+                }
+            };
+        }
+
+        for reg in self.r.iter() {
+            write_bytes!(&reg.to_le_bytes());
+        }
+        write_bytes!(&self.sp.to_le_bytes());
+        write_bytes!(&self.lr.to_le_bytes());
+        write_bytes!(&self.pc.to_le_bytes());
+        write_bytes!(&self.xpsr.to_le_bytes());
+
+        if FPU {
+            // D<n> is made up of S<2n> (low word) and S<2n+1> (high word)
+            for reg in self.s.iter() {
+                write_bytes!(&reg.to_le_bytes());
+            }
+            write_bytes!(&self.fpscr.to_le_bytes());
+        }
+    }
+
+    fn gdb_deserialize(&mut self, mut bytes: &[u8]) -> Result<(), ()> {
+        let expected_len = if FPU { (17 + 33) * 4 } else { 17 * 4 };
+        if bytes.len() != expected_len {
+            return Err(());
+        }
+
+        let mut next_reg = || {
+            if bytes.len() < 4 {
+                Err(())
+            } else {
+                use core::convert::TryInto;
+
+                let (next, rest) = bytes.split_at(4);
+                bytes = rest;
+                Ok(u32::from_le_bytes(next.try_into().unwrap()))
+            }
+        };
+
+        for reg in self.r.iter_mut() {
+            *reg = next_reg()?
+        }
+        self.sp = next_reg()?;
+        self.lr = next_reg()?;
+        self.pc = next_reg()?;
+        self.xpsr = next_reg()?;
+
+        if FPU {
+            for reg in self.s.iter_mut() {
+                *reg = next_reg()?
+            }
+            self.fpscr = next_reg()?;
+        }
+
+        if next_reg().is_ok() {
+            return Err(());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arm::reg::id::ArmCortexMRegId;
+    use gdbstub::arch::RegId;
+
+    fn round_trip<const FPU: bool>() -> Vec<u8> {
+        let mut regs_before = ArmCortexMRegs::<FPU> {
+            sp: 13,
+            lr: 14,
+            pc: 15,
+            xpsr: 0x0100_0000,
+            fpscr: 42,
+            ..Default::default()
+        };
+        for (i, reg) in regs_before.r.iter_mut().enumerate() {
+            *reg = i as u32;
+        }
+        for (i, reg) in regs_before.s.iter_mut().enumerate() {
+            *reg = 100 + i as u32;
+        }
+
+        let mut data = vec![];
+        regs_before.gdb_serialize(|x| data.push(x.unwrap()));
+
+        let mut regs_after = ArmCortexMRegs::<FPU>::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+        if !FPU {
+            regs_before.s = Default::default();
+            regs_before.fpscr = 0;
+        }
+        assert_eq!(regs_before, regs_after);
+
+        data
+    }
+
+    fn reg_id_size(id: usize) -> usize {
+        ArmCortexMRegId::from_raw_id(id).unwrap().1.unwrap().get()
+    }
+
+    #[test]
+    fn cortex_m_no_fpu() {
+        let data = round_trip::<false>();
+        let expected_len: usize = (0..=15).chain([25]).map(reg_id_size).sum();
+        assert_eq!(data.len(), expected_len);
+        gdbstub::arch::validate_reg_ids::<crate::arm::ArmV7em>((0..=15).chain([25])).unwrap();
+    }
+
+    #[test]
+    fn cortex_m_fpu() {
+        let data = round_trip::<true>();
+        let expected_len: usize = (0..=15).chain(25..=42).map(reg_id_size).sum();
+        assert_eq!(data.len(), expected_len);
+        gdbstub::arch::validate_reg_ids::<crate::arm::ArmV7emf>((0..=15).chain(25..=42)).unwrap();
+
+        // d1 is made up of s2 (low word) and s3 (high word)
+        let d1 = &data[17 * 4 + 8..][..8];
+        assert_eq!(d1, [102, 0, 0, 0, 103, 0, 0, 0]);
+    }
+}
@@ -36,3 +36,42 @@ impl RegId for ArmCoreRegId {
         Some((reg, Some(NonZeroUsize::new(4)?)))
     }
 }
+
+/// ARM Cortex-M (M-profile) register identifier.
+///
+/// Floating point registers are only valid on targets with an FPU.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ArmCortexMRegId {
+    /// General purpose registers (R0-R12)
+    Gpr(u8),
+    /// Stack Pointer (R13)
+    Sp,
+    /// Link Register (R14)
+    Lr,
+    /// Program Counter (R15)
+    Pc,
+    /// Combined Program Status Register (xpsr)
+    Xpsr,
+    /// Double precision floating point registers (D0-D15), each aliasing a
+    /// pair of single precision registers (`S<2n>` and `S<2n+1>`)
+    Dpr(u8),
+    /// Floating Point Status and Control Register
+    Fpscr,
+}
+
+impl RegId for ArmCortexMRegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        let (reg, size) = match id {
+            0..=12 => (Self::Gpr(id as u8), 4),
+            13 => (Self::Sp, 4),
+            14 => (Self::Lr, 4),
+            15 => (Self::Pc, 4),
+            25 => (Self::Xpsr, 4),
+            26..=41 => (Self::Dpr((id as u8) - 26), 8),
+            42 => (Self::Fpscr, 4),
+            _ => return None,
+        };
+        Some((reg, Some(NonZeroUsize::new(size)?)))
+    }
+}
@@ -4,5 +4,7 @@
 pub mod id;
 
 mod arm_core;
+mod cortex_m;
 
 pub use arm_core::ArmCoreRegs;
+pub use cortex_m::ArmCortexMRegs;
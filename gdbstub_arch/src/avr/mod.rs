@@ -0,0 +1,42 @@
+//! Implementations for the 8-bit [AVR](https://en.wikipedia.org/wiki/AVR_microcontrollers)
+//! family of MCUs.
+//!
+//! # Address spaces
+//!
+//! AVR is a Harvard architecture, with separate flash (program) and SRAM
+//! (data) address spaces. GDB maps both into a single flat address space,
+//! using the high address bits to distinguish between them:
+//!
+//! | Address range             | Memory            |
+//! | ------------------------- | ----------------- |
+//! | `0x000000` - `0x7fffff`   | Flash             |
+//! | `0x800000` - `0x80ffff`   | SRAM (and I/O)    |
+//! | `0x810000` - `0x81ffff`   | EEPROM            |
+//!
+//! Targets are responsible for decoding these ranges in their
+//! `read_addrs`/`write_addrs` implementations.
+//!
+//! # Program Counter
+//!
+//! The AVR program counter addresses 16-bit _words_ of flash, whereas GDB
+//! expects the `PC` register to hold a _byte_ address. As such,
+//! [`AvrCoreRegs::pc`](reg::AvrCoreRegs::pc) must be set to twice the value of
+//! the hardware PC (and halved when written back).
+
+use gdbstub::arch::Arch;
+
+pub mod reg;
+
+/// Implements `Arch` for 8-bit AVR MCUs.
+pub enum Avr {}
+
+impl Arch for Avr {
+    type Usize = u32;
+    type Registers = reg::AvrCoreRegs;
+    type RegId = reg::id::AvrRegId;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(r#"<target version="1.0"><architecture>avr</architecture></target>"#)
+    }
+}
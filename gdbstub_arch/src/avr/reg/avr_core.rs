@@ -0,0 +1,88 @@
+use gdbstub::arch::Registers;
+
+/// AVR core registers.
+///
+/// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/avr-tdep.c>
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct AvrCoreRegs {
+    /// General purpose registers (R0-R31)
+    pub r: [u8; 32],
+    /// Status Register
+    pub sreg: u8,
+    /// Stack Pointer
+    pub sp: u16,
+    /// Program Counter, as a _byte_ address (i.e: twice the hardware PC, which
+    /// addresses 16-bit words)
+    pub pc: u32,
+}
+
+impl Registers for AvrCoreRegs {
+    type ProgramCounter = u32;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_bytes {
+            ($bytes:expr) => {
+                for b in $bytes {
+                    write_byte(Some(*b))
+                }
+            };
+        }
+
+        write_bytes!(&self.r);
+        write_bytes!(&[self.sreg]);
+        write_bytes!(&self.sp.to_le_bytes());
+        write_bytes!(&self.pc.to_le_bytes());
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        use core::convert::TryInto;
+
+        if bytes.len() != 32 + 1 + 2 + 4 {
+            return Err(());
+        }
+
+        let (r, bytes) = bytes.split_at(32);
+        let (sreg, bytes) = bytes.split_at(1);
+        let (sp, pc) = bytes.split_at(2);
+
+        self.r.copy_from_slice(r);
+        self.sreg = sreg[0];
+        self.sp = u16::from_le_bytes(sp.try_into().unwrap());
+        self.pc = u32::from_le_bytes(pc.try_into().unwrap());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avr_core_round_trip() {
+        let mut regs_before = AvrCoreRegs {
+            sreg: 0x82,
+            sp: 0x08ff,
+            pc: 0x1234 * 2,
+            ..Default::default()
+        };
+        for (i, reg) in regs_before.r.iter_mut().enumerate() {
+            *reg = i as u8;
+        }
+
+        let mut data = vec![];
+        regs_before.gdb_serialize(|x| data.push(x.unwrap()));
+        assert_eq!(data.len(), 39);
+        assert_eq!(&data[32..], [0x82, 0xff, 0x08, 0x68, 0x24, 0, 0]);
+
+        let mut regs_after = AvrCoreRegs::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+        assert_eq!(regs_before, regs_after);
+
+        assert!(regs_after.gdb_deserialize(&data[..38]).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use core::num::NonZeroUsize;
+use gdbstub::arch::RegId;
+
+/// AVR register identifier.
+///
+/// GDB does not provide a XML file for the AVR.
+/// The best file to reference is [avr-tdep.c](https://github.com/bminor/binutils-gdb/blob/master/gdb/avr-tdep.c).
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum AvrRegId {
+    /// General Purpose Registers (R0-R31)
+    Gpr(u8),
+    /// Status Register
+    Sreg,
+    /// Stack Pointer
+    Sp,
+    /// Program Counter (as a byte address)
+    Pc,
+}
+
+impl RegId for AvrRegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        let (reg, size) = match id {
+            0..=31 => (Self::Gpr(id as u8), 1),
+            32 => (Self::Sreg, 1),
+            33 => (Self::Sp, 2),
+            34 => (Self::Pc, 4),
+            _ => return None,
+        };
+        Some((reg, Some(NonZeroUsize::new(size)?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AvrRegId;
+    use crate::avr::reg::AvrCoreRegs;
+    use gdbstub::arch::RegId;
+    use gdbstub::arch::Registers;
+
+    #[test]
+    fn test_avr() {
+        let mut serialized_data_len = 0;
+        AvrCoreRegs::default().gdb_serialize(|b| {
+            if b.is_some() {
+                serialized_data_len += 1;
+            }
+        });
+
+        let mut i = 0;
+        let mut sum_reg_sizes = 0;
+        while let Some((_, size)) = AvrRegId::from_raw_id(i) {
+            sum_reg_sizes += size.unwrap().get();
+            i += 1;
+        }
+
+        assert_eq!(serialized_data_len, sum_reg_sizes);
+        gdbstub::arch::validate_reg_ids::<crate::avr::Avr>(0..).unwrap();
+    }
+}
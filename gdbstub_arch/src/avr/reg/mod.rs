@@ -0,0 +1,8 @@
+//! `Register` structs for AVR MCUs.
+
+/// `RegId` definitions for AVR MCUs.
+pub mod id;
+
+mod avr_core;
+
+pub use avr_core::AvrCoreRegs;
@@ -55,6 +55,7 @@
 
 pub mod aarch64;
 pub mod arm;
+pub mod avr;
 pub mod mips;
 pub mod msp430;
 pub mod ppc;
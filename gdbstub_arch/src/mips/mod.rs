@@ -1,4 +1,17 @@
 //! Implementations for the MIPS architecture.
+//!
+//! # Branch delay slots
+//!
+//! The instruction following a MIPS branch (its "delay slot") is executed
+//! before the branch takes effect. If a target stops while the delay slot
+//! instruction is pending (e.g: due to an exception, signalled by the `BD` bit
+//! of the `Cause` register), the reported `pc` should be the address of the
+//! _branch_ instruction (i.e: the value of `EPC`), so that resuming execution
+//! re-executes the branch along with its delay slot.
+//!
+//! Similarly, targets implementing single-stepping should treat a branch and
+//! its delay slot as a single step, as GDB never expects to stop "between"
+//! the two.
 
 use gdbstub::arch::Arch;
 
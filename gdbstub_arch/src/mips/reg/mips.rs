@@ -267,3 +267,62 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn val<U: From<u8>>(v: u8) -> U {
+        U::from(v)
+    }
+
+    fn round_trip<U>(expected_len: usize)
+    where
+        U: PrimInt + LeBytes + Default + core::fmt::Debug + From<u8>,
+    {
+        let mut regs_before = MipsCoreRegs::<U> {
+            lo: val(33),
+            hi: val(34),
+            pc: val(37),
+            cp0: MipsCp0Regs {
+                status: val(32),
+                badvaddr: val(35),
+                cause: val(36),
+            },
+            ..Default::default()
+        };
+        for (i, reg) in regs_before.r.iter_mut().enumerate() {
+            *reg = val(i as u8);
+        }
+        for (i, reg) in regs_before.fpu.r.iter_mut().enumerate() {
+            *reg = val(38 + i as u8);
+        }
+        regs_before.fpu.fcsr = val(70);
+        regs_before.fpu.fir = val(71);
+
+        let mut data = vec![];
+        regs_before.gdb_serialize(|x| data.push(x.unwrap()));
+        assert_eq!(data.len(), expected_len);
+
+        // registers are serialized in regnum order
+        let ptrsize = core::mem::size_of::<U>();
+        for (regnum, reg) in data.chunks_exact(ptrsize).enumerate() {
+            assert_eq!(reg[0], regnum as u8);
+        }
+
+        let mut regs_after = MipsCoreRegs::<U>::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+        assert_eq!(regs_before, regs_after);
+    }
+
+    #[test]
+    fn mips32_core_round_trip() {
+        // 32 GPRs, sr, lo, hi, bad, cause, pc, 32 FPRs, fsr, fir
+        round_trip::<u32>(72 * 4)
+    }
+
+    #[test]
+    fn mips64_core_round_trip() {
+        round_trip::<u64>(72 * 8)
+    }
+}
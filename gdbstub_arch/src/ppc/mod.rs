@@ -1,4 +1,7 @@
 //! Implementations for various PowerPC architectures.
+//!
+//! *Note*: PowerPC is (typically) a big-endian architecture, so all registers
+//! are serialized in big-endian byte order.
 
 use gdbstub::arch::Arch;
 use gdbstub::arch::RegId;
@@ -26,3 +29,47 @@ impl<RegIdImpl: RegId> Arch for PowerPcAltivec32<RegIdImpl> {
         )
     }
 }
+
+/// Implements `Arch` for 32-bit big-endian PowerPC, with only the core
+/// (integer) registers.
+pub enum PowerPc32 {}
+
+/// Implements `Arch` for 64-bit big-endian PowerPC, with only the core
+/// (integer) registers.
+pub enum PowerPc64 {}
+
+impl Arch for PowerPc32 {
+    type Usize = u32;
+    type Registers = reg::PowerPcCoreRegs<u32>;
+    type RegId = reg::id::PowerPcCoreRegId<u32>;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        static DESCRIPTION_XML: &str = concat!(
+            r#"<target version="1.0">"#,
+            "<architecture>powerpc:common</architecture>",
+            include_str!("core32.xml"), // feature "org.gnu.gdb.power.core"
+            "</target>",
+        );
+
+        Some(DESCRIPTION_XML)
+    }
+}
+
+impl Arch for PowerPc64 {
+    type Usize = u64;
+    type Registers = reg::PowerPcCoreRegs<u64>;
+    type RegId = reg::id::PowerPcCoreRegId<u64>;
+    type BreakpointKind = usize;
+
+    fn target_description_xml() -> Option<&'static str> {
+        static DESCRIPTION_XML: &str = concat!(
+            r#"<target version="1.0">"#,
+            "<architecture>powerpc:common64</architecture>",
+            include_str!("core64.xml"), // feature "org.gnu.gdb.power.core"
+            "</target>",
+        );
+
+        Some(DESCRIPTION_XML)
+    }
+}
@@ -1,2 +1,56 @@
-// TODO: Add proper `RegId` implementation. See [issue #29](https://github.com/daniel5151/gdbstub/issues/29)
-// pub enum PowerPc32RegId {}
+use core::num::NonZeroUsize;
+use gdbstub::arch::RegId;
+
+/// PowerPC core register identifier.
+///
+/// The register width is set to `u32` or `u64` based on the `<U>` type (though
+/// `cr` and `xer` are always 32-bit).
+///
+/// Source: <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power-core.xml>
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum PowerPcCoreRegId<U> {
+    /// General purpose registers (r0-r31)
+    Gpr(u8),
+    /// Program counter
+    Pc,
+    /// Machine state
+    Msr,
+    /// Condition register
+    Cr,
+    /// Link register
+    Lr,
+    /// Count register
+    Ctr,
+    /// Integer exception register
+    Xer,
+    #[doc(hidden)]
+    _Size(core::marker::PhantomData<U>),
+}
+
+fn from_raw_id<U>(id: usize) -> Option<(PowerPcCoreRegId<U>, Option<NonZeroUsize>)> {
+    let ptrsize = core::mem::size_of::<U>();
+    let (reg, size) = match id {
+        0..=31 => (PowerPcCoreRegId::Gpr(id as u8), ptrsize),
+        64 => (PowerPcCoreRegId::Pc, ptrsize),
+        65 => (PowerPcCoreRegId::Msr, ptrsize),
+        66 => (PowerPcCoreRegId::Cr, 4),
+        67 => (PowerPcCoreRegId::Lr, ptrsize),
+        68 => (PowerPcCoreRegId::Ctr, ptrsize),
+        69 => (PowerPcCoreRegId::Xer, 4),
+        _ => return None,
+    };
+    Some((reg, Some(NonZeroUsize::new(size)?)))
+}
+
+impl RegId for PowerPcCoreRegId<u32> {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        from_raw_id::<u32>(id)
+    }
+}
+
+impl RegId for PowerPcCoreRegId<u64> {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        from_raw_id::<u64>(id)
+    }
+}
@@ -4,6 +4,8 @@
 pub mod id;
 
 mod common;
+mod ppc_core;
 
 pub use common::PowerPcCommonRegs;
+pub use ppc_core::PowerPcCoreRegs;
 type PpcVector = u128;
@@ -0,0 +1,161 @@
+use gdbstub::arch::Registers;
+use gdbstub::internal::BeBytes;
+use num_traits::PrimInt;
+
+/// PowerPC core registers.
+///
+/// The register width is set to `u32` or `u64` based on the `<U>` type (though
+/// `cr` and `xer` are always 32-bit). All registers are serialized in
+/// big-endian byte order.
+///
+/// Sources:
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power-core.xml>
+/// * <https://github.com/bminor/binutils-gdb/blob/master/gdb/features/rs6000/power64-core.xml>
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct PowerPcCoreRegs<U> {
+    /// General purpose registers
+    pub r: [U; 32],
+    /// Program counter
+    pub pc: U,
+    /// Machine state
+    pub msr: U,
+    /// Condition register
+    pub cr: u32,
+    /// Link register
+    pub lr: U,
+    /// Count register
+    pub ctr: U,
+    /// Integer exception register
+    pub xer: u32,
+}
+
+impl<U> Registers for PowerPcCoreRegs<U>
+where
+    U: PrimInt + BeBytes + Default + core::fmt::Debug,
+{
+    type ProgramCounter = U;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        macro_rules! write_be_bytes {
+            ($value:expr) => {
+                let mut buf = [0; 16];
+                // infallible (unless digit is a >128 bit number)
+                let len = BeBytes::to_be_bytes(*$value, &mut buf).unwrap();
+                let buf = &buf[..len];
+                for b in buf {
+                    write_byte(Some(*b));
+                }
+            };
+        }
+
+        for reg in self.r.iter() {
+            write_be_bytes!(reg);
+        }
+
+        // pc is regnum 64 (regnums 32-63 belong to the FPU)
+        write_be_bytes!(&self.pc);
+        write_be_bytes!(&self.msr);
+        write_be_bytes!(&self.cr);
+        write_be_bytes!(&self.lr);
+        write_be_bytes!(&self.ctr);
+        write_be_bytes!(&self.xer);
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        let ptrsize = core::mem::size_of::<U>();
+
+        if bytes.len() != ptrsize * 36 + 4 * 2 {
+            return Err(());
+        }
+
+        let mut bytes = bytes;
+        let mut next = |size: usize| {
+            let (next, rest) = bytes.split_at(size);
+            bytes = rest;
+            next
+        };
+
+        for reg in self.r.iter_mut() {
+            *reg = U::from_be_bytes(next(ptrsize)).ok_or(())?;
+        }
+        self.pc = U::from_be_bytes(next(ptrsize)).ok_or(())?;
+        self.msr = U::from_be_bytes(next(ptrsize)).ok_or(())?;
+        self.cr = BeBytes::from_be_bytes(next(4)).ok_or(())?;
+        self.lr = U::from_be_bytes(next(ptrsize)).ok_or(())?;
+        self.ctr = U::from_be_bytes(next(ptrsize)).ok_or(())?;
+        self.xer = BeBytes::from_be_bytes(next(4)).ok_or(())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ppc::reg::id::PowerPcCoreRegId;
+    use gdbstub::arch::RegId;
+
+    fn round_trip<U, RId>(regs_before: PowerPcCoreRegs<U>) -> Vec<u8>
+    where
+        U: PrimInt + BeBytes + Default + core::fmt::Debug,
+        RId: RegId,
+    {
+        let mut data = vec![];
+        regs_before.gdb_serialize(|x| data.push(x.unwrap()));
+
+        let expected_len: usize = (0..=31)
+            .chain(64..=69)
+            .map(|id| RId::from_raw_id(id).unwrap().1.unwrap().get())
+            .sum();
+        assert_eq!(data.len(), expected_len);
+
+        let mut regs_after = PowerPcCoreRegs::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+        assert_eq!(regs_before, regs_after);
+
+        data
+    }
+
+    #[test]
+    fn ppc_core_reg_ids() {
+        use gdbstub::arch::validate_reg_ids;
+
+        validate_reg_ids::<crate::ppc::PowerPc32>((0..=31).chain(64..=69)).unwrap();
+        validate_reg_ids::<crate::ppc::PowerPc64>((0..=31).chain(64..=69)).unwrap();
+    }
+
+    #[test]
+    fn ppc32_core_big_endian() {
+        let regs = PowerPcCoreRegs::<u32> {
+            pc: 0x0102_0304,
+            cr: 0x0a0b_0c0d,
+            xer: 0x2000_0000,
+            ..Default::default()
+        };
+        let data = round_trip::<_, PowerPcCoreRegId<u32>>(regs);
+        assert_eq!(&data[32 * 4..33 * 4], [1, 2, 3, 4]);
+        assert_eq!(&data[34 * 4..35 * 4], [0xa, 0xb, 0xc, 0xd]);
+        assert_eq!(&data[37 * 4..], [0x20, 0, 0, 0]);
+    }
+
+    #[test]
+    fn ppc64_core_big_endian() {
+        let regs = PowerPcCoreRegs::<u64> {
+            pc: 0x0102_0304_0506_0708,
+            cr: 0x0a0b_0c0d,
+            lr: 0x1122_3344_5566_7788,
+            ..Default::default()
+        };
+        let data = round_trip::<_, PowerPcCoreRegId<u64>>(regs);
+        assert_eq!(&data[32 * 8..33 * 8], [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(&data[34 * 8..34 * 8 + 4], [0xa, 0xb, 0xc, 0xd]);
+        assert_eq!(
+            &data[34 * 8 + 4..35 * 8 + 4],
+            [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88]
+        );
+    }
+}
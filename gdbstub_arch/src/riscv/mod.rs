@@ -1,6 +1,8 @@
 //! Implementations for the [RISC-V](https://riscv.org/) architecture.
 //!
-//! *Note*: currently only supports integer versions of the ISA.
+//! *Note*: currently only supports integer versions of the ISA, optionally
+//! along with a handful of commonly used machine-mode CSRs (see
+//! [`Riscv32Csr`] / [`Riscv64Csr`]).
 
 use gdbstub::arch::Arch;
 
@@ -12,6 +14,14 @@ pub enum Riscv32 {}
 /// Implements `Arch` for 64-bit RISC-V.
 pub enum Riscv64 {}
 
+/// Implements `Arch` for 32-bit RISC-V, including the machine-mode CSRs in
+/// [`reg::RiscvCsrRegs`].
+pub enum Riscv32Csr {}
+
+/// Implements `Arch` for 64-bit RISC-V, including the machine-mode CSRs in
+/// [`reg::RiscvCsrRegs`].
+pub enum Riscv64Csr {}
+
 impl Arch for Riscv32 {
     type Usize = u32;
     type Registers = reg::RiscvCoreRegs<u32>;
@@ -33,3 +43,25 @@ impl Arch for Riscv64 {
         Some(include_str!("rv64i.xml"))
     }
 }
+
+impl Arch for Riscv32Csr {
+    type Usize = u32;
+    type Registers = reg::RiscvCoreCsrRegs<u32>;
+    type BreakpointKind = usize;
+    type RegId = reg::id::RiscvRegId<u32>;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(include_str!("rv32csr.xml"))
+    }
+}
+
+impl Arch for Riscv64Csr {
+    type Usize = u64;
+    type Registers = reg::RiscvCoreCsrRegs<u64>;
+    type BreakpointKind = usize;
+    type RegId = reg::id::RiscvRegId<u64>;
+
+    fn target_description_xml() -> Option<&'static str> {
+        Some(include_str!("rv64csr.xml"))
+    }
+}
@@ -11,7 +11,8 @@ pub enum RiscvRegId<U> {
     Fpr(u8),
     /// Program Counter.
     Pc,
-    /// Control and Status Register.
+    /// Control and Status Register, identified by its CSR number (i.e: GDB
+    /// register `65 + n`).
     Csr(u16),
     /// Privilege level.
     Priv,
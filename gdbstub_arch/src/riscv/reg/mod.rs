@@ -5,4 +5,6 @@ pub mod id;
 
 mod riscv;
 
+pub use riscv::RiscvCoreCsrRegs;
 pub use riscv::RiscvCoreRegs;
+pub use riscv::RiscvCsrRegs;
@@ -17,6 +17,75 @@ pub struct RiscvCoreRegs<U> {
     pub pc: U,
 }
 
+/// RISC-V Integer registers, followed by a handful of machine-mode control
+/// and status registers.
+///
+/// Used by the [`Riscv32Csr`](crate::riscv::Riscv32Csr) and
+/// [`Riscv64Csr`](crate::riscv::Riscv64Csr) architectures.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RiscvCoreCsrRegs<U> {
+    /// Integer registers
+    pub core: RiscvCoreRegs<U>,
+    /// Machine-mode control and status registers
+    pub csr: RiscvCsrRegs<U>,
+}
+
+/// RISC-V machine-mode control and status registers.
+///
+/// These are the CSRs advertised in the `Riscv32Csr` / `Riscv64Csr` target
+/// description XML, listed in the order they are (de)serialized.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RiscvCsrRegs<U> {
+    /// Machine status register (CSR `0x300`)
+    pub mstatus: U,
+    /// Machine ISA register (CSR `0x301`)
+    pub misa: U,
+    /// Machine interrupt-enable register (CSR `0x304`)
+    pub mie: U,
+    /// Machine trap-handler base address (CSR `0x305`)
+    pub mtvec: U,
+    /// Machine scratch register (CSR `0x340`)
+    pub mscratch: U,
+    /// Machine exception program counter (CSR `0x341`)
+    pub mepc: U,
+    /// Machine trap cause (CSR `0x342`)
+    pub mcause: U,
+    /// Machine bad address or instruction (CSR `0x343`)
+    pub mtval: U,
+    /// Machine interrupt pending (CSR `0x344`)
+    pub mip: U,
+}
+
+impl<U: Copy> RiscvCsrRegs<U> {
+    fn to_array(&self) -> [U; 9] {
+        [
+            self.mstatus,
+            self.misa,
+            self.mie,
+            self.mtvec,
+            self.mscratch,
+            self.mepc,
+            self.mcause,
+            self.mtval,
+            self.mip,
+        ]
+    }
+
+    fn regs_mut(&mut self) -> [&mut U; 9] {
+        [
+            &mut self.mstatus,
+            &mut self.misa,
+            &mut self.mie,
+            &mut self.mtvec,
+            &mut self.mscratch,
+            &mut self.mepc,
+            &mut self.mcause,
+            &mut self.mtval,
+            &mut self.mip,
+        ]
+    }
+}
+
 impl<U> Registers for RiscvCoreRegs<U>
 where
     U: PrimInt + LeBytes + Default + core::fmt::Debug,
@@ -74,3 +143,134 @@ where
         Ok(())
     }
 }
+
+impl<U> Registers for RiscvCoreCsrRegs<U>
+where
+    U: PrimInt + LeBytes + Default + core::fmt::Debug,
+{
+    type ProgramCounter = U;
+
+    fn pc(&self) -> Self::ProgramCounter {
+        self.core.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        self.core.gdb_serialize(&mut write_byte);
+
+        // CSRs (regnum 65 + csr number)
+        for reg in self.csr.to_array().iter() {
+            let mut buf = [0; 16];
+            // infallible (unless digit is a >128 bit number)
+            let len = reg.to_le_bytes(&mut buf).unwrap();
+            for b in &buf[..len] {
+                write_byte(Some(*b));
+            }
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        let ptrsize = core::mem::size_of::<U>();
+
+        // GPRs + PC
+        let split = (33 * ptrsize).min(bytes.len());
+        let (core, csr) = bytes.split_at(split);
+        self.core.gdb_deserialize(core)?;
+
+        // ensure csr.chunks_exact(ptrsize) won't panic
+        if csr.len() % ptrsize != 0 {
+            return Err(());
+        }
+
+        let mut regs = csr
+            .chunks_exact(ptrsize)
+            .map(|c| U::from_le_bytes(c).unwrap());
+
+        for reg in self.csr.regs_mut() {
+            *reg = regs.next().ok_or(())?
+        }
+
+        if regs.next().is_some() {
+            return Err(());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample<U: From<u8>>() -> RiscvCoreCsrRegs<U> {
+        RiscvCoreCsrRegs {
+            core: RiscvCoreRegs {
+                x: core::array::from_fn(|i| U::from(i as u8)),
+                pc: U::from(32),
+            },
+            csr: RiscvCsrRegs {
+                mstatus: U::from(33),
+                misa: U::from(34),
+                mie: U::from(35),
+                mtvec: U::from(36),
+                mscratch: U::from(37),
+                mepc: U::from(38),
+                mcause: U::from(39),
+                mtval: U::from(40),
+                mip: U::from(41),
+            },
+        }
+    }
+
+    fn round_trip<R: Registers + Default + PartialEq + core::fmt::Debug>(
+        regs_before: R,
+        len: usize,
+    ) {
+        let mut data = vec![];
+        regs_before.gdb_serialize(|x| data.push(x.unwrap()));
+        assert_eq!(data.len(), len);
+
+        let mut regs_after = R::default();
+        regs_after.gdb_deserialize(&data).unwrap();
+        assert_eq!(regs_before, regs_after);
+
+        assert!(regs_after.gdb_deserialize(&data[..len - 1]).is_err());
+    }
+
+    fn round_trip_xlen<U>(xlen: usize)
+    where
+        U: PrimInt + LeBytes + Default + core::fmt::Debug + From<u8>,
+    {
+        let regs = sample::<U>();
+
+        // 32 GPRs + PC
+        round_trip(regs.core.clone(), 33 * xlen);
+        // 32 GPRs + PC + 9 CSRs
+        round_trip(regs, 42 * xlen);
+    }
+
+    #[test]
+    fn riscv_reg_ids() {
+        use gdbstub::arch::validate_reg_ids;
+
+        validate_reg_ids::<crate::riscv::Riscv32>(0..=32).unwrap();
+        validate_reg_ids::<crate::riscv::Riscv64>(0..=32).unwrap();
+
+        // the CSRs are numbered `65 + <csr number>`
+        let csrs = [
+            0x300, 0x301, 0x304, 0x305, 0x340, 0x341, 0x342, 0x343, 0x344,
+        ];
+        let ids = || (0..=32).chain(csrs.iter().map(|csr| 65 + csr));
+        validate_reg_ids::<crate::riscv::Riscv32Csr>(ids()).unwrap();
+        validate_reg_ids::<crate::riscv::Riscv64Csr>(ids()).unwrap();
+    }
+
+    #[test]
+    fn riscv32_round_trip() {
+        round_trip_xlen::<u32>(4)
+    }
+
+    #[test]
+    fn riscv64_round_trip() {
+        round_trip_xlen::<u64>(8)
+    }
+}
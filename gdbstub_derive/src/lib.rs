@@ -0,0 +1,218 @@
+//! Derive macros for [`gdbstub`](https://docs.rs/gdbstub).
+//!
+//! # `#[derive(Registers)]`
+//!
+//! Generates an implementation of `gdbstub::arch::Registers` for a struct,
+//! (de)serializing each field in declaration order, which must match the
+//! register order of GDB's `g` packet (i.e: ascending register number).
+//!
+//! Fields must be integers, or arrays of integers. Each field (or array
+//! element) is serialized as a single register, using the following
+//! (optional) `#[reg(...)]` attributes:
+//!
+//! - `pc`: marks the program counter. Exactly one field must be marked.
+//! - `bytes = N`: the register's size in the `g` packet. Defaults to the size
+//!   of the field's type. Values are zero-extended (or truncated) to fit.
+//! - `endian = "little" | "big"`: the register's byte order. Defaults to
+//!   `"little"`. Placing this attribute on the struct itself changes the
+//!   default for all of its fields.
+//!
+//! ```
+//! use gdbstub::arch::Registers;
+//! use gdbstub_derive::Registers;
+//!
+//! #[derive(Debug, Default, Clone, PartialEq, Registers)]
+//! #[reg(endian = "big")]
+//! struct MyRegs {
+//!     r: [u32; 2],
+//!     #[reg(pc)]
+//!     pc: u32,
+//!     #[reg(bytes = 4, endian = "little")]
+//!     flags: u8,
+//! }
+//!
+//! let regs = MyRegs { r: [1, 2], pc: 3, flags: 4 };
+//! let mut data = Vec::new();
+//! regs.gdb_serialize(|b| data.push(b.unwrap()));
+//! assert_eq!(data, [0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3, 4, 0, 0, 0]);
+//! ```
+
+#![deny(missing_docs)]
+
+use proc_macro2::Span;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse_macro_input;
+use syn::spanned::Spanned;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Error;
+use syn::Fields;
+use syn::Lit;
+use syn::Meta;
+use syn::NestedMeta;
+use syn::Type;
+
+/// Derive `gdbstub::arch::Registers`. See the [crate-level docs](crate) for
+/// details.
+#[proc_macro_derive(Registers, attributes(reg))]
+pub fn derive_registers(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Options parsed from a `#[reg(...)]` attribute.
+#[derive(Default)]
+struct RegAttrs {
+    pc: bool,
+    bytes: Option<usize>,
+    big_endian: Option<bool>,
+}
+
+fn parse_attrs(attrs: &[syn::Attribute]) -> syn::Result<RegAttrs> {
+    let mut res = RegAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("reg")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new(meta.span(), "expected `#[reg(...)]`")),
+        };
+
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("pc") => res.pc = true,
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("bytes") => {
+                    match &nv.lit {
+                        Lit::Int(n) => res.bytes = Some(n.base10_parse()?),
+                        lit => return Err(Error::new(lit.span(), "expected an integer")),
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("endian") => {
+                    let big_endian = match &nv.lit {
+                        Lit::Str(s) if s.value() == "little" => false,
+                        Lit::Str(s) if s.value() == "big" => true,
+                        lit => {
+                            return Err(Error::new(lit.span(), r#"expected `"little"` or `"big"`"#))
+                        }
+                    };
+                    res.big_endian = Some(big_endian);
+                }
+                nested => return Err(Error::new(nested.span(), "unknown `reg` attribute")),
+            }
+        }
+    }
+    Ok(res)
+}
+
+fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let struct_attrs = parse_attrs(&input.attrs)?;
+    if struct_attrs.pc || struct_attrs.bytes.is_some() {
+        return Err(Error::new(
+            Span::call_site(),
+            "only `endian` may be specified on the struct itself",
+        ));
+    }
+    let default_big_endian = struct_attrs.big_endian.unwrap_or(false);
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "`Registers` can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`Registers` can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut pc = None;
+    let mut serialize = Vec::new();
+    let mut deserialize = Vec::new();
+    for field in fields {
+        let attrs = parse_attrs(&field.attrs)?;
+        let ident = field.ident.as_ref().unwrap();
+        let big_endian = attrs.big_endian.unwrap_or(default_big_endian);
+
+        let (elem, is_array) = match &field.ty {
+            Type::Array(array) => (&*array.elem, true),
+            ty => (ty, false),
+        };
+
+        let width = match attrs.bytes {
+            Some(bytes) => quote!(#bytes),
+            None => quote!(::core::mem::size_of::<#elem>()),
+        };
+
+        if attrs.pc {
+            if is_array {
+                return Err(Error::new(field.span(), "the `pc` cannot be an array"));
+            }
+            if pc.is_some() {
+                return Err(Error::new(field.span(), "multiple `pc` fields"));
+            }
+            pc = Some((ident, elem));
+        }
+
+        if is_array {
+            serialize.push(quote! {
+                for reg in self.#ident.iter() {
+                    ::gdbstub::internal::derive::write_reg(*reg, #width, #big_endian, &mut write_byte);
+                }
+            });
+            deserialize.push(quote! {
+                for reg in self.#ident.iter_mut() {
+                    *reg = ::gdbstub::internal::derive::read_reg(&mut bytes, #width, #big_endian)?;
+                }
+            });
+        } else {
+            serialize.push(quote! {
+                ::gdbstub::internal::derive::write_reg(self.#ident, #width, #big_endian, &mut write_byte);
+            });
+            deserialize.push(quote! {
+                self.#ident = ::gdbstub::internal::derive::read_reg(&mut bytes, #width, #big_endian)?;
+            });
+        }
+    }
+
+    let (pc, pc_ty) = pc.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "missing a `#[reg(pc)]` field for the program counter",
+        )
+    })?;
+
+    Ok(quote! {
+        impl #impl_generics ::gdbstub::arch::Registers for #name #ty_generics #where_clause {
+            type ProgramCounter = #pc_ty;
+
+            fn pc(&self) -> Self::ProgramCounter {
+                self.#pc
+            }
+
+            fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+                #(#serialize)*
+            }
+
+            fn gdb_deserialize(&mut self, mut bytes: &[u8]) -> Result<(), ()> {
+                #(#deserialize)*
+
+                if !bytes.is_empty() {
+                    return Err(());
+                }
+
+                Ok(())
+            }
+        }
+    })
+}
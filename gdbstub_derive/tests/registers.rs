@@ -0,0 +1,125 @@
+//! Tests for `#[derive(Registers)]`.
+
+use core::num::NonZeroUsize;
+use gdbstub::arch::Arch;
+use gdbstub::arch::RegId;
+use gdbstub::arch::Registers;
+use gdbstub_derive::Registers;
+
+#[derive(Debug, Default, Clone, PartialEq, Registers)]
+struct CoreRegs {
+    r: [u32; 4],
+    #[reg(pc)]
+    pc: u32,
+    #[reg(bytes = 4)]
+    flags: u8,
+    #[reg(endian = "big")]
+    be: u16,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Registers)]
+#[reg(endian = "big")]
+struct GenericRegs<U>
+where
+    U: Copy + Default + core::fmt::Debug + PartialEq,
+    U: gdbstub::internal::LeBytes + gdbstub::internal::BeBytes,
+{
+    r: [U; 2],
+    #[reg(pc)]
+    pc: U,
+    #[reg(endian = "little")]
+    le: U,
+}
+
+fn serialize(regs: &impl Registers) -> Vec<u8> {
+    let mut data = Vec::new();
+    regs.gdb_serialize(|b| data.push(b.unwrap()));
+    data
+}
+
+#[test]
+fn derived_layout() {
+    let regs = CoreRegs {
+        r: [1, 2, 3, 4],
+        pc: 0x1234_5678,
+        flags: 0xaa,
+        be: 0x0102,
+    };
+    assert_eq!(regs.pc(), 0x1234_5678);
+
+    let data = serialize(&regs);
+    assert_eq!(
+        data,
+        [
+            1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0, // r
+            0x78, 0x56, 0x34, 0x12, // pc
+            0xaa, 0, 0, 0, // flags
+            1, 2, // be
+        ]
+    );
+
+    let mut regs_after = CoreRegs::default();
+    regs_after.gdb_deserialize(&data).unwrap();
+    assert_eq!(regs, regs_after);
+
+    // too short, too long, and an out-of-range `flags`
+    assert!(regs_after.gdb_deserialize(&data[..data.len() - 1]).is_err());
+    assert!(regs_after
+        .gdb_deserialize(&[data.clone(), vec![0]].concat())
+        .is_err());
+    let mut bad_flags = data;
+    bad_flags[21] = 1;
+    assert!(regs_after.gdb_deserialize(&bad_flags).is_err());
+}
+
+#[test]
+fn derived_generic_layout() {
+    let regs = GenericRegs::<u16> {
+        r: [0x0102, 0x0304],
+        pc: 0x0506,
+        le: 0x0708,
+    };
+    let data = serialize(&regs);
+    assert_eq!(data, [1, 2, 3, 4, 5, 6, 8, 7]);
+
+    let mut regs_after = GenericRegs::default();
+    regs_after.gdb_deserialize(&data).unwrap();
+    assert_eq!(regs, regs_after);
+
+    assert_eq!(serialize(&GenericRegs::<u64>::default()).len(), 4 * 8);
+}
+
+#[derive(Debug)]
+enum CoreRegId {
+    Gpr(#[allow(dead_code)] usize),
+    Pc,
+    Flags,
+    Be,
+}
+
+impl RegId for CoreRegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        let (reg, size) = match id {
+            0..=3 => (CoreRegId::Gpr(id), 4),
+            4 => (CoreRegId::Pc, 4),
+            5 => (CoreRegId::Flags, 4),
+            6 => (CoreRegId::Be, 2),
+            _ => return None,
+        };
+        Some((reg, NonZeroUsize::new(size)))
+    }
+}
+
+enum CoreArch {}
+
+impl Arch for CoreArch {
+    type Usize = u32;
+    type Registers = CoreRegs;
+    type RegId = CoreRegId;
+    type BreakpointKind = usize;
+}
+
+#[test]
+fn derived_layout_matches_reg_ids() {
+    gdbstub::arch::validate_reg_ids::<CoreArch>(0..).unwrap();
+}
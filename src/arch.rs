@@ -57,6 +57,10 @@ impl RegId for () {
 ///
 /// e.g: for ARM:
 /// github.com/bminor/binutils-gdb/blob/master/gdb/features/arm/arm-core.xml
+///
+/// Register files made up of integer fields can use the `#[derive(Registers)]`
+/// macro from the [`gdbstub_derive`](https://docs.rs/gdbstub_derive) crate,
+/// instead of hand-writing these methods.
 // TODO: add way to de/serialize arbitrary "missing"/"uncollected" registers.
 pub trait Registers: Default + Debug + Clone + PartialEq {
     /// The type of the architecture's program counter / instruction pointer.
@@ -156,6 +160,11 @@ pub trait Arch {
     ///
     /// See the [GDB docs](https://sourceware.org/gdb/current/onlinedocs/gdb/Target-Description-Format.html)
     /// for details on the target description XML format.
+    ///
+    /// Registers can be assigned to register groups (enabling commands such as
+    /// `info registers float`) using the `<reg>` element's `group` attribute.
+    /// Targets that generate their `<reg>` elements at runtime can use
+    /// [`target_xml::Reg`] to do so.
     #[inline(always)]
     fn target_description_xml() -> Option<&'static str> {
         None
@@ -191,6 +200,228 @@ pub trait Arch {
     }
 }
 
+/// An inconsistency between an [`Arch`]'s [`RegId`] mapping and its
+/// [`Registers`] serialization, as reported by [`validate_reg_ids`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RegIdLayoutError {
+    /// `RegId::from_raw_id` did not recognize the raw register `id`, which
+    /// would start at byte `offset` of the serialized register block.
+    UnknownRegId {
+        /// The raw GDB register number.
+        id: usize,
+        /// Byte offset at which the register would start.
+        offset: usize,
+    },
+    /// `RegId::from_raw_id` did not report a size for the raw register `id`.
+    UnsizedRegId {
+        /// The raw GDB register number.
+        id: usize,
+    },
+    /// The raw register `id` extends past the end of the serialized register
+    /// block.
+    OutOfBounds {
+        /// The raw GDB register number.
+        id: usize,
+        /// Byte offset at which the register starts.
+        offset: usize,
+        /// The register's size, in bytes.
+        size: usize,
+        /// The length of the serialized register block.
+        len: usize,
+    },
+    /// The provided register ids only cover the first `covered` bytes of the
+    /// serialized register block.
+    Incomplete {
+        /// Number of bytes covered by the provided register ids.
+        covered: usize,
+        /// The length of the serialized register block.
+        len: usize,
+    },
+}
+
+impl core::fmt::Display for RegIdLayoutError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use self::RegIdLayoutError::*;
+        match *self {
+            UnknownRegId { id, offset } => {
+                write!(f, "no RegId for register {} (at offset {})", id, offset)
+            }
+            UnsizedRegId { id } => write!(f, "RegId for register {} has no size", id),
+            OutOfBounds {
+                id,
+                offset,
+                size,
+                len,
+            } => write!(
+                f,
+                "register {} ({} bytes at offset {}) extends past the end of the {} byte register block",
+                id, size, offset, len
+            ),
+            Incomplete { covered, len } => write!(
+                f,
+                "register ids only cover {} of the {} byte register block",
+                covered, len
+            ),
+        }
+    }
+}
+
+/// Check that `A::RegId` is consistent with the layout of `A::Registers`.
+///
+/// This is a helper for use in tests, which catches `RegId` mappings that have
+/// drifted out of sync with the corresponding `gdb_serialize` implementation
+/// (resulting in `p`/`P` packets accessing the wrong register).
+///
+/// `ids` should list the raw GDB register numbers included in the `g` packet,
+/// in ascending order. The size of each register (as reported by
+/// [`RegId::from_raw_id`]) is used to compute its byte offset within the
+/// serialized register block, and the resulting layout must tile the _entire_
+/// block, without exceeding it. Iteration stops as soon as the block is fully
+/// covered, so `0..` can be passed for architectures without any gaps in their
+/// register numbering.
+///
+/// ```
+/// # use gdbstub::arch::{Arch, RegId, Registers};
+/// # use core::num::NonZeroUsize;
+/// #[derive(Debug, Default, Clone, PartialEq)]
+/// struct Regs { r: [u32; 2], pc: u32 }
+///
+/// #[derive(Debug)]
+/// enum Reg { R(u8), Pc }
+///
+/// impl RegId for Reg {
+///     fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+///         let reg = match id {
+///             0..=1 => Reg::R(id as u8),
+///             2 => Reg::Pc,
+///             _ => return None,
+///         };
+///         Some((reg, NonZeroUsize::new(4)))
+///     }
+/// }
+///
+/// # impl Registers for Regs {
+/// #     type ProgramCounter = u32;
+/// #     fn pc(&self) -> u32 { self.pc }
+/// #     fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+/// #         for r in self.r.iter().chain([self.pc].iter()) {
+/// #             r.to_le_bytes().iter().for_each(|b| write_byte(Some(*b)));
+/// #         }
+/// #     }
+/// #     fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+/// #         if bytes.len() != 12 { return Err(()) }
+/// #         let mut regs = bytes.chunks(4).map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]));
+/// #         self.r = [regs.next().unwrap(), regs.next().unwrap()];
+/// #         self.pc = regs.next().unwrap();
+/// #         Ok(())
+/// #     }
+/// # }
+/// enum MyArch {}
+///
+/// impl Arch for MyArch {
+///     // ...
+/// #   type Usize = u32;
+/// #   type Registers = Regs;
+/// #   type RegId = Reg;
+/// #   type BreakpointKind = usize;
+/// }
+///
+/// // typically placed in a `#[test]`
+/// gdbstub::arch::validate_reg_ids::<MyArch>(0..).unwrap();
+/// ```
+pub fn validate_reg_ids<A: Arch>(
+    ids: impl IntoIterator<Item = usize>,
+) -> Result<(), RegIdLayoutError> {
+    let regs = A::Registers::default();
+
+    let mut len = 0;
+    regs.gdb_serialize(|_| len += 1);
+
+    let mut offset = 0;
+    for id in ids {
+        if offset == len {
+            break;
+        }
+
+        let size = match A::RegId::from_raw_id(id) {
+            Some((_, Some(size))) => size.get(),
+            Some((_, None)) => return Err(RegIdLayoutError::UnsizedRegId { id }),
+            None => return Err(RegIdLayoutError::UnknownRegId { id, offset }),
+        };
+
+        if offset + size > len {
+            return Err(RegIdLayoutError::OutOfBounds {
+                id,
+                offset,
+                size,
+                len,
+            });
+        }
+        offset += size;
+    }
+
+    if offset != len {
+        return Err(RegIdLayoutError::Incomplete {
+            covered: offset,
+            len,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::mock::MockArch;
+    use crate::stub::mock::MockRegs;
+
+    /// Claims the (4 byte) mock PC is 8 bytes wide.
+    #[derive(Debug)]
+    struct WideRegId;
+
+    impl RegId for WideRegId {
+        fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+            match id {
+                0 => Some((WideRegId, NonZeroUsize::new(8))),
+                _ => None,
+            }
+        }
+    }
+
+    enum DriftedArch {}
+
+    impl Arch for DriftedArch {
+        type Usize = u32;
+        type Registers = MockRegs;
+        type RegId = WideRegId;
+        type BreakpointKind = usize;
+    }
+
+    #[test]
+    fn reg_id_layout() {
+        assert_eq!(validate_reg_ids::<MockArch>(0..), Ok(()));
+        assert_eq!(
+            validate_reg_ids::<MockArch>(None),
+            Err(RegIdLayoutError::Incomplete { covered: 0, len: 4 })
+        );
+        assert_eq!(
+            validate_reg_ids::<DriftedArch>(0..),
+            Err(RegIdLayoutError::OutOfBounds {
+                id: 0,
+                offset: 0,
+                size: 8,
+                len: 4
+            })
+        );
+        assert_eq!(
+            validate_reg_ids::<DriftedArch>(1..),
+            Err(RegIdLayoutError::UnknownRegId { id: 1, offset: 0 })
+        );
+    }
+}
+
 /// LLDB-specific types supporting [`Arch::lldb_register_info`] and
 /// [`LldbRegisterInfoOverride`] APIs.
 ///
@@ -312,3 +543,220 @@ pub mod lldb {
         Arg8,
     }
 }
+
+/// Types for generating target description XML at runtime.
+///
+/// These are primarily intended for use alongside the
+/// [`TargetDescriptionXmlOverride`] extension, which allows targets to
+/// generate their target description XML dynamically.
+///
+/// [`TargetDescriptionXmlOverride`]: crate::target::ext::target_description_xml_override::TargetDescriptionXmlOverride
+pub mod target_xml {
+    use core::fmt;
+
+    /// Describes a single register, serialized as a `<reg>` element.
+    ///
+    /// See the [GDB docs](https://sourceware.org/gdb/current/onlinedocs/gdb/Target-Description-Format.html#Registers)
+    /// for details on each of the register's attributes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gdbstub::arch::target_xml::Reg;
+    ///
+    /// let reg = Reg {
+    ///     name: "xmm0",
+    ///     bitsize: 128,
+    ///     ty: Some("vec128"),
+    ///     regnum: Some(40),
+    ///     groups: &["float", "vector"],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     reg.to_string(),
+    ///     r#"<reg name="xmm0" bitsize="128" type="vec128" regnum="40" group="float,vector"/>"#
+    /// );
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Reg<'a> {
+        /// The register's name.
+        pub name: &'a str,
+        /// The register's size, in bits.
+        pub bitsize: usize,
+        /// (optional) The register's type (e.g: `uint32`, `code_ptr`,
+        /// `ieee_double`). GDB defaults to `int`.
+        pub ty: Option<&'a str>,
+        /// (optional) The register's number. GDB defaults to one greater than
+        /// the number of the previous register.
+        pub regnum: Option<usize>,
+        /// The register groups the register belongs to (e.g: `general`,
+        /// `float`, `vector`, `system`, or an arbitrary string).
+        ///
+        /// Registers belonging to multiple groups have their groups serialized
+        /// as a single comma-separated `group` attribute. If empty, GDB picks a
+        /// group based on the register's type.
+        pub groups: &'a [&'a str],
+    }
+
+    /// Writes `s`, escaping any characters which aren't valid in an XML
+    /// attribute value.
+    fn write_escaped(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&apos;")?,
+                c => fmt::Write::write_char(f, c)?,
+            }
+        }
+        Ok(())
+    }
+
+    impl fmt::Display for Reg<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("<reg name=\"")?;
+            write_escaped(f, self.name)?;
+            write!(f, "\" bitsize=\"{}\"", self.bitsize)?;
+            if let Some(ty) = self.ty {
+                f.write_str(" type=\"")?;
+                write_escaped(f, ty)?;
+                f.write_str("\"")?;
+            }
+            if let Some(regnum) = self.regnum {
+                write!(f, " regnum=\"{}\"", regnum)?;
+            }
+            if !self.groups.is_empty() {
+                f.write_str(" group=\"")?;
+                for (i, group) in self.groups.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(",")?;
+                    }
+                    write_escaped(f, group)?;
+                }
+                f.write_str("\"")?;
+            }
+            f.write_str("/>")
+        }
+    }
+
+    /// A target description XML document, assembled at runtime from an
+    /// architecture name and a list of static `<feature>` fragments.
+    ///
+    /// This makes it easy for a single `Target` to describe different register
+    /// sets depending on the features supported by the CPU being debugged,
+    /// without requiring a separate `Arch` per configuration (or any heap
+    /// allocation).
+    ///
+    /// ```
+    /// use gdbstub::arch::target_xml::TargetDescription;
+    ///
+    /// const CORE: &str = r#"<feature name="org.gnu.gdb.i386.core">...</feature>"#;
+    /// const AVX: &str = r#"<feature name="org.gnu.gdb.i386.avx">...</feature>"#;
+    ///
+    /// fn describe(has_avx: bool) -> TargetDescription<'static> {
+    ///     let features: &[&str] = if has_avx { &[CORE, AVX] } else { &[CORE] };
+    ///     TargetDescription::new("i386:x86-64", features)
+    /// }
+    ///
+    /// // within `TargetDescriptionXmlOverride::target_description_xml`:
+    /// let mut buf = [0; 256];
+    /// let len = describe(true).copy_range_to_buf(0, buf.len(), &mut buf);
+    /// assert!(buf[..len].ends_with(b"i386.avx\">...</feature></target>"));
+    /// ```
+    #[derive(Debug, Clone, Copy)]
+    pub struct TargetDescription<'a> {
+        architecture: &'a str,
+        features: &'a [&'a str],
+    }
+
+    impl<'a> TargetDescription<'a> {
+        /// Describe a target with the given `<architecture>`, and the specified
+        /// `<feature>` fragments (in order).
+        pub fn new(architecture: &'a str, features: &'a [&'a str]) -> TargetDescription<'a> {
+            TargetDescription {
+                architecture,
+                features,
+            }
+        }
+
+        fn pieces(&self) -> impl Iterator<Item = &'a str> {
+            let head = [
+                r#"<target version="1.0"><architecture>"#,
+                self.architecture,
+                "</architecture>",
+            ];
+            IntoIterator::into_iter(head)
+                .chain(self.features.iter().copied())
+                .chain(core::iter::once("</target>"))
+        }
+
+        /// Copy the range `offset..offset + length` of the assembled XML into
+        /// `buf`, returning the number of bytes copied.
+        ///
+        /// Like [`copy_range_to_buf`](crate::target::ext::copy_range_to_buf),
+        /// this returns `0` if `offset` is past the end of the document.
+        pub fn copy_range_to_buf(&self, offset: u64, length: usize, buf: &mut [u8]) -> usize {
+            let len = length.min(buf.len());
+            let mut skip = offset;
+            let mut copied = 0;
+            for piece in self.pieces().map(str::as_bytes) {
+                if copied == len {
+                    break;
+                }
+                if skip >= piece.len() as u64 {
+                    skip -= piece.len() as u64;
+                    continue;
+                }
+                let piece = &piece[skip as usize..];
+                skip = 0;
+                let n = piece.len().min(len - copied);
+                buf[copied..copied + n].copy_from_slice(&piece[..n]);
+                copied += n;
+            }
+            copied
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn read_all(desc: TargetDescription<'_>) -> String {
+            // read in small chunks, to exercise reads that straddle fragments
+            let mut buf = [0; 7];
+            let mut out = Vec::new();
+            loop {
+                let n = desc.copy_range_to_buf(out.len() as u64, 100, &mut buf);
+                if n == 0 {
+                    break;
+                }
+                out.extend_from_slice(&buf[..n]);
+            }
+            String::from_utf8(out).unwrap()
+        }
+
+        #[test]
+        fn target_description_features() {
+            let core = r#"<feature name="core"/>"#;
+            let neon = r#"<feature name="neon"/>"#;
+
+            assert_eq!(
+                read_all(TargetDescription::new("arm", &[core])),
+                r#"<target version="1.0"><architecture>arm</architecture><feature name="core"/></target>"#
+            );
+            assert_eq!(
+                read_all(TargetDescription::new("arm", &[core, neon])),
+                r#"<target version="1.0"><architecture>arm</architecture><feature name="core"/><feature name="neon"/></target>"#
+            );
+
+            let mut buf = [0; 8];
+            let features = [core];
+            let desc = TargetDescription::new("arm", &features);
+            assert_eq!(desc.copy_range_to_buf(10_000, 8, &mut buf), 0);
+            assert_eq!(desc.copy_range_to_buf(u64::MAX, 8, &mut buf), 0);
+            assert_eq!(desc.copy_range_to_buf(0, 3, &mut buf), 3);
+        }
+    }
+}
@@ -3,9 +3,20 @@
 mod signal;
 
 pub use self::signal::Signal;
+pub use crate::protocol::IdKind;
+pub use crate::protocol::SpecificIdKind;
 
 /// Thread ID
+///
+/// On the wire, GDB encodes thread IDs as hex, reserving `-1` for "all
+/// threads" and `0` for "any thread". `gdbstub` handles those special values
+/// internally, so a `Tid` always refers to a single, concrete thread.
+///
+/// See [`IdKind`] for a type which can represent (and format / parse) any
+/// on-the-wire ID.
 pub type Tid = core::num::NonZeroUsize;
 
 /// Process ID
+///
+/// Uses the same wire encoding as [`Tid`].
 pub type Pid = core::num::NonZeroUsize;
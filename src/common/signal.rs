@@ -1,6 +1,40 @@
 /// Cross-platform signal numbers defined by the GDB Remote Serial Protocol.
 ///
 /// Transcribed from <https://github.com/bminor/binutils-gdb/blob/master/include/gdb/signals.def>
+///
+/// # Signal numbering
+///
+/// These are GDB's _own_ signal numbers (i.e: `GDB_SIGNAL_*`), which the GDB
+/// client translates to/from host signals as necessary. They do **not**
+/// necessarily match the signal numbers of any particular OS! e.g: `SIGBUS`
+/// is signal `10` here, but signal `7` on Linux x86.
+///
+/// Targets which don't use POSIX signals at all (e.g: bare-metal targets with
+/// their own exception model) should map each kind of fault onto the named
+/// constant which best describes it, rather than reporting raw numbers:
+///
+/// ```
+/// use gdbstub::common::Signal;
+///
+/// enum Fault {
+///     Breakpoint,
+///     BadAccess,
+///     UndefinedInstruction,
+///     DivideByZero,
+/// }
+///
+/// fn to_signal(fault: Fault) -> Signal {
+///     match fault {
+///         Fault::Breakpoint => Signal::SIGTRAP,
+///         Fault::BadAccess => Signal::SIGSEGV,
+///         Fault::UndefinedInstruction => Signal::SIGILL,
+///         Fault::DivideByZero => Signal::SIGFPE,
+///     }
+/// }
+///
+/// assert_eq!(to_signal(Fault::BadAccess), Signal::SIGSEGV);
+/// assert_eq!(Signal::SIGSEGV.0, 11);
+/// ```
 #[repr(transparent)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Signal(pub u8);
@@ -183,6 +217,22 @@ impl Signal {
     #[doc = "librt internal signal"]           pub const SIGLIBRT:            Self = Self(151);
 }
 
+impl Signal {
+    /// Create a `Signal` from a raw GDB signal number, returning `None` if the
+    /// number doesn't correspond to any signal defined by GDB.
+    ///
+    /// Note that this expects GDB's own signal numbering (see the
+    /// [type-level docs](Signal#signal-numbering)), _not_ a host OS's signal
+    /// number.
+    pub const fn from_gdb_number(signal: u8) -> Option<Signal> {
+        if signal <= Signal::SIGLIBRT.0 {
+            Some(Signal(signal))
+        } else {
+            None
+        }
+    }
+}
+
 impl core::fmt::Display for Signal {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         #[rustfmt::skip]
@@ -346,3 +396,16 @@ impl core::fmt::Display for Signal {
         write!(f, "{}", s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_gdb_number() {
+        assert_eq!(Signal::from_gdb_number(5), Some(Signal::SIGTRAP));
+        assert_eq!(Signal::from_gdb_number(11), Some(Signal::SIGSEGV));
+        assert_eq!(Signal::from_gdb_number(151), Some(Signal::SIGLIBRT));
+        assert_eq!(Signal::from_gdb_number(152), None);
+    }
+}
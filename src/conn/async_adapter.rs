@@ -0,0 +1,135 @@
+use crate::conn::AsyncConnection;
+use crate::conn::Connection;
+use alloc::vec::Vec;
+
+/// Adapts an [`AsyncConnection`] into a [`Connection`] that can be handed to
+/// [`GdbStub`](crate::stub::GdbStub).
+///
+/// `gdbstub`'s protocol implementation is synchronous, so `AsyncAdapter`
+/// queues up every response in memory, and relies on
+/// [`GdbStub::run_async`](crate::stub::GdbStub::run_async) to write the queued
+/// bytes to the underlying `AsyncConnection` between state-machine
+/// transitions.
+pub struct AsyncAdapter<C> {
+    inner: C,
+    pending: Vec<u8>,
+}
+
+impl<C: AsyncConnection> AsyncAdapter<C> {
+    /// Wrap the provided `AsyncConnection`.
+    pub fn new(inner: C) -> AsyncAdapter<C> {
+        AsyncAdapter {
+            inner,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Return a reference to the inner connection.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Return a mutable reference to the inner connection.
+    pub fn get_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Return the inner connection, discarding any queued bytes.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Write any queued bytes to the inner connection, and flush it.
+    pub async fn drain(&mut self) -> Result<(), C::Error> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let res = self.inner.write_all(&self.pending).await;
+        self.pending.clear();
+        res?;
+        self.inner.flush().await
+    }
+}
+
+impl<C: AsyncConnection> Connection for AsyncAdapter<C> {
+    type Error = C::Error;
+
+    fn write(&mut self, byte: u8) -> Result<(), Self::Error> {
+        self.pending.push(byte);
+        Ok(())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.pending.extend_from_slice(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        // bytes are flushed asynchronously via `AsyncAdapter::drain`
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::Context;
+    use core::task::Poll;
+    use core::task::Waker;
+
+    /// Busy-poll a future to completion (suitable for futures which never
+    /// actually wait on anything).
+    pub(crate) fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(res) = fut.as_mut().poll(&mut cx) {
+                return res;
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct MockConnection {
+        written: Vec<u8>,
+        flushes: usize,
+    }
+
+    impl AsyncConnection for MockConnection {
+        type Error = ();
+
+        async fn read(&mut self) -> Result<u8, ()> {
+            Err(())
+        }
+
+        async fn write_all(&mut self, buf: &[u8]) -> Result<(), ()> {
+            self.written.extend_from_slice(buf);
+            Ok(())
+        }
+
+        async fn flush(&mut self) -> Result<(), ()> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn async_adapter_drain() {
+        let mut conn = AsyncAdapter::new(MockConnection::default());
+
+        Connection::write_all(&mut conn, b"$OK#9a").unwrap();
+        Connection::flush(&mut conn).unwrap();
+        assert!(conn.get_ref().written.is_empty());
+
+        block_on(conn.drain()).unwrap();
+        assert_eq!(conn.get_ref().written, b"$OK#9a");
+        assert_eq!(conn.get_ref().flushes, 1);
+
+        // nothing queued, so nothing to flush
+        block_on(conn.drain()).unwrap();
+        assert_eq!(conn.get_ref().flushes, 1);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::conn::Connection;
+use crate::conn::ConnectionExt;
+use core::fmt::Display;
+use core::fmt::{self};
+use core::time::Duration;
+use managed::ManagedSlice;
+
+/// An error which may occur when writing through a [`BufferedConnection`].
+#[derive(Debug)]
+pub enum BufferedConnectionError<E> {
+    /// Error reported by the underlying connection.
+    Connection(E),
+    /// A fixed-size backing buffer ran out of space before the next `flush`.
+    BufferFull,
+}
+
+impl<E: Display> Display for BufferedConnectionError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::BufferedConnectionError::*;
+        match self {
+            Connection(e) => write!(f, "{}", e),
+            BufferFull => write!(f, "BufferedConnection's backing buffer is full"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> std::error::Error for BufferedConnectionError<E> where E: fmt::Debug + Display {}
+
+/// A [`Connection`] adapter which accumulates written bytes in memory, and
+/// only forwards them to the inner connection (via a single `write_all` call)
+/// on `flush`.
+///
+/// `gdbstub` flushes its connection once at the end of every response
+/// packet, so wrapping a connection that has a high per-call overhead (e.g: a
+/// `TcpStream` with `TCP_NODELAY` set) in a `BufferedConnection` results in
+/// one underlying write per packet, instead of one per byte.
+///
+/// The backing buffer can either be a fixed-size `&mut [u8]` (see
+/// [`BufferedConnection::new`]), in which case writing past the end of the
+/// buffer returns [`BufferedConnectionError::BufferFull`], or (when the
+/// `alloc` feature is enabled) a growable `Vec<u8>` (see
+/// [`BufferedConnection::new_growable`]).
+///
+/// When using a fixed-size buffer, make sure it is at least as large as the
+/// largest response `gdbstub` may send, which is bounded by the packet buffer
+/// size plus a few bytes of packet framing.
+pub struct BufferedConnection<'a, C> {
+    inner: C,
+    buf: ManagedSlice<'a, u8>,
+    len: usize,
+}
+
+impl<'a, C: Connection> BufferedConnection<'a, C> {
+    /// Wrap `inner`, buffering writes in the provided fixed-size buffer.
+    pub fn new(inner: C, buf: &'a mut [u8]) -> BufferedConnection<'a, C> {
+        BufferedConnection {
+            inner,
+            buf: ManagedSlice::Borrowed(buf),
+            len: 0,
+        }
+    }
+
+    /// Wrap `inner`, buffering writes in a heap-allocated buffer that grows
+    /// as needed.
+    #[cfg(feature = "alloc")]
+    pub fn new_growable(inner: C) -> BufferedConnection<'static, C> {
+        BufferedConnection {
+            inner,
+            buf: ManagedSlice::Owned(alloc::vec::Vec::new()),
+            len: 0,
+        }
+    }
+
+    /// Return a reference to the inner connection.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Return a mutable reference to the inner connection.
+    ///
+    /// Writing directly to the inner connection bypasses (and does not
+    /// flush) any bytes which are currently buffered.
+    pub fn get_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+
+    /// Return the inner connection, discarding any unflushed bytes.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Return the bytes which have been written but not yet flushed.
+    pub fn buffered(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl<C: Connection> Connection for BufferedConnection<'_, C> {
+    type Error = BufferedConnectionError<C::Error>;
+
+    fn write(&mut self, byte: u8) -> Result<(), Self::Error> {
+        self.write_all(&[byte])
+    }
+
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        let end = self.len + data.len();
+        match &mut self.buf {
+            ManagedSlice::Borrowed(buf) => {
+                let dst = buf
+                    .get_mut(self.len..end)
+                    .ok_or(BufferedConnectionError::BufferFull)?;
+                dst.copy_from_slice(data);
+            }
+            #[cfg(feature = "alloc")]
+            ManagedSlice::Owned(buf) => buf.extend_from_slice(data),
+        }
+        self.len = end;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        let len = core::mem::replace(&mut self.len, 0);
+        let res = self.inner.write_all(&self.buf[..len]);
+        #[cfg(feature = "alloc")]
+        if let ManagedSlice::Owned(buf) = &mut self.buf {
+            buf.clear();
+        }
+        res.map_err(BufferedConnectionError::Connection)?;
+        self.inner
+            .flush()
+            .map_err(BufferedConnectionError::Connection)
+    }
+
+    fn on_session_start(&mut self) -> Result<(), Self::Error> {
+        self.inner
+            .on_session_start()
+            .map_err(BufferedConnectionError::Connection)
+    }
+}
+
+impl<C: ConnectionExt> ConnectionExt for BufferedConnection<'_, C> {
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        self.inner
+            .read()
+            .map_err(BufferedConnectionError::Connection)
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, Self::Error> {
+        self.inner
+            .peek()
+            .map_err(BufferedConnectionError::Connection)
+    }
+
+    fn read_with_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        self.inner
+            .read_with_timeout(timeout)
+            .map_err(BufferedConnectionError::Connection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockConnection {
+        writes: Vec<Vec<u8>>,
+    }
+
+    impl Connection for MockConnection {
+        type Error = ();
+
+        fn write(&mut self, byte: u8) -> Result<(), ()> {
+            self.writes.push(vec![byte]);
+            Ok(())
+        }
+
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), ()> {
+            self.writes.push(buf.to_vec());
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn buffered_fixed() {
+        let mut buf = [0; 4];
+        let mut conn = BufferedConnection::new(MockConnection { writes: Vec::new() }, &mut buf);
+
+        conn.write_all(b"$OK").unwrap();
+        conn.write(b'#').unwrap();
+        assert!(conn.get_ref().writes.is_empty());
+        assert!(matches!(
+            conn.write(b'9'),
+            Err(BufferedConnectionError::BufferFull)
+        ));
+
+        conn.flush().unwrap();
+        conn.write_all(b"+").unwrap();
+        conn.flush().unwrap();
+        assert_eq!(conn.get_ref().writes, [b"$OK#".to_vec(), b"+".to_vec()]);
+    }
+
+    #[test]
+    fn buffered_growable() {
+        let mut conn = BufferedConnection::new_growable(MockConnection { writes: Vec::new() });
+
+        let data = [b'x'; 1024];
+        conn.write_all(&data).unwrap();
+        conn.write(b'y').unwrap();
+        assert_eq!(conn.buffered().len(), 1025);
+
+        conn.flush().unwrap();
+        assert!(conn.buffered().is_empty());
+        assert_eq!(conn.get_ref().writes.len(), 1);
+        assert_eq!(conn.get_ref().writes[0].len(), 1025);
+    }
+}
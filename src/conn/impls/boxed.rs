@@ -1,6 +1,7 @@
 use crate::conn::Connection;
 use crate::conn::ConnectionExt;
 use alloc::boxed::Box;
+use core::time::Duration;
 
 impl<E> Connection for Box<dyn Connection<Error = E>> {
     type Error = E;
@@ -50,4 +51,8 @@ impl<E> ConnectionExt for Box<dyn ConnectionExt<Error = E>> {
     fn peek(&mut self) -> Result<Option<u8>, Self::Error> {
         (**self).peek()
     }
+
+    fn read_with_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        (**self).read_with_timeout(timeout)
+    }
 }
@@ -12,6 +12,7 @@ mod unixstream;
 
 use crate::conn::Connection;
 use crate::conn::ConnectionExt;
+use core::time::Duration;
 
 impl<E> Connection for &mut dyn Connection<Error = E> {
     type Error = E;
@@ -61,4 +62,8 @@ impl<E> ConnectionExt for &mut dyn ConnectionExt<Error = E> {
     fn peek(&mut self) -> Result<Option<u8>, Self::Error> {
         (**self).peek()
     }
+
+    fn read_with_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        (**self).read_with_timeout(timeout)
+    }
 }
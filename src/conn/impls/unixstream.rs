@@ -1,5 +1,6 @@
 use crate::conn::Connection;
 use crate::conn::ConnectionExt;
+use core::time::Duration;
 use std::io;
 use std::os::unix::net::UnixStream;
 
@@ -99,4 +100,81 @@ impl ConnectionExt for UnixStream {
             Err(e) => Err(e),
         }
     }
+
+    fn read_with_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        use std::io::ErrorKind;
+        use std::io::Read;
+
+        // a zero read timeout is rejected by the OS, so treat it as a poll
+        if timeout.is_zero() {
+            return match ConnectionExt::peek(self)? {
+                Some(_) => ConnectionExt::read(self).map(Some),
+                None => Ok(None),
+            };
+        }
+
+        self.set_nonblocking(false)?;
+        let prev_timeout = UnixStream::read_timeout(self)?;
+        self.set_read_timeout(Some(timeout))?;
+
+        let mut buf = [0u8];
+        let res = match Read::read(self, &mut buf) {
+            Ok(0) => Err(ErrorKind::UnexpectedEof.into()),
+            Ok(_) => Ok(Some(buf[0])),
+            Err(ref e)
+                if matches!(
+                    e.kind(),
+                    ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        };
+
+        self.set_read_timeout(prev_timeout)?;
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // the zero-timeout poll relies on `peek`, which is unavailable with `paranoid_unsafe`
+    #[cfg(not(feature = "paranoid_unsafe"))]
+    fn unixstream_packet_exchange() {
+        let (mut client, mut server) = UnixStream::pair().unwrap();
+
+        let timeout = Duration::from_millis(10);
+        assert_eq!(server.read_with_timeout(timeout).unwrap(), None);
+        assert_eq!(server.read_with_timeout(Duration::ZERO).unwrap(), None);
+
+        Connection::write_all(&mut client, b"$?#3f").unwrap();
+        Connection::flush(&mut client).unwrap();
+
+        let mut packet = Vec::new();
+        while packet.len() < 5 {
+            if let Some(byte) = server.read_with_timeout(Duration::from_secs(5)).unwrap() {
+                packet.push(byte);
+            }
+        }
+        assert_eq!(packet, b"$?#3f");
+        // the socket's own read timeout is left untouched
+        assert_eq!(UnixStream::read_timeout(&server).unwrap(), None);
+
+        // like `read`, a non-zero timeout leaves the socket in blocking mode
+        server.set_nonblocking(true).unwrap();
+        assert_eq!(server.read_with_timeout(timeout).unwrap(), None);
+        Connection::write_all(&mut client, b"+").unwrap();
+        assert_eq!(ConnectionExt::read(&mut server).unwrap(), b'+');
+
+        Connection::write_all(&mut server, b"+$S05#b8").unwrap();
+        Connection::flush(&mut server).unwrap();
+
+        let mut reply = [0; 8];
+        std::io::Read::read_exact(&mut client, &mut reply).unwrap();
+        assert_eq!(&reply, b"+$S05#b8");
+    }
 }
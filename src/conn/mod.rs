@@ -1,7 +1,20 @@
 //! Traits to perform in-order, serial, byte-wise I/O.
 
+use core::future::Future;
+use core::time::Duration;
+
+#[cfg(feature = "alloc")]
+mod async_adapter;
+mod buffered;
 mod impls;
 
+#[cfg(all(test, feature = "alloc"))]
+pub(crate) use async_adapter::tests::block_on;
+#[cfg(feature = "alloc")]
+pub use async_adapter::AsyncAdapter;
+pub use buffered::BufferedConnection;
+pub use buffered::BufferedConnectionError;
+
 /// A trait to perform in-order, serial, byte-wise I/O.
 ///
 /// When the `std` feature is enabled, this trait is automatically implemented
@@ -16,6 +29,10 @@ pub trait Connection {
 
     /// Write the entire buffer, blocking until complete.
     ///
+    /// `gdbstub` sends outgoing packets in batches of several bytes at a time
+    /// via this method, only falling back to byte-wise `write` calls through
+    /// this method's default implementation.
+    ///
     /// This method's default implementation calls `self.write()` on each byte
     /// in the buffer. This can be quite inefficient, so if a more efficient
     /// implementation exists (such as calling `write_all()` on an underlying
@@ -30,6 +47,10 @@ pub trait Connection {
     /// Flush this Connection, ensuring that all intermediately buffered
     /// contents reach their destination.
     ///
+    /// `gdbstub` calls this method exactly once at the end of every packet it
+    /// sends, making it a good place for packetized transports (e.g: a USB CDC
+    /// endpoint) to transmit any buffered data.
+    ///
     /// _Note:_ Not all `Connection`s have internal buffering (e.g: writing data
     /// to a UART TX register with FIFOs disabled). In these cases, it's fine to
     /// simply return `Ok(())`.
@@ -52,7 +73,7 @@ pub trait Connection {
     }
 }
 
-/// Extends [`Connection`] with `read` and `peek` methods.
+/// Extends [`Connection`] with `read`, `peek` and `read_with_timeout` methods.
 ///
 /// This trait is used as part of `gdbstub`'s quickstart
 /// [`GdbStub::run_blocking`](crate::stub::GdbStub::run_blocking) API.
@@ -72,5 +93,67 @@ pub trait ConnectionExt: Connection {
     ///
     /// Returns a byte (if one is available) without removing that byte from the
     /// queue. Subsequent calls to `peek` MUST return the same byte.
-    fn peek(&mut self) -> Result<Option<u8>, Self::Error>;
+    ///
+    /// [`GdbStub::run_blocking`](crate::stub::GdbStub::run_blocking) peeks
+    /// the connection after resuming the target, so that data which is
+    /// already pending (e.g: a Ctrl-C interrupt sent right after a `continue`)
+    /// is handled immediately, without waiting on `wait_for_stop_reason`.
+    ///
+    /// This method's default implementation returns `Ok(None)`, which is
+    /// suitable for transports that cannot peek. The `TcpStream` and
+    /// `UnixStream` implementations perform a non-blocking socket peek.
+    fn peek(&mut self) -> Result<Option<u8>, Self::Error> {
+        Ok(None)
+    }
+
+    /// Read a single byte, blocking for at most `timeout`.
+    ///
+    /// Returns `Ok(None)` if no byte arrived before the timeout elapsed. This
+    /// lets an event loop built on top of the
+    /// [`GdbStubStateMachine`](crate::stub::state_machine::GdbStubStateMachine)
+    /// API wake up periodically (e.g: to check whether the host application
+    /// is shutting down) instead of blocking forever in `read`.
+    ///
+    /// This method's default implementation ignores the timeout, and simply
+    /// calls `self.read()`. The `TcpStream` and `UnixStream` implementations
+    /// honor the timeout by temporarily setting the socket's read timeout.
+    /// Like their `read` and `peek` implementations, each call puts the socket
+    /// into whichever blocking mode it needs (blocking, or non-blocking when
+    /// polling with a zero `timeout`), and leaves it that way.
+    fn read_with_timeout(&mut self, timeout: Duration) -> Result<Option<u8>, Self::Error> {
+        let _ = timeout;
+        self.read().map(Some)
+    }
+}
+
+/// An asynchronous counterpart to [`Connection`] + [`ConnectionExt`].
+///
+/// Implementations of this trait can be used to drive a debugging session
+/// from within an async runtime (e.g: `tokio`) via
+/// [`GdbStub::run_async`](crate::stub::GdbStub::run_async), without dedicating
+/// an OS thread to each session.
+///
+/// To hand an `AsyncConnection` to [`GdbStub`](crate::stub::GdbStub), wrap
+/// it in an [`AsyncAdapter`].
+pub trait AsyncConnection {
+    /// Transport-specific error type.
+    type Error;
+
+    /// Read a single byte.
+    fn read(&mut self) -> impl Future<Output = Result<u8, Self::Error>>;
+
+    /// Write the entire buffer.
+    fn write_all(&mut self, buf: &[u8]) -> impl Future<Output = Result<(), Self::Error>>;
+
+    /// Flush this connection, ensuring that all intermediately buffered
+    /// contents reach their destination.
+    fn flush(&mut self) -> impl Future<Output = Result<(), Self::Error>>;
+
+    /// Called at the start of a debugging session _before_ any GDB packets
+    /// have been sent/received.
+    ///
+    /// This method's default implementation is a no-op.
+    fn on_session_start(&mut self) -> impl Future<Output = Result<(), Self::Error>> {
+        async { Ok(()) }
+    }
 }
@@ -0,0 +1,97 @@
+//! Runtime support for the `gdbstub_derive` `#[derive(Registers)]` macro.
+
+use super::BeBytes;
+use super::LeBytes;
+
+/// Serialize `val` as a `width` byte register, truncating or zero-extending
+/// it as necessary.
+pub fn write_reg<T: LeBytes + BeBytes>(
+    val: T,
+    width: usize,
+    big_endian: bool,
+    write_byte: &mut impl FnMut(Option<u8>),
+) {
+    let mut buf = [0; 16];
+    // infallible (unless val is a >128 bit number)
+    let len = if big_endian {
+        BeBytes::to_be_bytes(val, &mut buf)
+    } else {
+        LeBytes::to_le_bytes(val, &mut buf)
+    }
+    .unwrap();
+    let buf = &buf[..len];
+
+    if big_endian {
+        let pad = width.saturating_sub(len);
+        (0..pad).for_each(|_| write_byte(Some(0)));
+        buf[len - (width - pad)..]
+            .iter()
+            .for_each(|b| write_byte(Some(*b)));
+    } else {
+        (0..width).for_each(|i| write_byte(Some(buf.get(i).copied().unwrap_or(0))));
+    }
+}
+
+/// Deserialize a `width` byte register from the front of `bytes`, advancing
+/// `bytes` past it.
+///
+/// Fails if `bytes` is too short, or if the register doesn't fit in a `T`.
+#[allow(clippy::result_unit_err)]
+pub fn read_reg<T: LeBytes + BeBytes>(
+    bytes: &mut &[u8],
+    width: usize,
+    big_endian: bool,
+) -> Result<T, ()> {
+    if bytes.len() < width {
+        return Err(());
+    }
+    let (reg, rest) = bytes.split_at(width);
+    *bytes = rest;
+
+    let size = core::mem::size_of::<T>();
+    let (reg, extra) = if big_endian {
+        let (extra, reg) = reg.split_at(width.saturating_sub(size));
+        (reg, extra)
+    } else {
+        reg.split_at(width.min(size))
+    };
+    if extra.iter().any(|b| *b != 0) {
+        return Err(());
+    }
+
+    if big_endian {
+        BeBytes::from_be_bytes(reg)
+    } else {
+        LeBytes::from_le_bytes(reg)
+    }
+    .ok_or(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write<T: LeBytes + BeBytes>(val: T, width: usize, big_endian: bool) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_reg(val, width, big_endian, &mut |b| out.push(b.unwrap()));
+        out
+    }
+
+    #[test]
+    fn resized_registers() {
+        assert_eq!(write(0x1234u16, 4, false), [0x34, 0x12, 0, 0]);
+        assert_eq!(write(0x1234u16, 4, true), [0, 0, 0x12, 0x34]);
+        assert_eq!(write(0x1234_5678u32, 2, false), [0x78, 0x56]);
+        assert_eq!(write(0x1234_5678u32, 2, true), [0x56, 0x78]);
+
+        let mut bytes: &[u8] = &[0x34, 0x12, 0, 0, 0, 0, 0x12, 0x34];
+        assert_eq!(read_reg::<u16>(&mut bytes, 4, false), Ok(0x1234));
+        assert_eq!(read_reg::<u16>(&mut bytes, 4, true), Ok(0x1234));
+        assert!(bytes.is_empty());
+        assert_eq!(read_reg::<u16>(&mut bytes, 1, false), Err(()));
+
+        // non-zero bytes which don't fit in the field
+        assert_eq!(read_reg::<u8>(&mut &[1, 1][..], 2, false), Err(()));
+        assert_eq!(read_reg::<u8>(&mut &[1, 1][..], 2, true), Err(()));
+    }
+}
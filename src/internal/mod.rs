@@ -4,5 +4,7 @@
 mod be_bytes;
 mod le_bytes;
 
+pub mod derive;
+
 pub use be_bytes::*;
 pub use le_bytes::*;
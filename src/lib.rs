@@ -27,6 +27,11 @@
 //! - `paranoid_unsafe`
 //!     - Please refer to the [`unsafe` in `gdbstub`](https://github.com/daniel5151/gdbstub#unsafe-in-gdbstub)
 //!       section of the README.md for more details.
+//! - `validate-memory-map`
+//!     - In debug builds, panic if the target's
+//!       [`MemoryMap`](target::ext::memory_map::MemoryMap) XML contains a
+//!       malformed `<memory>` element (e.g: a `start` or `length` attribute
+//!       which isn't a number). Has no effect in release builds.
 //!
 //! ## Getting Started
 //!
@@ -250,6 +255,10 @@
 //!                 println!("Target terminated with signal {}", sig)
 //!             }
 //!             DisconnectReason::Kill => println!("GDB sent a kill command"),
+//!             DisconnectReason::ProtocolError(e) => {
+//!                 println!("Session aborted due to a protocol error: {:?}", e)
+//!             }
+//!             reason => println!("GDB session ended: {:?}", reason),
 //!         },
 //!         Err(e) => {
 //!             if e.is_target_error() {
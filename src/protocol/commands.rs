@@ -19,6 +19,19 @@ pub trait ParseCommand<'a>: Sized {
     fn from_packet(buf: PacketBuf<'a>) -> Option<Self>;
 }
 
+/// Bind `$bufname` to a `PacketBuf` containing the packet `$prefix$body`,
+/// with the `$prefix` already stripped (as it would be by the packet
+/// dispatcher).
+#[cfg(test)]
+macro_rules! test_buf {
+    ($bufname:ident, $prefix:literal, $body:literal) => {
+        let mut test = [&$prefix[..], &$body[..]].concat();
+        let mut buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
+        assert!(buf.strip_prefix($prefix));
+        let $bufname = buf;
+    };
+}
+
 macro_rules! commands {
     (
         $(
@@ -40,6 +53,12 @@ macro_rules! commands {
         // While it's entirely possible to eagerly parse the "Z" packet for
         // bytecode, doing so would unnecessary bloat implementations that do
         // not support evaluating agent expressions.
+        //
+        // # Memory CRC packets (qCRC)
+        //
+        // The "qC" packet is a prefix of "qCRC", so "qCRC" packets sent to
+        // targets which don't implement `MemoryCrc` are special-cased as unknown
+        // packets, instead of being rejected as a malformed "qC" packet.
 
 
         $($(
@@ -50,7 +69,7 @@ macro_rules! commands {
 
         pub mod ext {
             $(
-                #[allow(non_camel_case_types, clippy::enum_variant_names)]
+                #[allow(non_camel_case_types, clippy::enum_variant_names, clippy::upper_case_acronyms)]
                 pub enum [<$ext:camel>] $(<$lt>)? {
                     $($command(super::$mod::$command<$($lifetime)?>),)*
                 }
@@ -61,8 +80,6 @@ macro_rules! commands {
             pub enum Breakpoints<'a> {
                 z(BasicBreakpoint<'a>),
                 Z(BasicBreakpoint<'a>),
-                // Bytecode hasn't yet been plumbed all the way through
-                #[allow(dead_code)]
                 ZWithBytecode(BytecodeBreakpoint<'a>),
             }
 
@@ -93,9 +110,12 @@ macro_rules! commands {
                     fn support_single_register_access(&mut self) -> Option<()>;
                     fn support_reverse_step(&mut self) -> Option<()>;
                     fn support_reverse_cont(&mut self) -> Option<()>;
+                    fn support_non_stop(&mut self) -> Option<()>;
                     fn support_no_ack_mode(&mut self) -> Option<()>;
                     fn support_x_upcase_packet(&mut self) -> Option<()>;
                     fn support_thread_extra_info(&mut self) -> Option<()>;
+                    fn support_thread_events(&mut self) -> Option<()>;
+                    fn support_thread_list(&mut self) -> Option<()>;
                 }
 
                 impl<T: Target> Hack for T {
@@ -155,6 +175,14 @@ macro_rules! commands {
                         }
                     }
 
+                    fn support_non_stop(&mut self) -> Option<()> {
+                        use crate::target::ext::base::ResumeOps;
+                        match self.base_ops().resume_ops()? {
+                            ResumeOps::SingleThread(_) => None,
+                            ResumeOps::MultiThread(ops) => ops.support_non_stop().map(drop),
+                        }
+                    }
+
                     fn support_x_upcase_packet(&mut self) -> Option<()> {
                         if self.use_x_upcase_packet() {
                             Some(())
@@ -178,6 +206,26 @@ macro_rules! commands {
                             BaseOps::MultiThread(ops) => ops.support_thread_extra_info().map(drop),
                         }
                     }
+
+                    fn support_thread_events(&mut self) -> Option<()> {
+                        use crate::target::ext::base::BaseOps;
+                        match self.base_ops() {
+                            BaseOps::SingleThread(_) => None,
+                            BaseOps::MultiThread(ops) => ops.support_thread_events().map(drop),
+                        }
+                    }
+
+                    fn support_thread_list(&mut self) -> Option<()> {
+                        use crate::target::ext::base::BaseOps;
+                        match self.base_ops() {
+                            BaseOps::SingleThread(_) => None,
+                            BaseOps::MultiThread(ops) => ops.support_thread_list().map(drop),
+                        }
+                    }
+                }
+
+                if target.support_memory_crc().is_none() && buf.strip_prefix(b"qCRC") {
+                    return Some(Command::Unknown(b"qCRC"));
                 }
 
                 // TODO?: use tries for more efficient longest prefix matching
@@ -201,7 +249,7 @@ macro_rules! commands {
                 }
                 )*
 
-                if let Some(_breakpoint_ops) = target.support_breakpoints() {
+                if let Some(breakpoint_ops) = target.support_breakpoints() {
                     use breakpoint::{BasicBreakpoint, BytecodeBreakpoint};
 
                     if buf.strip_prefix(b"z") {
@@ -210,8 +258,9 @@ macro_rules! commands {
                     }
 
                     if buf.strip_prefix(b"Z") {
-                        // TODO: agent bytecode currently unimplemented
-                        if true {
+                        if breakpoint_ops.support_conditional_breakpoints().is_none()
+                            && breakpoint_ops.support_breakpoint_commands().is_none()
+                        {
                             let cmd = BasicBreakpoint::from_slice(buf.into_body())?;
                             return Some(Command::Breakpoints(ext::Breakpoints::Z(cmd)))
                         } else {
@@ -228,6 +277,11 @@ macro_rules! commands {
 }
 
 commands! {
+    // must be matched before `base`, whose "qC" is a prefix of "qCRC"
+    memory_crc use 'a {
+        "qCRC" => _qCRC::qCRC<'a>,
+    }
+
     base use 'a {
         "?" => question_mark::QuestionMark,
         "D" => _d_upcase::D,
@@ -237,12 +291,15 @@ commands! {
         "k" => _k::k,
         "m" => _m::m<'a>,
         "M" => _m_upcase::M<'a>,
+        "QListThreadsInStopReply" => _QListThreadsInStopReply::QListThreadsInStopReply,
         "qAttached" => _qAttached::qAttached,
+        "qC" => _qC::qC,
         "qfThreadInfo" => _qfThreadInfo::qfThreadInfo,
         "qsThreadInfo" => _qsThreadInfo::qsThreadInfo,
         "qSupported" => _qSupported::qSupported<'a>,
         "T" => _t_upcase::T,
         "vKill" => _vKill::vKill,
+        "vMustReplyEmpty" => _vMustReplyEmpty::vMustReplyEmpty,
     }
 
     target_xml use 'a {
@@ -266,11 +323,11 @@ commands! {
     single_register_access use 'a {
         "p" => _p::p<'a>,
         "P" => _p_upcase::P<'a>,
+        "QThreadSuffixSupported" => _QThreadSuffixSupported::QThreadSuffixSupported,
     }
 
     extended_mode use 'a {
         "!" => exclamation_mark::ExclamationMark,
-        "qC" => _qC::qC,
         "QDisableRandomization" => _QDisableRandomization::QDisableRandomization,
         "QEnvironmentHexEncoded" => _QEnvironmentHexEncoded::QEnvironmentHexEncoded<'a>,
         "QEnvironmentReset" => _QEnvironmentReset::QEnvironmentReset,
@@ -298,6 +355,12 @@ commands! {
         "bs" => _bs::bs,
     }
 
+    non_stop {
+        "QNonStop" => _QNonStop::QNonStop,
+        "vCtrlC" => _vCtrlC::vCtrlC,
+        "vStopped" => _vStopped::vStopped,
+    }
+
     memory_map use 'a {
         "qXfer:memory-map:read" => _qXfer_memory_map::qXferMemoryMapRead<'a>,
     }
@@ -310,6 +373,22 @@ commands! {
         "qXfer:exec-file:read" => _qXfer_exec_file::qXferExecFileRead<'a>,
     }
 
+    os_data use 'a {
+        "qXfer:osdata:read" => _qXfer_osdata_read::qXferOsDataRead<'a>,
+    }
+
+    signal_info use 'a {
+        "qXfer:siginfo:read" => _qXfer_siginfo_read::qXferSiginfoRead<'a>,
+    }
+
+    thread_local_storage use 'a {
+        "qGetTLSAddr" => _qGetTLSAddr::qGetTLSAddr<'a>,
+    }
+
+    windows_thread_info {
+        "qGetTIBAddr" => _qGetTIBAddr::qGetTIBAddr,
+    }
+
     host_io use 'a {
         "vFile:open" => _vFile_open::vFileOpen<'a>,
         "vFile:close" => _vFile_close::vFileClose,
@@ -325,10 +404,26 @@ commands! {
         "QCatchSyscalls" => _QCatchSyscalls::QCatchSyscalls<'a>,
     }
 
+    pass_signals use 'a {
+        "QPassSignals" => _QPassSignals::QPassSignals<'a>,
+    }
+
+    program_signals use 'a {
+        "QProgramSignals" => _QProgramSignals::QProgramSignals<'a>,
+    }
+
     thread_extra_info use 'a {
         "qThreadExtraInfo" => _qThreadExtraInfo::qThreadExtraInfo<'a>,
     }
 
+    thread_events {
+        "QThreadEvents" => _QThreadEvents::QThreadEvents,
+    }
+
+    thread_list use 'a {
+        "qXfer:threads:read" => _qXfer_threads_read::qXferThreadsRead<'a>,
+    }
+
     lldb_register_info {
         "qRegisterInfo" => _qRegisterInfo::qRegisterInfo,
     }
@@ -336,4 +431,35 @@ commands! {
     libraries_svr4 use 'a {
         "qXfer:libraries-svr4:read" => _qXfer_libraries_svr4_read::qXferLibrariesSvr4Read<'a>,
     }
+
+    memory_search use 'a {
+        "qSearch:memory" => _qSearch_memory::qSearchMemory<'a>,
+    }
+
+    flash_operations use 'a {
+        "vFlashErase" => _vFlashErase::vFlashErase<'a>,
+        "vFlashWrite" => _vFlashWrite::vFlashWrite<'a>,
+        "vFlashDone" => _vFlashDone::vFlashDone,
+    }
+
+    memory_tagging use 'a {
+        "qMemTags" => _qMemTags::qMemTags<'a>,
+        "QMemTags" => _QMemTags_upcase::QMemTags<'a>,
+    }
+
+    tracepoints use 'a {
+        "QTDP" => _QTDP::QTDP<'a>,
+        "QTinit" => _QTinit::QTinit,
+        "QTStart" => _QTStart::QTStart,
+        "QTStop" => _QTStop::QTStop,
+        "qTStatus" => _qTStatus::qTStatus,
+        "QTDV" => _QTDV::QTDV<'a>,
+        "qTfV" => _qTfV::qTfV,
+        "qTsV" => _qTsV::qTsV,
+        "qTV" => _qTV::qTV,
+    }
+
+    agent {
+        "QAgent" => _QAgent::QAgent,
+    }
 }
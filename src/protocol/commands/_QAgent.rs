@@ -2,18 +2,18 @@ use super::prelude::*;
 
 #[derive(Debug)]
 pub struct QAgent {
-    pub value: bool,
+    pub enabled: bool,
 }
 
 impl<'a> ParseCommand<'a> for QAgent {
     #[inline(always)]
     fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
         let body = buf.into_body();
-        let value = match body as &[u8] {
+        let enabled = match body as &[u8] {
             b":0" => false,
             b":1" => true,
             _ => return None,
         };
-        Some(QAgent { value })
+        Some(QAgent { enabled })
     }
 }
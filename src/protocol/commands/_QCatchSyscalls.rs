@@ -16,10 +16,55 @@ impl<'a> ParseCommand<'a> for QCatchSyscalls<'a> {
         match body {
             [b':', b'0'] => Some(QCatchSyscalls::Disable),
             [b':', b'1', b';', sysno @ ..] => {
-                Some(QCatchSyscalls::Enable(ArgListHex::from_packet(sysno)?))
+                // an empty filter list catches every syscall, same as no list at all
+                if sysno.iter().all(|b| *b == b';') {
+                    Some(QCatchSyscalls::EnableAll)
+                } else {
+                    Some(QCatchSyscalls::Enable(ArgListHex::from_packet(sysno)?))
+                }
             }
             [b':', b'1'] => Some(QCatchSyscalls::EnableAll),
             _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catch_all() {
+        test_buf!(buf, b"QCatchSyscalls", b":1");
+        assert!(matches!(
+            QCatchSyscalls::from_packet(buf),
+            Some(QCatchSyscalls::EnableAll)
+        ));
+
+        test_buf!(buf, b"QCatchSyscalls", b":1;");
+        assert!(matches!(
+            QCatchSyscalls::from_packet(buf),
+            Some(QCatchSyscalls::EnableAll)
+        ));
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn catch_filtered() {
+        test_buf!(buf, b"QCatchSyscalls", b":1;3c;e7");
+        let sysno = match QCatchSyscalls::from_packet(buf) {
+            Some(QCatchSyscalls::Enable(sysno)) => sysno,
+            _ => panic!("expected a filter list"),
+        };
+        assert!(sysno.into_iter().eq([&[0x3c][..], &[0xe7][..]]));
+    }
+
+    #[test]
+    fn invalid_QCatchSyscalls() {
+        test_buf!(buf, b"QCatchSyscalls", b":1;xyz");
+        assert!(QCatchSyscalls::from_packet(buf).is_none());
+
+        test_buf!(buf, b"QCatchSyscalls", b":2");
+        assert!(QCatchSyscalls::from_packet(buf).is_none());
+    }
+}
@@ -17,3 +17,24 @@ impl<'a> ParseCommand<'a> for QDisableRandomization {
         Some(QDisableRandomization { value })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_QDisableRandomization() {
+        test_buf!(buf, b"QDisableRandomization", b":1");
+        assert!(QDisableRandomization::from_packet(buf).unwrap().value);
+
+        test_buf!(buf, b"QDisableRandomization", b":0");
+        assert!(!QDisableRandomization::from_packet(buf).unwrap().value);
+    }
+
+    #[test]
+    fn invalid_QDisableRandomization() {
+        test_buf!(buf, b"QDisableRandomization", b":2");
+
+        assert!(QDisableRandomization::from_packet(buf).is_none());
+    }
+}
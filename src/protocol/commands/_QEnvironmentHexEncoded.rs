@@ -16,9 +16,11 @@ impl<'a> ParseCommand<'a> for QEnvironmentHexEncoded<'a> {
                 let keyval = decode_hex_buf(keyval).ok()?;
                 let mut keyval = keyval.splitn(2, |b| *b == b'=');
                 let key = keyval.next()?;
-                let value = match keyval.next()? {
-                    [] => None,
-                    s => Some(s),
+                // only the first `=` separates the key from the value, as values may
+                // themselves contain `=` (or any other byte, including NUL)
+                let value = match keyval.next() {
+                    None | Some([]) => None,
+                    Some(s) => Some(s),
                 };
                 (key, value)
             }
@@ -28,3 +30,48 @@ impl<'a> ParseCommand<'a> for QEnvironmentHexEncoded<'a> {
         Some(QEnvironmentHexEncoded { key, value })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_QEnvironmentHexEncoded() {
+        // FOO=bar
+        test_buf!(buf, b"QEnvironmentHexEncoded", b":464f4f3d626172");
+
+        let pkt = QEnvironmentHexEncoded::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.key, b"FOO");
+        assert_eq!(pkt.value, Some(&b"bar"[..]));
+    }
+
+    #[test]
+    fn valid_QEnvironmentHexEncoded_value_with_eq_and_nul() {
+        // FOO=a=b\0c
+        test_buf!(buf, b"QEnvironmentHexEncoded", b":464f4f3d613d620063");
+
+        let pkt = QEnvironmentHexEncoded::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.key, b"FOO");
+        assert_eq!(pkt.value, Some(&b"a=b\0c"[..]));
+    }
+
+    #[test]
+    fn valid_QEnvironmentHexEncoded_empty_value() {
+        // FOO=
+        test_buf!(buf, b"QEnvironmentHexEncoded", b":464f4f3d");
+
+        let pkt = QEnvironmentHexEncoded::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.key, b"FOO");
+        assert_eq!(pkt.value, None);
+    }
+
+    #[test]
+    fn invalid_QEnvironmentHexEncoded() {
+        test_buf!(buf, b"QEnvironmentHexEncoded", b":nothex");
+
+        assert!(QEnvironmentHexEncoded::from_packet(buf).is_none());
+    }
+}
@@ -0,0 +1,14 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct QListThreadsInStopReply;
+
+impl<'a> ParseCommand<'a> for QListThreadsInStopReply {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        if !buf.into_body().is_empty() {
+            return None;
+        }
+        Some(QListThreadsInStopReply)
+    }
+}
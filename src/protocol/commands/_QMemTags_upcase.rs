@@ -0,0 +1,70 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct QMemTags<'a> {
+    pub addr: &'a [u8],
+    pub len: usize,
+    pub ty: i32,
+    pub tags: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for QMemTags<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        let body = match body {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.splitn_mut(4, |&b| b == b',' || b == b':');
+
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let len = decode_hex(body.next()?).ok()?;
+        // the type is a signed integer, but GDB sends it as an unsigned hex value
+        let ty = decode_hex::<u32>(body.next()?).ok()? as i32;
+        let tags = decode_hex_buf(body.next()?).ok()?;
+
+        Some(QMemTags {
+            addr,
+            len,
+            ty,
+            tags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_QMemTags() {
+        test_buf!(buf, b"QMemTags", b":1000,20:1:0a0b");
+
+        let pkt = QMemTags::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.addr, &[0x10, 0x00]);
+        assert_eq!(pkt.len, 0x20);
+        assert_eq!(pkt.ty, 1);
+        assert_eq!(pkt.tags, &[0x0a, 0x0b]);
+    }
+
+    #[test]
+    fn valid_QMemTags_negative_type() {
+        test_buf!(buf, b"QMemTags", b":1000,20:ffffffff:");
+
+        let pkt = QMemTags::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.ty, -1);
+        assert_eq!(pkt.tags, &[] as &[u8]);
+    }
+
+    #[test]
+    fn invalid_QMemTags_no_tags() {
+        test_buf!(buf, b"QMemTags", b":1000,20:1");
+
+        assert!(QMemTags::from_packet(buf).is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use super::prelude::*;
+use crate::protocol::common::lists::ArgListHex;
+
+#[derive(Debug)]
+pub struct QPassSignals<'a> {
+    pub signals: ArgListHex<'a>,
+}
+
+impl<'a> ParseCommand<'a> for QPassSignals<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        match body {
+            [b':', signals @ ..] => Some(QPassSignals {
+                signals: ArgListHex::from_packet(signals)?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_QPassSignals() {
+        test_buf!(buf, b"QPassSignals", b":e;1f;;e0");
+
+        let pkt = QPassSignals::from_packet(buf).unwrap();
+        let signals: Vec<&[u8]> = pkt.signals.into_iter().collect();
+        assert_eq!(signals, [&[0x0e][..], &[0x1f], &[0xe0]]);
+    }
+
+    #[test]
+    fn empty_QPassSignals() {
+        test_buf!(buf, b"QPassSignals", b":");
+
+        let pkt = QPassSignals::from_packet(buf).unwrap();
+        assert_eq!(pkt.signals.into_iter().count(), 0);
+    }
+
+    #[test]
+    fn invalid_QPassSignals() {
+        test_buf!(buf, b"QPassSignals", b":e;xyz");
+        assert!(QPassSignals::from_packet(buf).is_none());
+
+        // missing ':' separator
+        test_buf!(buf, b"QPassSignals", b"e");
+        assert!(QPassSignals::from_packet(buf).is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use super::prelude::*;
+use crate::protocol::common::lists::ArgListHex;
+
+#[derive(Debug)]
+pub struct QProgramSignals<'a> {
+    pub signals: ArgListHex<'a>,
+}
+
+impl<'a> ParseCommand<'a> for QProgramSignals<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        match body {
+            [b':', signals @ ..] => Some(QProgramSignals {
+                signals: ArgListHex::from_packet(signals)?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_QProgramSignals_list() {
+        test_buf!(buf, b"QProgramSignals", b":0e;1d;e");
+
+        let pkt = QProgramSignals::from_packet(buf).unwrap();
+        let signals = pkt.signals.into_iter().collect::<Vec<_>>();
+
+        assert_eq!(signals, &[&[0x0e], &[0x1d], &[0x0e]]);
+    }
+
+    #[test]
+    fn valid_QProgramSignals_empty() {
+        test_buf!(buf, b"QProgramSignals", b":");
+
+        let pkt = QProgramSignals::from_packet(buf).unwrap();
+        let signals = pkt.signals.into_iter().collect::<Vec<_>>();
+
+        assert_eq!(signals, &[] as &[&[u8]]);
+    }
+
+    #[test]
+    fn invalid_QProgramSignals_not_hex() {
+        test_buf!(buf, b"QProgramSignals", b":0e;zz");
+
+        assert!(QProgramSignals::from_packet(buf).is_none());
+    }
+}
@@ -20,3 +20,42 @@ impl<'a> ParseCommand<'a> for QSetWorkingDir<'a> {
         Some(QSetWorkingDir { dir })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_QSetWorkingDir() {
+        test_buf!(buf, b"QSetWorkingDir", b":2f746d70");
+
+        let pkt = QSetWorkingDir::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.dir, Some(&b"/tmp"[..]));
+    }
+
+    #[test]
+    fn valid_QSetWorkingDir_non_utf8() {
+        test_buf!(buf, b"QSetWorkingDir", b":2fff00");
+
+        let pkt = QSetWorkingDir::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.dir, Some(&b"/\xff\0"[..]));
+    }
+
+    #[test]
+    fn valid_QSetWorkingDir_reset() {
+        test_buf!(buf, b"QSetWorkingDir", b":");
+
+        let pkt = QSetWorkingDir::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.dir, None);
+    }
+
+    #[test]
+    fn invalid_QSetWorkingDir() {
+        test_buf!(buf, b"QSetWorkingDir", b"");
+
+        assert!(QSetWorkingDir::from_packet(buf).is_none());
+    }
+}
@@ -0,0 +1,137 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum QTDP<'a> {
+    /// `QTDP:n:addr:ena:step:pass[-]`
+    Define(QTDPDefine<'a>),
+    /// `QTDP:-n:addr:...` (i.e: actions to attach to an existing tracepoint)
+    Actions,
+}
+
+#[derive(Debug)]
+pub struct QTDPDefine<'a> {
+    pub number: usize,
+    pub addr: &'a [u8],
+    pub enabled: bool,
+    pub step: u64,
+    pub pass: u64,
+    /// The client will send further `QTDP` packets containing actions.
+    pub more: bool,
+    /// Unparsed trailing options (i.e: fast tracepoints and conditions).
+    pub options: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for QTDP<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        let body = match body {
+            [b':', b'-', ..] => return Some(QTDP::Actions),
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.splitn_mut(5, |&b| b == b':');
+        let number = decode_hex(body.next()?).ok()?;
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let enabled = match body.next()? as &[u8] {
+            b"E" => true,
+            b"D" => false,
+            _ => return None,
+        };
+        let step = decode_hex(body.next()?).ok()?;
+
+        // the pass count may be followed by `:`-separated options, and/or a
+        // trailing `-` indicating that actions will follow
+        let rest = body.next()?;
+        let (pass, options) = match rest.iter().position(|&b| b == b':') {
+            Some(idx) => {
+                let (pass, options) = rest.split_at_mut(idx);
+                (pass, &mut options[1..])
+            }
+            None => rest.split_at_mut(rest.len()),
+        };
+        let (pass, more) = match pass {
+            [pass @ .., b'-'] => (pass, true),
+            pass => (pass, false),
+        };
+        let pass = decode_hex(pass).ok()?;
+
+        Some(QTDP::Define(QTDPDefine {
+            number,
+            addr,
+            enabled,
+            step,
+            pass,
+            more,
+            options,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn as_define(pkt: Option<QTDP<'_>>) -> Option<QTDPDefine<'_>> {
+        match pkt? {
+            QTDP::Define(pkt) => Some(pkt),
+            QTDP::Actions => None,
+        }
+    }
+
+    #[test]
+    fn valid_QTDP_define() {
+        test_buf!(buf, b"QTDP", b":1:00001000:E:0:0");
+
+        let pkt = as_define(QTDP::from_packet(buf)).unwrap();
+
+        assert_eq!(pkt.number, 1);
+        assert_eq!(pkt.addr, [0x00, 0x00, 0x10, 0x00]);
+        assert!(pkt.enabled);
+        assert_eq!(pkt.step, 0);
+        assert_eq!(pkt.pass, 0);
+        assert!(!pkt.more);
+        assert!(pkt.options.is_empty());
+    }
+
+    #[test]
+    fn valid_QTDP_define_more() {
+        test_buf!(buf, b"QTDP", b":2:1234:D:3:a-");
+
+        let pkt = as_define(QTDP::from_packet(buf)).unwrap();
+
+        assert_eq!(pkt.number, 2);
+        assert!(!pkt.enabled);
+        assert_eq!(pkt.step, 3);
+        assert_eq!(pkt.pass, 0xa);
+        assert!(pkt.more);
+    }
+
+    #[test]
+    fn valid_QTDP_define_options() {
+        test_buf!(buf, b"QTDP", b":3:1234:E:0:0:X3,220100");
+
+        let pkt = as_define(QTDP::from_packet(buf)).unwrap();
+
+        assert_eq!(pkt.pass, 0);
+        assert!(!pkt.more);
+        assert_eq!(pkt.options, b"X3,220100");
+    }
+
+    #[test]
+    fn valid_QTDP_actions() {
+        test_buf!(buf, b"QTDP", b":-1:00001000:R01");
+
+        assert!(matches!(QTDP::from_packet(buf), Some(QTDP::Actions)));
+    }
+
+    #[test]
+    fn invalid_QTDP_enabled() {
+        test_buf!(buf, b"QTDP", b":1:1000:Y:0:0");
+
+        assert!(QTDP::from_packet(buf).is_none());
+    }
+}
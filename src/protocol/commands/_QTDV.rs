@@ -0,0 +1,64 @@
+use super::prelude::*;
+
+/// `QTDV:n:value:builtin:name`
+#[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct QTDV<'a> {
+    pub number: usize,
+    pub value: u64,
+    pub builtin: bool,
+    pub name: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for QTDV<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = match buf.into_body() {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.splitn_mut(4, |&b| b == b':');
+        let number = decode_hex(body.next()?).ok()?;
+        let value = decode_hex(body.next()?).ok()?;
+        let builtin = match body.next()? as &[u8] {
+            b"0" => false,
+            b"1" => true,
+            _ => return None,
+        };
+        let name = decode_hex_buf(body.next()?).ok()?;
+
+        Some(QTDV {
+            number,
+            value,
+            builtin,
+            name,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_QTDV() {
+        test_buf!(buf, b"QTDV", b":1:00000000000000a5:0:706b7473");
+
+        let pkt = QTDV::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.number, 1);
+        assert_eq!(pkt.value, 0xa5);
+        assert!(!pkt.builtin);
+        assert_eq!(pkt.name, b"pkts");
+    }
+
+    #[test]
+    fn invalid_QTDV() {
+        test_buf!(buf, b"QTDV", b":1:0:2:706b7473");
+        assert!(QTDV::from_packet(buf).is_none());
+
+        test_buf!(buf, b"QTDV", b":1:0:0");
+        assert!(QTDV::from_packet(buf).is_none());
+    }
+}
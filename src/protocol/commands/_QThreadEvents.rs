@@ -0,0 +1,18 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct QThreadEvents {
+    pub enabled: bool,
+}
+
+impl<'a> ParseCommand<'a> for QThreadEvents {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let enabled = match buf.into_body() as &[u8] {
+            b":0" => false,
+            b":1" => true,
+            _ => return None,
+        };
+        Some(QThreadEvents { enabled })
+    }
+}
@@ -0,0 +1,14 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct QThreadSuffixSupported;
+
+impl<'a> ParseCommand<'a> for QThreadSuffixSupported {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        if !buf.into_body().is_empty() {
+            return None;
+        }
+        Some(QThreadSuffixSupported)
+    }
+}
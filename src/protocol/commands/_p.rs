@@ -1,12 +1,33 @@
 use super::prelude::*;
+use crate::protocol::common::thread_id::ThreadId;
 
 #[derive(Debug)]
 pub struct p<'a> {
     pub reg_id: usize,
+    /// Explicit thread-id, as passed via a `;thread:<tid>;` suffix (see
+    /// `QThreadSuffixSupported`).
+    pub thread: Option<ThreadId>,
 
     pub buf: &'a mut [u8],
 }
 
+/// Parse the optional `;thread:<tid>;` suffix appended to register access
+/// packets once `QThreadSuffixSupported` has been negotiated.
+///
+/// `suffix` is everything following the first `;` in the packet body.
+pub(crate) fn parse_thread_suffix(suffix: &[u8]) -> Option<ThreadId> {
+    let tid = match suffix {
+        [b't', b'h', b'r', b'e', b'a', b'd', b':', tid @ ..] => tid,
+        _ => return None,
+    };
+    let tid = match tid {
+        [tid @ .., b';'] => tid,
+        tid => tid,
+    };
+
+    ThreadId::try_from(tid).ok()
+}
+
 impl<'a> ParseCommand<'a> for p<'a> {
     #[inline(always)]
     fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
@@ -17,8 +38,56 @@ impl<'a> ParseCommand<'a> for p<'a> {
             return None;
         }
 
-        let reg_id = decode_hex(body).ok()?;
+        let mut body = body.splitn(2, |&b| b == b';');
+        let reg_id = decode_hex(body.next()?).ok()?;
+        let thread = match body.next() {
+            Some(suffix) => Some(parse_thread_suffix(suffix)?),
+            None => None,
+        };
+
+        Some(p {
+            reg_id,
+            thread,
+            buf,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::common::thread_id::IdKind;
+    use core::num::NonZeroUsize;
+
+    #[test]
+    fn valid_p() {
+        test_buf!(buf, b"p", b"1f");
+
+        let pkt = p::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.reg_id, 0x1f);
+        assert_eq!(pkt.thread, None);
+    }
+
+    #[test]
+    fn valid_p_thread_suffix() {
+        test_buf!(buf, b"p", b"1f;thread:2a;");
+
+        let pkt = p::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.reg_id, 0x1f);
+        assert_eq!(
+            pkt.thread,
+            Some(ThreadId {
+                pid: None,
+                tid: IdKind::WithId(NonZeroUsize::new(0x2a).unwrap())
+            })
+        );
+    }
 
-        Some(p { reg_id, buf })
+    #[test]
+    fn invalid_p_suffix() {
+        test_buf!(buf, b"p", b"1f;core:2;");
+        assert!(p::from_packet(buf).is_none());
     }
 }
@@ -1,18 +1,34 @@
+use super::_p::parse_thread_suffix;
 use super::prelude::*;
+use crate::protocol::common::thread_id::ThreadId;
 
 #[derive(Debug)]
 pub struct P<'a> {
     pub reg_id: usize,
     pub val: &'a [u8],
+    /// Explicit thread-id, as passed via a `;thread:<tid>;` suffix (see
+    /// `QThreadSuffixSupported`).
+    pub thread: Option<ThreadId>,
 }
 
 impl<'a> ParseCommand<'a> for P<'a> {
     #[inline(always)]
     fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
         let body = buf.into_body();
-        let mut body = body.split_mut(|&b| b == b'=');
-        let reg_id = decode_hex(body.next()?).ok()?;
-        let val = decode_hex_buf(body.next()?).ok()?;
-        Some(P { reg_id, val })
+        let mut body = body.splitn_mut(2, |&b| b == b';');
+        let reg = body.next()?;
+        let thread = match body.next() {
+            Some(suffix) => Some(parse_thread_suffix(suffix)?),
+            None => None,
+        };
+
+        let mut reg = reg.split_mut(|&b| b == b'=');
+        let reg_id = decode_hex(reg.next()?).ok()?;
+        let val = decode_hex_buf(reg.next()?).ok()?;
+        Some(P {
+            reg_id,
+            val,
+            thread,
+        })
     }
 }
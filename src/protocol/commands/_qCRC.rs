@@ -0,0 +1,49 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qCRC<'a> {
+    pub addr: &'a [u8],
+    pub len: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for qCRC<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        let body = match body {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.splitn_mut(2, |&b| b == b',');
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let len = decode_hex_buf(body.next()?).ok()?;
+
+        Some(qCRC { addr, len })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_qCRC() {
+        test_buf!(buf, b"qCRC", b":8000,1f0");
+
+        let pkt = qCRC::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.addr, &[0x80, 0x00]);
+        assert_eq!(pkt.len, &[0x01, 0xf0]);
+    }
+
+    #[test]
+    fn invalid_qCRC() {
+        test_buf!(buf, b"qCRC", b":8000");
+        assert!(qCRC::from_packet(buf).is_none());
+
+        test_buf!(buf, b"qCRC", b"8000,10");
+        assert!(qCRC::from_packet(buf).is_none());
+    }
+}
@@ -0,0 +1,46 @@
+use super::prelude::*;
+use crate::protocol::common::thread_id::ThreadId;
+use crate::protocol::ConcreteThreadId;
+
+#[derive(Debug)]
+pub struct qGetTIBAddr {
+    pub id: ConcreteThreadId,
+}
+
+impl<'a> ParseCommand<'a> for qGetTIBAddr {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        let body = match body {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let id = ConcreteThreadId::try_from(ThreadId::try_from(&*body).ok()?).ok()?;
+
+        Some(qGetTIBAddr { id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_qGetTIBAddr() {
+        test_buf!(buf, b"qGetTIBAddr", b":1a");
+
+        let pkt = qGetTIBAddr::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.id.pid, None);
+        assert_eq!(pkt.id.tid.get(), 0x1a);
+    }
+
+    #[test]
+    fn invalid_qGetTIBAddr_any() {
+        test_buf!(buf, b"qGetTIBAddr", b":0");
+
+        assert!(qGetTIBAddr::from_packet(buf).is_none());
+    }
+}
@@ -0,0 +1,60 @@
+use super::prelude::*;
+use crate::protocol::common::thread_id::ThreadId;
+use crate::protocol::ConcreteThreadId;
+
+#[derive(Debug)]
+pub struct qGetTLSAddr<'a> {
+    pub id: ConcreteThreadId,
+    pub offset: &'a [u8],
+    pub lm: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for qGetTLSAddr<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        let body = match body {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.splitn_mut(3, |&b| b == b',');
+        let id = ConcreteThreadId::try_from(ThreadId::try_from(&*body.next()?).ok()?).ok()?;
+        let offset = decode_hex_buf(body.next()?).ok()?;
+        let lm = decode_hex_buf(body.next()?).ok()?;
+
+        Some(qGetTLSAddr { id, offset, lm })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_qGetTLSAddr() {
+        test_buf!(buf, b"qGetTLSAddr", b":p2.3,10,7ffff7d9");
+
+        let pkt = qGetTLSAddr::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.id.pid.map(|pid| pid.get()), Some(2));
+        assert_eq!(pkt.id.tid.get(), 3);
+        assert_eq!(pkt.offset, [0x10]);
+        assert_eq!(pkt.lm, [0x7f, 0xff, 0xf7, 0xd9]);
+    }
+
+    #[test]
+    fn invalid_qGetTLSAddr_all() {
+        test_buf!(buf, b"qGetTLSAddr", b":-1,10,0");
+
+        assert!(qGetTLSAddr::from_packet(buf).is_none());
+    }
+
+    #[test]
+    fn invalid_qGetTLSAddr_missing_lm() {
+        test_buf!(buf, b"qGetTLSAddr", b":1,10");
+
+        assert!(qGetTLSAddr::from_packet(buf).is_none());
+    }
+}
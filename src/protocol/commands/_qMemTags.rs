@@ -0,0 +1,84 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qMemTags<'a> {
+    pub addr: &'a [u8],
+    pub len: usize,
+    pub ty: i32,
+
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> ParseCommand<'a> for qMemTags<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        // See the comment in `_m.rs` for why the decoded `addr` is left in place
+        // at the start of the packet buffer.
+        let (buf, body_range) = buf.into_raw_buf();
+        let body = buf.get_mut(body_range.start..body_range.end)?;
+
+        let body = match body {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.split_mut(|&b| b == b',' || b == b':');
+
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let addr_len = addr.len();
+        let len = decode_hex(body.next()?).ok()?;
+        // the type is a signed integer, but GDB sends it as an unsigned hex value
+        let ty = decode_hex::<u32>(body.next()?).ok()? as i32;
+
+        let addr_start = body_range.start + 1;
+
+        // ensures that `split_at_mut` doesn't panic
+        if buf.len() < addr_start + addr_len {
+            return None;
+        }
+
+        let (addr, buf) = buf.split_at_mut(addr_start + addr_len);
+        let addr = addr.get(addr_start..)?;
+
+        Some(qMemTags { addr, len, ty, buf })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_qMemTags() {
+        test_buf!(buf, b"qMemTags", b":1000,20:1");
+
+        let pkt = qMemTags::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.addr, &[0x10, 0x00]);
+        assert_eq!(pkt.len, 0x20);
+        assert_eq!(pkt.ty, 1);
+    }
+
+    #[test]
+    fn valid_qMemTags_negative_type() {
+        test_buf!(buf, b"qMemTags", b":1000,20:ffffffff");
+
+        let pkt = qMemTags::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.ty, -1);
+    }
+
+    #[test]
+    fn invalid_qMemTags_no_type() {
+        test_buf!(buf, b"qMemTags", b":1000,20");
+
+        assert!(qMemTags::from_packet(buf).is_none());
+    }
+
+    #[test]
+    fn invalid_qMemTags_no_colon() {
+        test_buf!(buf, b"qMemTags", b"1000,20:1");
+
+        assert!(qMemTags::from_packet(buf).is_none());
+    }
+}
@@ -0,0 +1,60 @@
+use super::prelude::*;
+use crate::protocol::common::hex::decode_bin_buf;
+
+#[derive(Debug)]
+pub struct qSearchMemory<'a> {
+    pub addr: &'a [u8],
+    pub len: &'a [u8],
+    pub pattern: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for qSearchMemory<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        let body = match body {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.splitn_mut(3, |&b| b == b';');
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let len = decode_hex_buf(body.next()?).ok()?;
+        let pattern = decode_bin_buf(body.next()?)?;
+
+        Some(qSearchMemory { addr, len, pattern })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_qSearch_memory() {
+        test_buf!(buf, b"qSearch:memory", b":1000;1f0;abc");
+
+        let pkt = qSearchMemory::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.addr, &[0x10, 0x00]);
+        assert_eq!(pkt.len, &[0x01, 0xf0]);
+        assert_eq!(pkt.pattern, b"abc");
+    }
+
+    #[test]
+    fn valid_qSearch_memory_escaped_pattern() {
+        test_buf!(buf, b"qSearch:memory", b":0;10;a}\x03b");
+
+        let pkt = qSearchMemory::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.pattern, b"a#b");
+    }
+
+    #[test]
+    fn invalid_qSearch_memory() {
+        test_buf!(buf, b"qSearch:memory", b":nothex;10;abc");
+
+        assert!(qSearchMemory::from_packet(buf).is_none());
+    }
+}
@@ -32,17 +32,25 @@ impl<'a> Features<'a> {
                 b'+' | b'-' => {
                     let feature = match &s[..s.len() - 1] {
                         b"multiprocess" => Feature::Multiprocess,
+                        b"swbreak" => Feature::SwBreak,
+                        b"hwbreak" => Feature::HwBreak,
+                        b"fork-events" => Feature::ForkEvents,
+                        b"vfork-events" => Feature::VforkEvents,
+                        b"exec-events" => Feature::ExecEvents,
                         // TODO: implementing other features will require IDET plumbing
                         _ => return Ok(None),
                     };
                     Ok(Some((feature, c == b'+')))
                 }
-                _ => {
+                _ => match s.iter().position(|b| *b == b'=') {
+                    Some(idx) if &s[..idx] == b"PacketSize" => {
+                        let size = decode_hex(&s[idx + 1..]).map_err(drop)?;
+                        Ok(Some((Feature::PacketSize(size), true)))
+                    }
                     // TODO: add support for "xmlRegisters="
-                    // that's the only feature packet that uses an '=', and AFAIK, it's not really
-                    // used anymore...
-                    Ok(None)
-                }
+                    // AFAIK, it's not really used anymore...
+                    _ => Ok(None),
+                },
             },
         })
     }
@@ -51,4 +59,11 @@ impl<'a> Features<'a> {
 #[derive(Debug)]
 pub enum Feature {
     Multiprocess,
+    SwBreak,
+    HwBreak,
+    ForkEvents,
+    VforkEvents,
+    ExecEvents,
+    /// The largest packet the client is willing to accept.
+    PacketSize(usize),
 }
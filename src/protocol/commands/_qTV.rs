@@ -0,0 +1,18 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct qTV {
+    pub number: usize,
+}
+
+impl<'a> ParseCommand<'a> for qTV {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        match buf.into_body() {
+            [b':', number @ ..] => Some(qTV {
+                number: decode_hex(number).ok()?,
+            }),
+            _ => None,
+        }
+    }
+}
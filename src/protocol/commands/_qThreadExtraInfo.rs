@@ -29,3 +29,35 @@ impl<'a> ParseCommand<'a> for qThreadExtraInfo<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_qThreadExtraInfo() {
+        test_buf!(buf, b"qThreadExtraInfo", b",1f");
+
+        let pkt = qThreadExtraInfo::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.id.pid, None);
+        assert_eq!(pkt.id.tid.get(), 0x1f);
+    }
+
+    #[test]
+    fn valid_qThreadExtraInfo_multiprocess() {
+        test_buf!(buf, b"qThreadExtraInfo", b",p2.3");
+
+        let pkt = qThreadExtraInfo::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.id.pid.map(|pid| pid.get()), Some(2));
+        assert_eq!(pkt.id.tid.get(), 3);
+    }
+
+    #[test]
+    fn invalid_qThreadExtraInfo_all() {
+        test_buf!(buf, b"qThreadExtraInfo", b",-1");
+
+        assert!(qThreadExtraInfo::from_packet(buf).is_none());
+    }
+}
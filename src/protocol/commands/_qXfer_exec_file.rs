@@ -21,3 +21,35 @@ impl<'a> ParseAnnex<'a> for ExecFileAnnex {
         Some(ExecFileAnnex { pid })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_qXferExecFileRead_pids() {
+        test_buf!(buf, b"qXfer:exec-file:read", b":1:0,fff");
+        let pkt = qXferExecFileRead::from_packet(buf).unwrap();
+        assert_eq!(pkt.annex.pid.map(|pid| pid.get()), Some(1));
+        assert_eq!(pkt.offset, 0);
+        assert_eq!(pkt.length, 0xfff);
+
+        test_buf!(buf, b"qXfer:exec-file:read", b":2a:10,fff");
+        let pkt = qXferExecFileRead::from_packet(buf).unwrap();
+        assert_eq!(pkt.annex.pid.map(|pid| pid.get()), Some(0x2a));
+        assert_eq!(pkt.offset, 0x10);
+    }
+
+    #[test]
+    fn valid_qXferExecFileRead_current() {
+        test_buf!(buf, b"qXfer:exec-file:read", b"::0,fff");
+        let pkt = qXferExecFileRead::from_packet(buf).unwrap();
+        assert_eq!(pkt.annex.pid, None);
+    }
+
+    #[test]
+    fn invalid_qXferExecFileRead_pid_zero() {
+        test_buf!(buf, b"qXfer:exec-file:read", b":0:0,fff");
+        assert!(qXferExecFileRead::from_packet(buf).is_none());
+    }
+}
@@ -15,3 +15,31 @@ impl<'a> ParseAnnex<'a> for FeaturesAnnex<'a> {
         Some(FeaturesAnnex { name: buf })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::commands::ParseCommand;
+    use crate::protocol::packet::PacketBuf;
+
+    #[test]
+    fn valid_qXferFeaturesRead_target_xml() {
+        test_buf!(buf, b"qXfer:features:read", b":target.xml:0,ffb");
+
+        let pkt = qXferFeaturesRead::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.annex.name, b"target.xml");
+        assert_eq!(pkt.offset, 0);
+        assert_eq!(pkt.length, 0xffb);
+    }
+
+    #[test]
+    fn valid_qXferFeaturesRead_included_annex() {
+        test_buf!(buf, b"qXfer:features:read", b":64bit-sse.xml:7fe,ffb");
+
+        let pkt = qXferFeaturesRead::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.annex.name, b"64bit-sse.xml");
+        assert_eq!(pkt.offset, 0x7fe);
+    }
+}
@@ -0,0 +1,17 @@
+// use super::prelude::*; // unused
+use crate::protocol::common::qxfer::ParseAnnex;
+use crate::protocol::common::qxfer::QXferReadBase;
+
+pub type qXferOsDataRead<'a> = QXferReadBase<'a, OsDataAnnex<'a>>;
+
+#[derive(Debug)]
+pub struct OsDataAnnex<'a> {
+    pub kind: &'a [u8],
+}
+
+impl<'a> ParseAnnex<'a> for OsDataAnnex<'a> {
+    #[inline(always)]
+    fn from_buf(buf: &'a [u8]) -> Option<Self> {
+        Some(OsDataAnnex { kind: buf })
+    }
+}
@@ -15,3 +15,51 @@ impl<'a> ParseCommand<'a> for T {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::common::thread_id::IdKind;
+    use core::num::NonZeroUsize;
+
+    fn id(n: usize) -> IdKind {
+        IdKind::WithId(NonZeroUsize::new(n).unwrap())
+    }
+
+    #[test]
+    fn valid_T() {
+        test_buf!(buf, b"T", b"1f");
+
+        let pkt = T::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.thread.pid, None);
+        assert_eq!(pkt.thread.tid, id(0x1f));
+    }
+
+    #[test]
+    fn valid_T_multiprocess() {
+        test_buf!(buf, b"T", b"p2a.3");
+
+        let pkt = T::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.thread.pid, Some(id(0x2a)));
+        assert_eq!(pkt.thread.tid, id(3));
+    }
+
+    #[test]
+    fn valid_T_multiprocess_any() {
+        test_buf!(buf, b"T", b"p-1.0");
+
+        let pkt = T::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.thread.pid, Some(IdKind::All));
+        assert_eq!(pkt.thread.tid, IdKind::Any);
+    }
+
+    #[test]
+    fn invalid_T() {
+        test_buf!(buf, b"T", b"p1.zz");
+
+        assert!(T::from_packet(buf).is_none());
+    }
+}
@@ -163,7 +163,12 @@ impl<'a> VContKind<'a> {
             [b'S', sig @ ..] => StepWithSig(Signal(decode_hex(sig).ok()?)),
             [b'r', range @ ..] => {
                 let mut range = range.split(|b| *b == b',');
-                RangeStep(HexString(range.next()?), HexString(range.next()?))
+                let start = range.next()?;
+                let end = range.next()?;
+                if range.next().is_some() {
+                    return None;
+                }
+                RangeStep(HexString(start), HexString(end))
             }
             _ => return None,
         };
@@ -194,3 +199,62 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actions<'a>(pkt: &'a vCont<'a>) -> Option<&'a Actions<'a>> {
+        match pkt {
+            vCont::Actions(actions) => Some(actions),
+            vCont::Query => None,
+        }
+    }
+
+    #[test]
+    fn valid_vCont_query() {
+        test_buf!(buf, b"vCont", b"?");
+
+        assert!(matches!(vCont::from_packet(buf), Some(vCont::Query)));
+    }
+
+    #[test]
+    fn valid_vCont_range_step() {
+        test_buf!(buf, b"vCont", b";r1000,1010:p1.2;c");
+
+        let pkt = vCont::from_packet(buf).unwrap();
+        let actions: Vec<_> = actions(&pkt).unwrap().iter().collect();
+        assert_eq!(actions.len(), 2);
+
+        let range_step = actions[0].unwrap();
+        let (start, end) = match range_step.kind {
+            VContKind::RangeStep(start, end) => Some((start, end)),
+            _ => None,
+        }
+        .unwrap();
+        assert_eq!(start.0, b"1000");
+        assert_eq!(end.0, b"1010");
+        let thread = range_step.thread.unwrap();
+        assert_eq!(thread.tid, crate::protocol::SpecificIdKind::WithId(core::num::NonZeroUsize::new(2).unwrap()));
+
+        let cont = actions[1].unwrap();
+        assert!(matches!(cont.kind, VContKind::Continue));
+        assert!(cont.thread.is_none());
+    }
+
+    #[test]
+    fn invalid_vCont_range_step() {
+        test_buf!(buf, b"vCont", b";r1000:1");
+
+        let pkt = vCont::from_packet(buf).unwrap();
+        assert!(actions(&pkt).unwrap().iter().all(|act| act.is_none()));
+    }
+
+    #[test]
+    fn invalid_vCont_range_step_extra_field() {
+        test_buf!(buf, b"vCont", b";r1000,1010,1020:1");
+
+        let pkt = vCont::from_packet(buf).unwrap();
+        assert!(actions(&pkt).unwrap().iter().all(|act| act.is_none()));
+    }
+}
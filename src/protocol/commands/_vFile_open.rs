@@ -33,3 +33,49 @@ impl<'a> ParseCommand<'a> for vFileOpen<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_vFile_open() {
+        // open("/tmp/x", O_CREAT | O_WRONLY | O_TRUNC, 0644)
+        test_buf!(buf, b"vFile:open", b":2f746d702f78,601,1a4");
+
+        let pkt = vFileOpen::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.filename, b"/tmp/x");
+        assert_eq!(
+            pkt.flags,
+            HostIoOpenFlags::O_CREAT | HostIoOpenFlags::O_WRONLY | HostIoOpenFlags::O_TRUNC
+        );
+        assert_eq!(
+            pkt.mode,
+            HostIoOpenMode::S_IRUSR
+                | HostIoOpenMode::S_IWUSR
+                | HostIoOpenMode::S_IRGRP
+                | HostIoOpenMode::S_IROTH
+        );
+    }
+
+    #[test]
+    fn valid_vFile_open_rdonly() {
+        test_buf!(buf, b"vFile:open", b":2f746d702f78,0,0");
+
+        let pkt = vFileOpen::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.flags, HostIoOpenFlags::O_RDONLY);
+        assert!(pkt.mode.is_empty());
+    }
+
+    #[test]
+    fn invalid_vFile_open() {
+        // 0x4 is not a protocol-defined open flag
+        test_buf!(buf, b"vFile:open", b":2f746d702f78,4,0");
+        assert!(vFileOpen::from_packet(buf).is_none());
+
+        test_buf!(buf, b"vFile:open", b":2f746d702f78,601");
+        assert!(vFileOpen::from_packet(buf).is_none());
+    }
+}
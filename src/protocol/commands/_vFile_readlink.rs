@@ -11,14 +11,19 @@ impl<'a> ParseCommand<'a> for vFileReadlink<'a> {
     #[inline(always)]
     fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
         let (buf, body_range) = buf.into_raw_buf();
-        // TODO: rewrite to avoid panic
-        let (body, buf) = buf[body_range.start..].split_at_mut(body_range.end - body_range.start);
+        let buf = buf.get_mut(body_range.start..)?;
 
-        if body.is_empty() {
+        // ensures that `split_at_mut` doesn't panic
+        let body_len = body_range.end - body_range.start;
+        if buf.len() < body_len {
             return None;
         }
 
+        let (body, buf) = buf.split_at_mut(body_len);
+
         match body {
+            // the filename is passed through as-is, without truncating at any
+            // embedded NUL bytes.
             [b':', body @ ..] => {
                 let filename = decode_hex_buf(body).ok()?;
                 Some(vFileReadlink { filename, buf })
@@ -27,3 +32,33 @@ impl<'a> ParseCommand<'a> for vFileReadlink<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_vFile_readlink() {
+        test_buf!(buf, b"vFile:readlink", b":2f6c6962");
+
+        let pkt = vFileReadlink::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.filename, b"/lib");
+    }
+
+    #[test]
+    fn valid_vFile_readlink_embedded_nul() {
+        test_buf!(buf, b"vFile:readlink", b":2f00782f");
+
+        let pkt = vFileReadlink::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.filename, b"/\0x/");
+    }
+
+    #[test]
+    fn invalid_vFile_readlink() {
+        test_buf!(buf, b"vFile:readlink", b"");
+
+        assert!(vFileReadlink::from_packet(buf).is_none());
+    }
+}
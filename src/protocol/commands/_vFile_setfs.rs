@@ -26,3 +26,26 @@ impl<'a> ParseCommand<'a> for vFileSetfs {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_vFile_setfs_stub() {
+        test_buf!(buf, b"vFile:setfs", b":0");
+
+        let pkt = vFileSetfs::from_packet(buf).unwrap();
+
+        assert!(matches!(pkt.fs, FsKind::Stub));
+    }
+
+    #[test]
+    fn valid_vFile_setfs_pid() {
+        test_buf!(buf, b"vFile:setfs", b":1f");
+
+        let pkt = vFileSetfs::from_packet(buf).unwrap();
+
+        assert!(matches!(pkt.fs, FsKind::Pid(pid) if pid.get() == 0x1f));
+    }
+}
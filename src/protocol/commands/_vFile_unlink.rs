@@ -22,3 +22,24 @@ impl<'a> ParseCommand<'a> for vFileUnlink<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_vFile_unlink() {
+        test_buf!(buf, b"vFile:unlink", b":2f746d70");
+
+        let pkt = vFileUnlink::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.filename, b"/tmp");
+    }
+
+    #[test]
+    fn invalid_vFile_unlink() {
+        test_buf!(buf, b"vFile:unlink", b":nothex");
+
+        assert!(vFileUnlink::from_packet(buf).is_none());
+    }
+}
@@ -0,0 +1,46 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct vFlashErase<'a> {
+    pub addr: &'a [u8],
+    pub length: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for vFlashErase<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        let body = match body {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.splitn_mut(2, |&b| b == b',');
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let length = decode_hex_buf(body.next()?).ok()?;
+
+        Some(vFlashErase { addr, length })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_vFlashErase() {
+        test_buf!(buf, b"vFlashErase", b":08000000,800");
+
+        let pkt = vFlashErase::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.addr, &[0x08, 0x00, 0x00, 0x00]);
+        assert_eq!(pkt.length, &[0x08, 0x00]);
+    }
+
+    #[test]
+    fn invalid_vFlashErase() {
+        test_buf!(buf, b"vFlashErase", b":08000000");
+        assert!(vFlashErase::from_packet(buf).is_none());
+    }
+}
@@ -0,0 +1,47 @@
+use super::prelude::*;
+use crate::protocol::common::hex::decode_bin_buf;
+
+#[derive(Debug)]
+pub struct vFlashWrite<'a> {
+    pub addr: &'a [u8],
+    pub data: &'a [u8],
+}
+
+impl<'a> ParseCommand<'a> for vFlashWrite<'a> {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        let body = buf.into_body();
+
+        let body = match body {
+            [b':', body @ ..] => body,
+            _ => return None,
+        };
+
+        let mut body = body.splitn_mut(2, |&b| b == b':');
+        let addr = decode_hex_buf(body.next()?).ok()?;
+        let data = decode_bin_buf(body.next()?)?;
+
+        Some(vFlashWrite { addr, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_vFlashWrite() {
+        test_buf!(buf, b"vFlashWrite", b":08000000:\x01\x02:}\x03");
+
+        let pkt = vFlashWrite::from_packet(buf).unwrap();
+
+        assert_eq!(pkt.addr, &[0x08, 0x00, 0x00, 0x00]);
+        assert_eq!(pkt.data, b"\x01\x02:#");
+    }
+
+    #[test]
+    fn invalid_vFlashWrite() {
+        test_buf!(buf, b"vFlashWrite", b":08000000");
+        assert!(vFlashWrite::from_packet(buf).is_none());
+    }
+}
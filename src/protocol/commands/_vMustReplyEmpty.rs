@@ -0,0 +1,14 @@
+use super::prelude::*;
+
+#[derive(Debug)]
+pub struct vMustReplyEmpty;
+
+impl<'a> ParseCommand<'a> for vMustReplyEmpty {
+    #[inline(always)]
+    fn from_packet(buf: PacketBuf<'a>) -> Option<Self> {
+        if !buf.into_body().is_empty() {
+            return None;
+        }
+        Some(vMustReplyEmpty)
+    }
+}
@@ -32,23 +32,12 @@ impl<'a> ParseCommand<'a> for vRun<'a> {
 mod tests {
     use super::*;
 
-    macro_rules! test_buf {
-        ($bufname:ident, $body:literal) => {
-            let mut test = $body.to_vec();
-            let mut buf = PacketBuf::new_with_raw_body(&mut test).unwrap();
-            if !buf.strip_prefix(b"vRun") {
-                panic!("invalid test");
-            }
-            let $bufname = buf;
-        };
-    }
-
     #[test]
     fn valid_vRun_foobarbaz() {
-        test_buf!(buf, b"vRun;;666f6f;626172;62617a");
+        test_buf!(buf, b"vRun", b";;666f6f;626172;62617a");
 
         let pkt = vRun::from_packet(buf).unwrap();
-        let args = pkt.args.into_iter().collect::<Vec<_>>();
+        let args = pkt.args.into_program_args().collect::<Vec<_>>();
 
         assert_eq!(pkt.filename, None);
         assert_eq!(args, &[b"foo", b"bar", b"baz"]);
@@ -56,10 +45,10 @@ mod tests {
 
     #[test]
     fn valid_vRun_noname() {
-        test_buf!(buf, b"vRun;");
+        test_buf!(buf, b"vRun", b";");
 
         let pkt = vRun::from_packet(buf).unwrap();
-        let args = pkt.args.into_iter().collect::<Vec<_>>();
+        let args = pkt.args.into_program_args().collect::<Vec<_>>();
 
         assert_eq!(pkt.filename, None);
         assert_eq!(args, &[] as &[&[u8]]);
@@ -67,10 +56,10 @@ mod tests {
 
     #[test]
     fn valid_vRun_noargs() {
-        test_buf!(buf, b"vRun;74657374");
+        test_buf!(buf, b"vRun", b";74657374");
 
         let pkt = vRun::from_packet(buf).unwrap();
-        let args = pkt.args.into_iter().collect::<Vec<_>>();
+        let args = pkt.args.into_program_args().collect::<Vec<_>>();
 
         assert_eq!(pkt.filename, Some(&b"test"[..]));
         assert_eq!(args, &[] as &[&[u8]]);
@@ -78,25 +67,47 @@ mod tests {
 
     #[test]
     fn valid_vRun_args() {
-        test_buf!(buf, b"vRun;74657374;74657374");
+        test_buf!(buf, b"vRun", b";74657374;74657374");
 
         let pkt = vRun::from_packet(buf).unwrap();
-        let args = pkt.args.into_iter().collect::<Vec<_>>();
+        let args = pkt.args.into_program_args().collect::<Vec<_>>();
 
         assert_eq!(pkt.filename, Some(&b"test"[..]));
         assert_eq!(args, &[b"test"]);
     }
 
+    #[test]
+    fn valid_vRun_noname_args() {
+        test_buf!(buf, b"vRun", b";;74657374");
+
+        let pkt = vRun::from_packet(buf).unwrap();
+        let args = pkt.args.into_program_args().collect::<Vec<_>>();
+
+        assert_eq!(pkt.filename, None);
+        assert_eq!(args, &[b"test"]);
+    }
+
+    #[test]
+    fn valid_vRun_empty_args() {
+        test_buf!(buf, b"vRun", b";74657374;;666f6f;");
+
+        let pkt = vRun::from_packet(buf).unwrap();
+        let args = pkt.args.into_program_args().collect::<Vec<_>>();
+
+        assert_eq!(pkt.filename, Some(&b"test"[..]));
+        assert_eq!(args, &[&b""[..], &b"foo"[..], &b""[..]]);
+    }
+
     #[test]
     fn invalid_vRun_args() {
-        test_buf!(buf, b"vRun;74657374;nothex");
+        test_buf!(buf, b"vRun", b";74657374;nothex");
 
         assert!(vRun::from_packet(buf).is_none());
     }
 
     #[test]
     fn invalid_vRun() {
-        test_buf!(buf, b"vRun;nothex;nothex");
+        test_buf!(buf, b"vRun", b";nothex;nothex");
 
         assert!(vRun::from_packet(buf).is_none());
     }
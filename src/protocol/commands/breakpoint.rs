@@ -11,8 +11,9 @@ use crate::protocol::common::hex::decode_hex_buf;
 //                          |
 //                  BytecodeBreakpoint
 //
-// If the target does not implement the `Agent` extension, only the
-// `BasicBreakpoint` part is parsed, which helps cut down on binary bloat.
+// If the target implements neither the `ConditionalBreakpoints` nor the
+// `BreakpointCommands` extension, only the `BasicBreakpoint` part is parsed,
+// which helps cut down on binary bloat.
 
 #[derive(Debug)]
 pub struct BasicBreakpoint<'a> {
@@ -33,7 +34,6 @@ impl<'a> BasicBreakpoint<'a> {
     }
 }
 
-#[allow(dead_code)] // Bytecode hasn't yet been plumbed all the way through
 #[derive(Debug)]
 pub struct BytecodeBreakpoint<'a> {
     pub base: BasicBreakpoint<'a>,
@@ -51,29 +51,32 @@ impl<'a> BytecodeBreakpoint<'a> {
         let mut cmds_persist = None;
 
         if let Some(rest) = body.next() {
-            let mut s = rest.split_mut(|b| *b == b':');
-            let (raw_conds, raw_cmds) = match (s.next(), s.next()) {
-                (Some(a), Some(b)) => (Some(strip_suffix_mut(a, b";cmds")?), Some(b)),
-                (Some(a), None) => {
-                    if a.starts_with(b"cmds") {
-                        (None, Some(a))
-                    } else {
-                        (Some(a), None)
-                    }
-                }
-                _ => return None,
+            // bytecode lists never contain a ':', so it unambiguously marks the start
+            // of the command list
+            let mut s = rest.splitn_mut(2, |b| *b == b':');
+            let (raw_conds, raw_cmds) = match (s.next()?, s.next()) {
+                (a, None) => (Some(a), None),
+                (a, Some(b)) if a == b"cmds" => (None, Some(b)),
+                (a, Some(b)) => (Some(strip_suffix_mut(a, b";cmds")?), Some(b)),
             };
 
             if let Some(raw_conds) = raw_conds {
-                conds = Some(BytecodeList(raw_conds));
+                let raw_conds = BytecodeList(raw_conds);
+                if !raw_conds.is_valid() {
+                    return None;
+                }
+                conds = Some(raw_conds);
             }
 
             if let Some(raw_cmds) = raw_cmds {
-                let mut raw_cmds = raw_cmds.split_mut(|b| *b == b',');
+                let mut raw_cmds = raw_cmds.splitn_mut(2, |b| *b == b',');
                 let raw_persist = decode_hex::<u8>(raw_cmds.next()?).ok()? != 0;
-                let raw_cmds = raw_cmds.next()?;
+                let raw_cmds = BytecodeList(raw_cmds.next()?);
+                if !raw_cmds.is_valid() {
+                    return None;
+                }
 
-                cmds_persist = Some((BytecodeList(raw_cmds), raw_persist));
+                cmds_persist = Some((raw_cmds, raw_persist));
             }
         }
 
@@ -104,13 +107,111 @@ where
 pub struct BytecodeList<'a>(&'a mut [u8]);
 
 impl<'a> BytecodeList<'a> {
-    #[allow(dead_code)]
-    pub fn into_iter(self) -> impl Iterator<Item = Option<&'a [u8]>> + 'a {
-        self.0.split_mut(|b| *b == b'X').skip(1).map(|s| {
-            let mut s = s.split_mut(|b| *b == b',');
+    /// Check that the list is a (possibly empty) sequence of well-formed
+    /// `X<len>,<hex bytecode>` expressions, without decoding it.
+    fn is_valid(&self) -> bool {
+        let mut exprs = self.0.split(|b| *b == b'X');
+        // the list must start with an expression (or be empty)
+        if !exprs.next().is_some_and(|s| s.is_empty()) {
+            return false;
+        }
+
+        exprs.all(|s| {
+            let mut s = s.splitn(2, |b| *b == b',');
+            let len = match s.next().map(decode_hex::<usize>) {
+                Some(Ok(len)) => len,
+                _ => return false,
+            };
+            match s.next() {
+                Some(code) => {
+                    code.len() == len * 2 && code.iter().all(|b| b.is_ascii_hexdigit())
+                }
+                None => false,
+            }
+        })
+    }
+
+    /// Decode each bytecode expression in the list.
+    ///
+    /// Lists are validated when parsed, so malformed expressions are skipped.
+    pub fn into_iter(self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        self.0.split_mut(|b| *b == b'X').skip(1).filter_map(|s| {
+            let mut s = s.splitn_mut(2, |b| *b == b',');
             let _len = s.next()?;
             let code = decode_hex_buf(s.next()?).ok()?;
             Some(code as &[u8])
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytecode_breakpoint_conds() {
+        let mut body = b"0,1000,4;X3,220027X2,2200".to_vec();
+        let bp = BytecodeBreakpoint::from_slice(&mut body).unwrap();
+
+        assert_eq!(bp.base.type_, 0);
+        assert_eq!(bp.base.addr, &[0x10, 0x00]);
+        assert_eq!(bp.base.kind, &[0x04]);
+        assert!(bp.cmds_persist.is_none());
+
+        let conds = bp.conds.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(conds, [&[0x22, 0x00, 0x27][..], &[0x22, 0x00][..]]);
+    }
+
+    #[test]
+    fn bytecode_breakpoint_cmds() {
+        let mut body = b"0,1000,4;X1,27;cmds:1,X3,220027X1,27".to_vec();
+        let bp = BytecodeBreakpoint::from_slice(&mut body).unwrap();
+
+        let conds = bp.conds.unwrap().into_iter().collect::<Vec<_>>();
+        assert_eq!(conds, [&[0x27][..]]);
+
+        let (cmds, persist) = bp.cmds_persist.unwrap();
+        assert!(persist);
+        let cmds = cmds.into_iter().collect::<Vec<_>>();
+        assert_eq!(cmds, [&[0x22, 0x00, 0x27][..], &[0x27][..]]);
+    }
+
+    #[test]
+    fn bytecode_breakpoint_only_cmds() {
+        let mut body = b"1,1000,4;cmds:0,X1,27".to_vec();
+        let bp = BytecodeBreakpoint::from_slice(&mut body).unwrap();
+
+        assert!(bp.conds.is_none());
+        let (cmds, persist) = bp.cmds_persist.unwrap();
+        assert!(!persist);
+        assert_eq!(cmds.into_iter().collect::<Vec<_>>(), [&[0x27][..]]);
+    }
+
+    #[test]
+    fn bytecode_breakpoint_no_conds() {
+        let mut body = b"1,1000,4".to_vec();
+        let bp = BytecodeBreakpoint::from_slice(&mut body).unwrap();
+
+        assert_eq!(bp.base.type_, 1);
+        assert!(bp.conds.is_none());
+    }
+
+    #[test]
+    fn bytecode_breakpoint_invalid_conds() {
+        // length doesn't match the bytecode
+        let mut body = b"0,1000,4;X3,2200".to_vec();
+        assert!(BytecodeBreakpoint::from_slice(&mut body).is_none());
+
+        // bytecode isn't hex
+        let mut body = b"0,1000,4;X1,zz".to_vec();
+        assert!(BytecodeBreakpoint::from_slice(&mut body).is_none());
+
+        // missing the leading 'X'
+        let mut body = b"0,1000,4;3,220027".to_vec();
+        assert!(BytecodeBreakpoint::from_slice(&mut body).is_none());
+
+        // malformed command list
+        let mut body = b"0,1000,4;cmds:1,X2,27".to_vec();
+        assert!(BytecodeBreakpoint::from_slice(&mut body).is_none());
+    }
+}
@@ -174,6 +174,11 @@ pub fn decode_hex_buf(base_buf: &mut [u8]) -> Result<&mut [u8], DecodeHexBufErro
 }
 
 /// Decode GDB escaped binary bytes into origin bytes _in place_.
+///
+/// Every `}`-prefixed byte is XOR'd with `0x20`, which covers the escaped forms
+/// of `#`, `$`, `}` and `*`. Note that the GDB client never run-length encodes
+/// the packets it sends, so a `*` which reaches this function is always a
+/// literal byte.
 //
 // Thanks reddit!
 // https://www.reddit.com/r/rust/comments/110qzq9/any_idea_why_rust_isnt_able_to_elide_this_bounds/
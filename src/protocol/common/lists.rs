@@ -24,4 +24,22 @@ impl<'a> ArgListHex<'a> {
             .map(|s| s as &[u8])
             .filter(|s| !s.is_empty())
     }
+
+    /// Like `into_iter`, except empty entries are preserved (as an empty
+    /// program argument is still an argument).
+    ///
+    /// An empty list yields no entries, as opposed to a single empty entry.
+    pub fn into_program_args(self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let args = if self.0.is_empty() {
+            None
+        } else {
+            Some(self.0)
+        };
+        args.into_iter()
+            .flat_map(|args| args.split_mut(|b| *b == b';'))
+            // the `from_packet` method guarantees that the args are valid hex ascii, so this should
+            // method should never fail.
+            .map(|raw| decode_hex_buf(raw).unwrap_or(&mut []))
+            .map(|s| s as &[u8])
+    }
 }
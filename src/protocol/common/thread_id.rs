@@ -1,9 +1,14 @@
 use crate::protocol::common::hex::decode_hex;
 use core::convert::TryFrom;
 use core::convert::TryInto;
+use core::fmt;
 use core::num::NonZeroUsize;
+use core::str::FromStr;
 
 /// Tid/Pid Selector.
+///
+/// Implements [`Display`](fmt::Display) and [`FromStr`] using the GDB wire
+/// format, which is useful when logging or constructing thread IDs.
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum IdKind {
     /// All threads (-1)
@@ -64,6 +69,36 @@ impl TryFrom<&[u8]> for IdKind {
     }
 }
 
+impl From<NonZeroUsize> for IdKind {
+    fn from(id: NonZeroUsize) -> IdKind {
+        IdKind::WithId(id)
+    }
+}
+
+/// Formats the ID using the GDB wire format (i.e: lowercase hex, with `-1`
+/// for "all", and `0` for "any").
+impl fmt::Display for IdKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdKind::All => write!(f, "-1"),
+            IdKind::Any => write!(f, "0"),
+            IdKind::WithId(id) => write!(f, "{:x}", id),
+        }
+    }
+}
+
+/// Parses an ID from the GDB wire format. Inverse of the [`Display`]
+/// implementation.
+///
+/// [`Display`]: fmt::Display
+impl FromStr for IdKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<IdKind, ()> {
+        IdKind::try_from(s.as_bytes())
+    }
+}
+
 impl TryFrom<&mut [u8]> for ThreadId {
     type Error = ();
 
@@ -112,6 +147,38 @@ impl TryFrom<IdKind> for SpecificIdKind {
     }
 }
 
+impl From<NonZeroUsize> for SpecificIdKind {
+    fn from(id: NonZeroUsize) -> SpecificIdKind {
+        SpecificIdKind::WithId(id)
+    }
+}
+
+impl From<SpecificIdKind> for IdKind {
+    fn from(id: SpecificIdKind) -> IdKind {
+        match id {
+            SpecificIdKind::All => IdKind::All,
+            SpecificIdKind::WithId(id) => IdKind::WithId(id),
+        }
+    }
+}
+
+/// Formats the ID using the GDB wire format (i.e: lowercase hex, with `-1`
+/// for "all").
+impl fmt::Display for SpecificIdKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        IdKind::from(*self).fmt(f)
+    }
+}
+
+/// Parses an ID from the GDB wire format, rejecting `0` ("any").
+impl FromStr for SpecificIdKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<SpecificIdKind, ()> {
+        s.parse::<IdKind>()?.try_into()
+    }
+}
+
 impl TryFrom<ThreadId> for SpecificThreadId {
     type Error = ();
 
@@ -159,3 +226,42 @@ impl TryFrom<IdKind> for NonZeroUsize {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: usize) -> NonZeroUsize {
+        NonZeroUsize::new(n).unwrap()
+    }
+
+    #[test]
+    fn id_kind_round_trip() {
+        for (kind, s) in [
+            (IdKind::All, "-1"),
+            (IdKind::Any, "0"),
+            (IdKind::WithId(id(1)), "1"),
+            (IdKind::WithId(id(0xdead)), "dead"),
+        ] {
+            assert_eq!(kind.to_string(), s);
+            assert_eq!(s.parse::<IdKind>(), Ok(kind));
+        }
+
+        assert_eq!(IdKind::from(id(0x1f)), IdKind::WithId(id(0x1f)));
+        assert_eq!("".parse::<IdKind>(), Err(()));
+        assert_eq!("xyz".parse::<IdKind>(), Err(()));
+    }
+
+    #[test]
+    fn specific_id_kind_round_trip() {
+        for (kind, s) in [
+            (SpecificIdKind::All, "-1"),
+            (SpecificIdKind::WithId(id(0x42)), "42"),
+        ] {
+            assert_eq!(kind.to_string(), s);
+            assert_eq!(s.parse::<SpecificIdKind>(), Ok(kind));
+        }
+
+        assert_eq!("0".parse::<SpecificIdKind>(), Err(()));
+    }
+}
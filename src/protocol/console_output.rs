@@ -51,11 +51,17 @@ impl<'a> ConsoleOutput<'a> {
         }
     }
 
-    /// Flush the internal output buffer.
+    /// Flush the internal output buffer, immediately sending any buffered
+    /// output to the GDB console as a self-contained `O` packet.
     ///
-    /// Only available when `alloc` is enabled.
-    #[cfg(feature = "alloc")]
+    /// Long-running monitor commands can call this periodically to stream
+    /// progress updates to the user, instead of having all output show up once
+    /// the command completes.
+    ///
+    /// When the `alloc` feature is disabled, output is never buffered, and this
+    /// method is a no-op.
     pub fn flush(&mut self) {
+        #[cfg(feature = "alloc")]
         if !self.buf.is_empty() {
             (self.callback)(&self.buf);
             self.buf.clear()
@@ -65,7 +71,6 @@ impl<'a> ConsoleOutput<'a> {
 
 impl Drop for ConsoleOutput<'_> {
     fn drop(&mut self) {
-        #[cfg(feature = "alloc")]
         self.flush()
     }
 }
@@ -15,13 +15,17 @@ enum State {
 pub struct RecvPacketStateMachine {
     state: State,
     idx: usize,
+    max_len: Option<usize>,
 }
 
 impl RecvPacketStateMachine {
-    pub fn new() -> Self {
+    /// Packets longer than `max_len` bytes (if provided) are rejected, even if
+    /// they would fit in the packet buffer.
+    pub fn new(max_len: Option<usize>) -> Self {
         RecvPacketStateMachine {
             state: State::Ready,
             idx: 0,
+            max_len,
         }
     }
 
@@ -30,6 +34,12 @@ impl RecvPacketStateMachine {
         packet_buffer: &'b mut ManagedSlice<'_, u8>,
         byte: u8,
     ) -> Result<Option<&'b mut [u8]>, CapacityError<u8>> {
+        if matches!(self.max_len, Some(max_len) if self.idx >= max_len) {
+            self.state = State::Ready;
+            self.idx = 0;
+            return Err(CapacityError(byte));
+        }
+
         let mut buf = ManagedVec::new_with_idx(packet_buffer, self.idx);
         buf.push(byte)?;
         self.idx += 1;
@@ -64,3 +74,43 @@ impl RecvPacketStateMachine {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pump_all(
+        recv: &mut RecvPacketStateMachine,
+        packet_buffer: &mut ManagedSlice<'_, u8>,
+        data: &[u8],
+    ) -> Result<Option<usize>, CapacityError<u8>> {
+        for &byte in data {
+            if let Some(buf) = recv.pump(packet_buffer, byte)? {
+                return Ok(Some(buf.len()));
+            }
+        }
+        Ok(None)
+    }
+
+    #[test]
+    fn recv_packet_max_len() {
+        let mut buf = ManagedSlice::Owned(vec![0; 4]);
+        let mut recv = RecvPacketStateMachine::new(Some(8));
+
+        // fits within the cap, growing the buffer as needed
+        assert!(pump_all(&mut recv, &mut buf, b"$abc#00").unwrap().is_some());
+        // exceeds the cap
+        assert!(pump_all(&mut recv, &mut buf, b"$abcdefg#00").is_err());
+        // the state machine is ready for the next packet
+        assert!(pump_all(&mut recv, &mut buf, b"$?#3f").unwrap().is_some());
+    }
+
+    #[test]
+    fn recv_packet_borrowed_overflow() {
+        let mut storage = [0; 4];
+        let mut buf = ManagedSlice::Borrowed(&mut storage[..]);
+        let mut recv = RecvPacketStateMachine::new(None);
+
+        assert!(pump_all(&mut recv, &mut buf, b"$abc#00").is_err());
+    }
+}
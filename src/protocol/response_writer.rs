@@ -16,11 +16,23 @@ use num_traits::PrimInt;
 #[derive(Debug, Clone)]
 pub struct Error<C>(pub C);
 
+/// Number of bytes which are batched together before being passed to
+/// [`Connection::write_all`].
+const BATCH_LEN: usize = 64;
+
 /// A wrapper around [`Connection`] that computes the single-byte checksum of
 /// incoming / outgoing data.
+///
+/// Outgoing bytes are batched, and forwarded to the underlying connection via
+/// [`Connection::write_all`] at the end of every `write_*` call (or whenever
+/// the batch fills up). As such, the underlying connection is never left with
+/// any pending bytes in between calls (e.g: when accessed via `as_conn`).
 pub struct ResponseWriter<'a, C: Connection> {
     inner: &'a mut C,
+    batch: [u8; BATCH_LEN],
+    batch_len: usize,
     started: bool,
+    start_char: u8,
     checksum: u8,
 
     rle_enabled: bool,
@@ -37,7 +49,10 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
     pub fn new(inner: &'a mut C, rle_enabled: bool) -> Self {
         Self {
             inner,
+            batch: [0; BATCH_LEN],
+            batch_len: 0,
             started: false,
+            start_char: b'$',
             checksum: 0,
 
             rle_enabled,
@@ -49,6 +64,15 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
         }
     }
 
+    /// Creates a new ResponseWriter for an asynchronous notification packet
+    /// (i.e: a packet starting with '%' instead of '$').
+    pub fn new_notification(inner: &'a mut C, rle_enabled: bool) -> Self {
+        Self {
+            start_char: b'%',
+            ..Self::new(inner, rle_enabled)
+        }
+    }
+
     /// Consumes self, writing out the final '#' and checksum
     pub fn flush(mut self) -> Result<(), Error<C::Error>> {
         // don't include the '#' in checksum calculation
@@ -71,8 +95,13 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
         }
 
         #[cfg(feature = "trace-pkt")]
-        trace!("--> ${}", String::from_utf8_lossy(&self.msg));
+        trace!(
+            "--> {}{}",
+            self.start_char as char,
+            String::from_utf8_lossy(&self.msg)
+        );
 
+        self.drain()?;
         self.inner.flush().map_err(Error)?;
 
         Ok(())
@@ -104,11 +133,31 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
 
         if !self.started {
             self.started = true;
-            self.inner.write(b'$').map_err(Error)?;
+            self.push(self.start_char)?;
         }
 
         self.checksum = self.checksum.wrapping_add(byte);
-        self.inner.write(byte).map_err(Error)
+        self.push(byte)
+    }
+
+    /// Append a byte to the current batch, draining it if it's full.
+    fn push(&mut self, byte: u8) -> Result<(), Error<C::Error>> {
+        if self.batch_len == BATCH_LEN {
+            self.drain()?;
+        }
+        self.batch[self.batch_len] = byte;
+        self.batch_len += 1;
+        Ok(())
+    }
+
+    /// Forward the current batch to the underlying connection.
+    fn drain(&mut self) -> Result<(), Error<C::Error>> {
+        let len = core::mem::replace(&mut self.batch_len, 0);
+        // LLVM can't prove `len <= BATCH_LEN` on its own
+        match self.batch.get(..len) {
+            Some(batch) if !batch.is_empty() => self.inner.write_all(batch).map_err(Error),
+            _ => Ok(()),
+        }
     }
 
     fn write(&mut self, byte: u8) -> Result<(), Error<C::Error>> {
@@ -134,8 +183,8 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
                             self.inner_write(self.rle_char)?
                         }
                     }
-                    // RLE would output an invalid char ('#' or '$')
-                    6 | 7 => {
+                    // RLE would output an invalid char ('#', '$', or '}')
+                    7 | 8 | 97 => {
                         self.inner_write(self.rle_char)?;
                         self.rle_repeat -= 1;
                         continue;
@@ -161,7 +210,7 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
         for b in s.as_bytes().iter() {
             self.write(*b)?;
         }
-        Ok(())
+        self.drain()
     }
 
     /// Write a single byte as a hex string (two ascii chars)
@@ -195,7 +244,7 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
         for b in data.iter() {
             self.write_hex(*b)?;
         }
-        Ok(())
+        self.drain()
     }
 
     /// Write data using the binary protocol.
@@ -209,14 +258,15 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
                 _ => self.write(b)?,
             }
         }
-        Ok(())
+        self.drain()
     }
 
     /// Write a number as a big-endian hex string using the most compact
     /// representation possible (i.e: trimming leading zeros).
     pub fn write_num<D: BeBytes + PrimInt>(&mut self, digit: D) -> Result<(), Error<C::Error>> {
         if digit.is_zero() {
-            return self.write_hex(0);
+            self.write_hex(0)?;
+            return self.drain();
         }
 
         let mut buf = [0; 16];
@@ -226,7 +276,7 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
         for b in buf.iter().copied().skip_while(|&b| b == 0) {
             self.write_hex(b)?
         }
-        Ok(())
+        self.drain()
     }
 
     /// Write a number as a decimal string, converting every digit to an ascii
@@ -236,7 +286,8 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
         mut digit: D,
     ) -> Result<(), Error<C::Error>> {
         if digit.is_zero() {
-            return self.write(b'0');
+            self.write(b'0')?;
+            return self.drain();
         }
 
         let one: D = one();
@@ -262,7 +313,7 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
             digit = digit % pow_10;
             pow_10 = pow_10 / ten;
         }
-        Ok(())
+        self.drain()
     }
 
     #[inline]
@@ -287,3 +338,151 @@ impl<'a, C: Connection + 'a> ResponseWriter<'a, C> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    impl Connection for Vec<u8> {
+        type Error = ();
+
+        fn write(&mut self, byte: u8) -> Result<(), Self::Error> {
+            self.push(byte);
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    /// Decode a packet body using the same rules as the GDB client, checking
+    /// that no reserved chars are ever emitted as a repeat count.
+    fn rle_decode(raw: &[u8]) -> Vec<u8> {
+        let body = raw.strip_prefix(b"$").unwrap();
+        let end = body.iter().position(|&b| b == b'#').unwrap();
+        let (body, checksum) = body.split_at(end);
+
+        let expected = u8::from_str_radix(core::str::from_utf8(&checksum[1..]).unwrap(), 16);
+        let actual = body.iter().fold(0u8, |a, &b| a.wrapping_add(b));
+        assert_eq!(expected, Ok(actual), "bad checksum");
+
+        let mut out = Vec::new();
+        let mut body = body.iter().copied();
+        while let Some(b) = body.next() {
+            if b == b'*' {
+                let count = body.next().unwrap();
+                assert!(!matches!(count, b'#' | b'$' | b'}'), "invalid count char");
+                assert!((b' '..=b'~').contains(&count), "unprintable count char");
+                let c = *out.last().unwrap();
+                for _ in 0..(count - 29) {
+                    out.push(c);
+                }
+            } else {
+                out.push(b);
+            }
+        }
+        out
+    }
+
+    fn write_with(rle: bool, data: &[u8]) -> Vec<u8> {
+        let mut conn = Vec::new();
+        let mut res = ResponseWriter::new(&mut conn, rle);
+        res.write_hex_buf(data).unwrap();
+        res.flush().unwrap();
+        conn
+    }
+
+    fn expected_raw(data: &[u8]) -> Vec<u8> {
+        let mut hex = Vec::new();
+        for b in data {
+            hex.extend_from_slice(format!("{:02x}", b).as_bytes());
+        }
+        let checksum = hex.iter().fold(0u8, |a, &b| a.wrapping_add(b));
+        let mut raw = b"$".to_vec();
+        raw.extend_from_slice(&hex);
+        raw.extend_from_slice(format!("#{:02x}", checksum).as_bytes());
+        raw
+    }
+
+    fn expected_hex(data: &[u8]) -> Vec<u8> {
+        let mut conn = Vec::new();
+        let mut res = ResponseWriter::new(&mut conn, false);
+        res.write_hex_buf(data).unwrap();
+        res.flush().unwrap();
+        rle_decode(&conn)
+    }
+
+    #[test]
+    fn rle_roundtrip_all_run_lengths() {
+        for len in 0..300 {
+            let data = vec![0u8; len];
+            let raw = write_with(true, &data);
+            assert_eq!(rle_decode(&raw), expected_hex(&data), "len = {}", len);
+        }
+    }
+
+    #[test]
+    fn rle_roundtrip_mixed() {
+        let mut data = Vec::new();
+        for i in 0..64u8 {
+            data.extend_from_slice(&vec![i % 3; i as usize]);
+            data.push(0xab);
+        }
+        let raw = write_with(true, &data);
+        assert_eq!(rle_decode(&raw), expected_hex(&data));
+    }
+
+    #[test]
+    fn rle_compresses() {
+        let data = [0u8; 4096];
+        let raw = write_with(true, &data);
+        assert!(raw.len() < data.len() / 8);
+    }
+
+    #[test]
+    fn writes_are_batched() {
+        struct CountingConn {
+            data: Vec<u8>,
+            calls: usize,
+        }
+
+        impl Connection for CountingConn {
+            type Error = ();
+
+            fn write(&mut self, byte: u8) -> Result<(), ()> {
+                self.write_all(&[byte])
+            }
+
+            fn write_all(&mut self, buf: &[u8]) -> Result<(), ()> {
+                self.data.extend_from_slice(buf);
+                self.calls += 1;
+                Ok(())
+            }
+
+            fn flush(&mut self) -> Result<(), ()> {
+                Ok(())
+            }
+        }
+
+        let data: Vec<u8> = (0..100).collect();
+        let mut conn = CountingConn {
+            data: Vec::new(),
+            calls: 0,
+        };
+        let mut res = ResponseWriter::new(&mut conn, false);
+        res.write_hex_buf(&data).unwrap();
+        res.flush().unwrap();
+
+        assert_eq!(conn.data, expected_raw(&data));
+        // 201 bytes of `$` + hex data (in batches of 64), followed by the checksum
+        assert_eq!(conn.calls, 5);
+    }
+
+    #[test]
+    fn rle_disabled() {
+        let raw = write_with(false, &[0u8; 64]);
+        assert!(!raw.contains(&b'*'));
+    }
+}
@@ -40,6 +40,8 @@ pub struct GdbStubBuilder<'a, T: Target, C: Connection> {
     conn: C,
     packet_buffer: Option<&'a mut [u8]>,
     packet_buffer_size: Option<usize>,
+    packet_size: Option<usize>,
+    assume_no_ack_mode: bool,
 
     _target: PhantomData<T>,
 }
@@ -51,6 +53,8 @@ impl<'a, T: Target, C: Connection> GdbStubBuilder<'a, T, C> {
             conn,
             packet_buffer: None,
             packet_buffer_size: None,
+            packet_size: None,
+            assume_no_ack_mode: false,
 
             _target: PhantomData,
         }
@@ -73,6 +77,31 @@ impl<'a, T: Target, C: Connection> GdbStubBuilder<'a, T, C> {
         self
     }
 
+    /// Cap the maximum packet size advertised to the GDB client (via
+    /// `PacketSize=`), independently of the packet buffer's size.
+    ///
+    /// This is useful when the transport can't reliably sustain large packets
+    /// (e.g: a flaky serial link). The advertised value is the smaller of
+    /// `size` and the packet buffer's length, and any incoming packet longer
+    /// than the advertised value is rejected with an error.
+    pub fn packet_size(mut self, size: usize) -> Self {
+        self.packet_size = Some(size);
+        self
+    }
+
+    /// Start the session in no-ack mode, without waiting for the client to
+    /// send `QStartNoAckMode`.
+    ///
+    /// **This is not compliant with the GDB Remote Serial Protocol**, and will
+    /// break sessions with standard GDB clients (which always begin the
+    /// session in ack mode)! It is only intended for custom front-ends which
+    /// communicate over a reliable transport, skip the ack handshake entirely,
+    /// and never send (nor expect) `+`/`-` acknowledgements.
+    pub fn assume_no_ack_mode(mut self) -> Self {
+        self.assume_no_ack_mode = true;
+        self
+    }
+
     /// Build the GdbStub, returning an error if something went wrong.
     pub fn build(self) -> Result<GdbStub<'a, T, C>, GdbStubBuilderError> {
         let packet_buffer = match self.packet_buffer {
@@ -104,10 +133,12 @@ impl<'a, T: Target, C: Connection> GdbStubBuilder<'a, T, C> {
             }
         };
 
+        let packet_size = self.packet_size.map(|size| size.min(packet_buffer.len()));
+
         Ok(GdbStub {
             conn: self.conn,
             packet_buffer,
-            inner: GdbStubImpl::new(),
+            inner: GdbStubImpl::new(packet_size, self.assume_no_ack_mode),
         })
     }
 }
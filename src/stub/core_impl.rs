@@ -1,12 +1,15 @@
+use crate::arch::Arch;
 use crate::common::Signal;
 use crate::common::Tid;
 use crate::conn::Connection;
 use crate::protocol::commands::Command;
 use crate::protocol::Packet;
+use crate::protocol::PacketParseError;
 use crate::protocol::ResponseWriter;
 use crate::protocol::SpecificIdKind;
 use crate::stub::error::InternalError;
 use crate::target::Target;
+use crate::target::TargetError;
 use crate::SINGLE_THREAD_TID;
 use core::marker::PhantomData;
 
@@ -24,25 +27,41 @@ mod prelude {
     pub(super) use crate::target::Target;
 }
 
+mod agent;
 mod auxv;
 mod base;
 mod breakpoints;
 mod catch_syscalls;
 mod exec_file;
 mod extended_mode;
+mod flash_operations;
 mod host_io;
 mod libraries;
 mod lldb_register_info;
+mod memory_crc;
 mod memory_map;
+mod memory_search;
+mod memory_tagging;
 mod monitor_cmd;
 mod no_ack_mode;
+mod non_stop;
+mod os_data;
+mod pass_signals;
+mod program_signals;
 mod resume;
 mod reverse_exec;
 mod section_offsets;
+mod signal_info;
 mod single_register_access;
 mod target_xml;
+mod thread_events;
 mod thread_extra_info;
+mod thread_list;
+mod thread_local_storage;
+mod tracepoints;
+mod windows_thread_info;
 mod x_upcase_packet;
+mod xfer_window;
 
 pub(crate) use resume::FinishExecStatus;
 
@@ -79,15 +98,45 @@ pub(crate) mod target_result_ext {
 
 /// Describes why the GDB session ended.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DisconnectReason {
     /// Target exited with given status code
-    TargetExited(u8),
+    TargetExited(u32),
     /// Target terminated with given signal
     TargetTerminated(Signal),
     /// GDB issued a disconnect command
     Disconnect,
     /// GDB issued a kill command
     Kill,
+    /// The session was aborted due to a protocol-level error (e.g: a corrupt
+    /// packet caused by line noise, or a misbehaving client).
+    ///
+    /// Unlike the other variants, this does not indicate an orderly shutdown,
+    /// so it may be appropriate to wait for the client to reconnect.
+    ProtocolError(ProtocolError),
+}
+
+/// Describes the protocol-level error that ended a GDB session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProtocolError {
+    /// The client nack'd the last packet, but `gdbstub` doesn't implement
+    /// re-transmission.
+    ClientSentNack,
+    /// Received a packet whose checksum didn't match its contents.
+    ChecksumMismatch,
+    /// Received a packet that was structurally malformed, or a command that
+    /// failed to parse.
+    MalformedPacket,
+}
+
+impl From<PacketParseError> for ProtocolError {
+    fn from(e: PacketParseError) -> ProtocolError {
+        match e {
+            PacketParseError::ChecksumMismatched { .. } => ProtocolError::ChecksumMismatch,
+            _ => ProtocolError::MalformedPacket,
+        }
+    }
 }
 
 pub enum State {
@@ -104,17 +153,52 @@ pub(crate) struct GdbStubImpl<T: Target, C: Connection> {
     current_mem_tid: Tid,
     current_resume_tid: SpecificIdKind,
     features: ProtocolFeatures,
+    stop_queue: non_stop::StopQueue<<T::Arch as Arch>::Usize>,
+    /// Snapshot of the most recently read registers (see
+    /// [`Target::use_register_cache`]).
+    register_cache: Option<(Tid, <T::Arch as Arch>::Registers)>,
+    /// Address range of an in-progress emulated range step (see
+    /// [`Target::use_emulated_range_step`]).
+    emulated_range_step: Option<(<T::Arch as Arch>::Usize, <T::Arch as Arch>::Usize)>,
+    /// Number of processes already reported by an in-progress multiprocess
+    /// `qfThreadInfo` / `qsThreadInfo` sequence.
+    thread_list_cursor: Option<usize>,
+    /// Number of trace state variables already reported by an in-progress
+    /// `qTfV` / `qTsV` sequence.
+    trace_state_variable_cursor: Option<usize>,
+    /// Hardware breakpoints currently set (see
+    /// [`HwBreakpoint::hw_breakpoint_count`]).
+    ///
+    /// [`HwBreakpoint::hw_breakpoint_count`]:
+    /// crate::target::ext::breakpoints::HwBreakpoint::hw_breakpoint_count
+    hw_breakpoints: breakpoints::HwSlots<<T::Arch as Arch>::Usize>,
+    /// Hardware watchpoints currently set.
+    hw_watchpoints: breakpoints::HwSlots<<T::Arch as Arch>::Usize>,
+    /// Maximum packet size accepted by the client (as advertised via
+    /// `qSupported`).
+    client_packet_size: Option<usize>,
+    /// Maximum packet size advertised to (and accepted from) the client, if
+    /// smaller than the packet buffer.
+    packet_size: Option<usize>,
 }
 
 pub enum HandlerStatus {
     Handled,
     NeedsOk,
+    /// The command must not be responded to (not even with an empty packet).
+    NoResponse,
     DeferredStopReason,
+    /// Acknowledge the command with `OK`, and then treat it like a Ctrl-C
+    /// interrupt.
+    CtrlCInterrupt,
     Disconnect(DisconnectReason),
 }
 
 impl<T: Target, C: Connection> GdbStubImpl<T, C> {
-    pub fn new() -> GdbStubImpl<T, C> {
+    pub fn new(packet_size: Option<usize>, no_ack_mode: bool) -> GdbStubImpl<T, C> {
+        let mut features = ProtocolFeatures::empty();
+        features.set_no_ack_mode(no_ack_mode);
+
         GdbStubImpl {
             _target: PhantomData,
             _connection: PhantomData,
@@ -129,7 +213,40 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             // the target will simply return a non-fatal error, which is totally fine.
             current_mem_tid: SINGLE_THREAD_TID,
             current_resume_tid: SpecificIdKind::WithId(SINGLE_THREAD_TID),
-            features: ProtocolFeatures::empty(),
+            features,
+            stop_queue: non_stop::StopQueue::new(),
+            register_cache: None,
+            emulated_range_step: None,
+            thread_list_cursor: None,
+            trace_state_variable_cursor: None,
+            hw_breakpoints: breakpoints::HwSlots::new(),
+            hw_watchpoints: breakpoints::HwSlots::new(),
+            client_packet_size: None,
+            packet_size,
+        }
+    }
+
+    /// Forget about any hardware breakpoints / watchpoints, which don't outlive
+    /// the process they were set in.
+    fn clear_hw_breakpoints(&mut self) {
+        self.hw_breakpoints.clear();
+        self.hw_watchpoints.clear();
+    }
+
+    pub fn packet_size(&self) -> Option<usize> {
+        self.packet_size
+    }
+
+    /// Clamp the length of a `qXfer` read to the space available in the packet
+    /// buffer (`buf_len`), as well as the client's advertised `PacketSize`.
+    ///
+    /// Since the reply is binary-escaped, a worst-case of 2 bytes per byte of
+    /// data is assumed, alongside room for the `$m` prefix and `#xx` checksum.
+    fn xfer_read_len(&self, length: usize, buf_len: usize) -> usize {
+        let length = length.min(buf_len);
+        match self.client_packet_size {
+            Some(size) => length.min((size.saturating_sub(5) / 2).max(1)),
+            None => length,
         }
     }
 
@@ -141,17 +258,32 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
     ) -> Result<State, InternalError<T::Error, C::Error>> {
         match packet {
             Packet::Ack => Ok(State::Pump),
-            Packet::Nack => Err(InternalError::ClientSentNack),
+            Packet::Nack => Ok(State::Disconnect(DisconnectReason::ProtocolError(
+                ProtocolError::ClientSentNack,
+            ))),
             Packet::Interrupt => {
                 debug!("<-- interrupt packet");
+                if let Some(ops) = target.support_interrupt() {
+                    match ops.interrupt() {
+                        Ok(()) => {}
+                        Err(TargetError::Fatal(e)) => return Err(InternalError::TargetError(e)),
+                        // there's no way to report an error in response to the raw interrupt byte
+                        Err(_) => warn!("target failed to handle interrupt"),
+                    }
+                }
                 Ok(State::CtrlCInterrupt)
             }
             Packet::Command(command) => {
                 // Acknowledge the command
-                if !self.features.no_ack_mode() {
+                let was_no_ack_mode = self.features.no_ack_mode();
+                if !was_no_ack_mode {
                     conn.write(b'+').map_err(InternalError::conn_write)?;
                 }
 
+                // sampled up-front, as killing the last process may cause the target to
+                // stop reporting extended mode support
+                let extended_mode = target.support_extended_mode().is_some();
+
                 let mut res = ResponseWriter::new(conn, target.use_rle());
                 let disconnect_reason = match self.handle_command(&mut res, target, command) {
                     Ok(HandlerStatus::Handled) => None,
@@ -159,7 +291,24 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                         res.write_str("OK")?;
                         None
                     }
-                    Ok(HandlerStatus::DeferredStopReason) => return Ok(State::DeferredStopReason),
+                    Ok(HandlerStatus::NoResponse) => {
+                        // make sure the ack still reaches the client
+                        res.as_conn().flush().map_err(InternalError::conn_write)?;
+                        return Ok(State::Pump);
+                    }
+                    Ok(HandlerStatus::DeferredStopReason) => {
+                        // in non-stop mode, resume packets are acknowledged immediately
+                        if self.features.non_stop() {
+                            res.flush()?;
+                        }
+                        return Ok(State::DeferredStopReason);
+                    }
+                    Ok(HandlerStatus::CtrlCInterrupt) => {
+                        // the `OK` must reach the client before any stop notification
+                        res.write_str("OK")?;
+                        res.flush()?;
+                        return Ok(State::CtrlCInterrupt);
+                    }
                     Ok(HandlerStatus::Disconnect(reason)) => Some(reason),
                     // HACK: handling this "dummy" error is required as part of the
                     // `TargetResultExt::handle_error()` machinery.
@@ -168,16 +317,28 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                         res.write_num(code)?;
                         None
                     }
+                    // commands are parsed lazily, so some malformed packets are only caught
+                    // by their handlers
+                    Err(InternalError::PacketParse(e)) => {
+                        warn!("failed to parse packet: {:?}", e);
+                        return Ok(State::Disconnect(DisconnectReason::ProtocolError(e.into())));
+                    }
                     Err(e) => return Err(e),
                 };
 
                 // every response needs to be flushed, _except_ for the response to a kill
                 // packet, but ONLY when extended mode is NOT implemented.
                 let is_kill = matches!(disconnect_reason, Some(DisconnectReason::Kill));
-                if !(target.support_extended_mode().is_none() && is_kill) {
+                if extended_mode || !is_kill {
                     res.flush()?;
                 }
 
+                if !was_no_ack_mode && self.features.no_ack_mode() {
+                    if let Some(ops) = target.support_protocol_callbacks() {
+                        ops.on_no_ack_mode_enabled();
+                    }
+                }
+
                 let state = match disconnect_reason {
                     Some(reason) => State::Disconnect(reason),
                     None => State::Pump,
@@ -188,12 +349,51 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         }
     }
 
+    /// Whether `cmd` is guaranteed to leave the target's registers untouched,
+    /// and can therefore be handled without invalidating the register cache.
+    ///
+    /// Errs on the side of caution: anything which _might_ modify registers
+    /// (e.g: resuming the target, writing registers, switching processes)
+    /// is assumed to do so.
+    fn preserves_registers(cmd: &Command<'_>) -> bool {
+        use crate::protocol::commands::ext::Base;
+        use crate::protocol::commands::ext::SingleRegisterAccess;
+
+        match cmd {
+            Command::Base(cmd) => matches!(
+                cmd,
+                Base::g(_)
+                    | Base::m(_)
+                    | Base::qfThreadInfo(_)
+                    | Base::qsThreadInfo(_)
+                    | Base::T(_)
+            ),
+            Command::SingleRegisterAccess(cmd) => matches!(cmd, SingleRegisterAccess::p(_)),
+            Command::TargetXml(_)
+            | Command::MemoryMap(_)
+            | Command::Auxv(_)
+            | Command::ExecFile(_)
+            | Command::LibrariesSvr4(_)
+            | Command::ThreadExtraInfo(_)
+            | Command::ThreadList(_)
+            | Command::LldbRegisterInfo(_)
+            | Command::MemorySearch(_)
+            | Command::MemoryCrc(_)
+            | Command::SectionOffsets(_) => true,
+            _ => false,
+        }
+    }
+
     fn handle_command(
         &mut self,
         res: &mut ResponseWriter<'_, C>,
         target: &mut T,
         cmd: Command<'_>,
     ) -> Result<HandlerStatus, InternalError<T::Error, C::Error>> {
+        if target.use_register_cache() && !Self::preserves_registers(&cmd) {
+            self.register_cache = None;
+        }
+
         match cmd {
             // `handle_X` methods are defined in the `ext` module
             Command::Base(cmd) => self.handle_base(res, target, cmd),
@@ -206,18 +406,33 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             }
             Command::Breakpoints(cmd) => self.handle_breakpoints(res, target, cmd),
             Command::CatchSyscalls(cmd) => self.handle_catch_syscalls(res, target, cmd),
+            Command::PassSignals(cmd) => self.handle_pass_signals(res, target, cmd),
+            Command::ProgramSignals(cmd) => self.handle_program_signals(res, target, cmd),
             Command::ExtendedMode(cmd) => self.handle_extended_mode(res, target, cmd),
             Command::MonitorCmd(cmd) => self.handle_monitor_cmd(res, target, cmd),
             Command::SectionOffsets(cmd) => self.handle_section_offsets(res, target, cmd),
             Command::ReverseCont(cmd) => self.handle_reverse_cont(res, target, cmd),
             Command::ReverseStep(cmd) => self.handle_reverse_step(res, target, cmd),
             Command::MemoryMap(cmd) => self.handle_memory_map(res, target, cmd),
+            Command::FlashOperations(cmd) => self.handle_flash_operations(res, target, cmd),
             Command::HostIo(cmd) => self.handle_host_io(res, target, cmd),
             Command::ExecFile(cmd) => self.handle_exec_file(res, target, cmd),
+            Command::OsData(cmd) => self.handle_os_data(res, target, cmd),
+            Command::SignalInfo(cmd) => self.handle_signal_info(res, target, cmd),
+            Command::ThreadLocalStorage(cmd) => self.handle_thread_local_storage(res, target, cmd),
+            Command::WindowsThreadInfo(cmd) => self.handle_windows_thread_info(res, target, cmd),
             Command::Auxv(cmd) => self.handle_auxv(res, target, cmd),
             Command::ThreadExtraInfo(cmd) => self.handle_thread_extra_info(res, target, cmd),
+            Command::ThreadEvents(cmd) => self.handle_thread_events(res, target, cmd),
+            Command::ThreadList(cmd) => self.handle_thread_list(res, target, cmd),
             Command::LldbRegisterInfo(cmd) => self.handle_lldb_register_info(res, target, cmd),
             Command::LibrariesSvr4(cmd) => self.handle_libraries_svr4(res, target, cmd),
+            Command::MemorySearch(cmd) => self.handle_memory_search(res, target, cmd),
+            Command::MemoryCrc(cmd) => self.handle_memory_crc(res, target, cmd),
+            Command::MemoryTagging(cmd) => self.handle_memory_tagging(res, target, cmd),
+            Command::NonStop(cmd) => self.handle_non_stop(res, target, cmd),
+            Command::Tracepoints(cmd) => self.handle_tracepoints(res, target, cmd),
+            Command::Agent(cmd) => self.handle_agent(res, target, cmd),
             // in the worst case, the command could not be parsed...
             Command::Unknown(cmd) => {
                 // HACK: if the user accidentally sends a resume command to a
@@ -232,7 +447,7 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     if is_resume_pkt {
                         warn!("attempted to resume target without resume support!");
 
-                        // TODO: omit this message if non-stop mode is active
+                        // (non-stop mode is never active here, as it requires resume support)
                         {
                             let mut res = ResponseWriter::new(res.as_conn(), target.use_rle());
                             res.write_str("O")?;
@@ -253,15 +468,23 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
 #[derive(Copy, Clone)]
 #[repr(transparent)]
-struct ProtocolFeatures(u8);
+struct ProtocolFeatures(u16);
 
 // This bitflag is not part of the protocol - it is an internal implementation
 // detail. The alternative would be to use multiple `bool` fields, which wastes
 // space in minimal `gdbstub` configurations.
 bitflags::bitflags! {
-    impl ProtocolFeatures: u8 {
+    impl ProtocolFeatures: u16 {
         const NO_ACK_MODE = 1 << 0;
         const MULTIPROCESS = 1 << 1;
+        const SWBREAK = 1 << 2;
+        const HWBREAK = 1 << 3;
+        const NON_STOP = 1 << 4;
+        const FORK_EVENTS = 1 << 5;
+        const VFORK_EVENTS = 1 << 6;
+        const EXEC_EVENTS = 1 << 7;
+        const LIST_THREADS_IN_STOP_REPLY = 1 << 8;
+        const THREAD_EVENTS = 1 << 9;
     }
 }
 
@@ -285,4 +508,84 @@ impl ProtocolFeatures {
     fn set_multiprocess(&mut self, val: bool) {
         self.set(ProtocolFeatures::MULTIPROCESS, val)
     }
+
+    #[inline(always)]
+    fn swbreak(&self) -> bool {
+        self.contains(ProtocolFeatures::SWBREAK)
+    }
+
+    #[inline(always)]
+    fn set_swbreak(&mut self, val: bool) {
+        self.set(ProtocolFeatures::SWBREAK, val)
+    }
+
+    #[inline(always)]
+    fn hwbreak(&self) -> bool {
+        self.contains(ProtocolFeatures::HWBREAK)
+    }
+
+    #[inline(always)]
+    fn set_hwbreak(&mut self, val: bool) {
+        self.set(ProtocolFeatures::HWBREAK, val)
+    }
+
+    #[inline(always)]
+    fn non_stop(&self) -> bool {
+        self.contains(ProtocolFeatures::NON_STOP)
+    }
+
+    #[inline(always)]
+    fn set_non_stop(&mut self, val: bool) {
+        self.set(ProtocolFeatures::NON_STOP, val)
+    }
+
+    #[inline(always)]
+    fn fork_events(&self) -> bool {
+        self.contains(ProtocolFeatures::FORK_EVENTS)
+    }
+
+    #[inline(always)]
+    fn set_fork_events(&mut self, val: bool) {
+        self.set(ProtocolFeatures::FORK_EVENTS, val)
+    }
+
+    #[inline(always)]
+    fn vfork_events(&self) -> bool {
+        self.contains(ProtocolFeatures::VFORK_EVENTS)
+    }
+
+    #[inline(always)]
+    fn set_vfork_events(&mut self, val: bool) {
+        self.set(ProtocolFeatures::VFORK_EVENTS, val)
+    }
+
+    #[inline(always)]
+    fn exec_events(&self) -> bool {
+        self.contains(ProtocolFeatures::EXEC_EVENTS)
+    }
+
+    #[inline(always)]
+    fn set_exec_events(&mut self, val: bool) {
+        self.set(ProtocolFeatures::EXEC_EVENTS, val)
+    }
+
+    #[inline(always)]
+    fn list_threads_in_stop_reply(&self) -> bool {
+        self.contains(ProtocolFeatures::LIST_THREADS_IN_STOP_REPLY)
+    }
+
+    #[inline(always)]
+    fn set_list_threads_in_stop_reply(&mut self, val: bool) {
+        self.set(ProtocolFeatures::LIST_THREADS_IN_STOP_REPLY, val)
+    }
+
+    #[inline(always)]
+    fn thread_events(&self) -> bool {
+        self.contains(ProtocolFeatures::THREAD_EVENTS)
+    }
+
+    #[inline(always)]
+    fn set_thread_events(&mut self, val: bool) {
+        self.set(ProtocolFeatures::THREAD_EVENTS, val)
+    }
 }
@@ -0,0 +1,27 @@
+use super::prelude::*;
+use crate::protocol::commands::ext::Agent;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_agent(
+        &mut self,
+        _res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: Agent,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_agent() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("agent", "impl");
+
+        let handler_status = match command {
+            Agent::QAgent(cmd) => {
+                ops.set_agent(cmd.enabled).handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
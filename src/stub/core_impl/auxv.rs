@@ -17,9 +17,10 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
         let handler_status = match command {
             Auxv::qXferAuxvRead(cmd) => {
-                let ret = ops
-                    .get_auxv(cmd.offset, cmd.length, cmd.buf)
-                    .handle_error()?;
+                // the client may request more data than can fit in the remaining packet
+                // buffer, in which case it'll simply request the rest in another packet
+                let length = self.xfer_read_len(cmd.length, cmd.buf.len());
+                let ret = ops.get_auxv(cmd.offset, length, cmd.buf).handle_error()?;
                 if ret == 0 {
                     res.write_str("l")?;
                 } else {
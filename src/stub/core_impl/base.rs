@@ -38,6 +38,68 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(tid)
     }
 
+    /// Check whether `tid` refers to a live thread in the current process.
+    fn is_thread_alive(
+        &mut self,
+        target: &mut T,
+        tid: IdKind,
+    ) -> Result<bool, Error<T::Error, C::Error>> {
+        let alive = match tid {
+            IdKind::WithId(tid) => match target.base_ops() {
+                BaseOps::SingleThread(_) => tid == SINGLE_THREAD_TID,
+                BaseOps::MultiThread(ops) => {
+                    ops.is_thread_alive(tid).map_err(Error::TargetError)?
+                }
+            },
+            // "any" / "all" threads are alive so long as there is at least one thread
+            IdKind::Any | IdKind::All => self.get_sane_any_tid(target)?.is_some(),
+        };
+        Ok(alive)
+    }
+
+    /// Check whether `tid` refers to a live thread in the (non-current) process
+    /// `pid`, without changing which process is selected.
+    fn is_process_thread_alive(
+        &mut self,
+        target: &mut T,
+        pid: Pid,
+        tid: IdKind,
+    ) -> Result<bool, Error<T::Error, C::Error>> {
+        if let BaseOps::MultiThread(ops) = target.base_ops() {
+            if let Some(ops) = ops.support_process_list() {
+                // the process may have exited in the meantime
+                let mut exists = false;
+                ops.list_processes(&mut |active_pid| exists |= pid == active_pid)
+                    .map_err(Error::TargetError)?;
+                if !exists {
+                    return Ok(false);
+                }
+
+                let mut alive = false;
+                ops.list_process_threads(pid, &mut |active_tid| {
+                    alive |= match tid {
+                        IdKind::WithId(tid) => tid == active_tid,
+                        IdKind::Any | IdKind::All => true,
+                    }
+                })
+                .map_err(Error::TargetError)?;
+                return Ok(alive);
+            }
+        }
+
+        // otherwise, switch to the process for the duration of the check
+        let current_pid = self.get_current_pid(target)?;
+        match target.support_fork_events() {
+            Some(ops) => ops.set_current_process(pid).handle_error()?,
+            None => return Ok(false),
+        }
+        let alive = self.is_thread_alive(target, tid);
+        if let Some(ops) = target.support_fork_events() {
+            ops.set_current_process(current_pid).handle_error()?;
+        }
+        alive
+    }
+
     pub(crate) fn get_current_pid(
         &mut self,
         target: &mut T,
@@ -52,6 +114,85 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         }
     }
 
+    /// Whether the thread list should span every process being debugged (see
+    /// [`ProcessList`](crate::target::ext::process_list::ProcessList)).
+    pub(crate) fn use_process_list(&self, target: &mut T) -> bool {
+        self.features.multiprocess()
+            && match target.base_ops() {
+                BaseOps::SingleThread(_) => false,
+                BaseOps::MultiThread(ops) => ops.support_process_list().is_some(),
+            }
+    }
+
+    /// Write the threads of the next process in the thread list (starting from
+    /// `self.thread_list_cursor`), skipping over any processes without
+    /// threads. Writes `l` once every process has been reported.
+    fn write_next_process_threads(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        let ops = match target.base_ops() {
+            BaseOps::SingleThread(_) => None,
+            BaseOps::MultiThread(ops) => ops.support_process_list(),
+        };
+        let (ops, mut cursor) = match (ops, self.thread_list_cursor) {
+            (Some(ops), Some(cursor)) => (ops, cursor),
+            _ => {
+                res.write_str("l")?;
+                return Ok(());
+            }
+        };
+
+        loop {
+            let mut idx = 0;
+            let mut next_pid = None;
+            ops.list_processes(&mut |pid| {
+                if idx == cursor {
+                    next_pid = Some(pid);
+                }
+                idx += 1;
+            })
+            .map_err(Error::TargetError)?;
+
+            let pid = match next_pid {
+                Some(pid) => pid,
+                None => {
+                    self.thread_list_cursor = None;
+                    res.write_str("l")?;
+                    return Ok(());
+                }
+            };
+            cursor += 1;
+
+            let mut err: Result<_, Error<T::Error, C::Error>> = Ok(());
+            let mut first = true;
+            ops.list_process_threads(pid, &mut |tid| {
+                // TODO: replace this with a try block (once stabilized)
+                let e = (|| {
+                    res.write_str(if first { "m" } else { "," })?;
+                    first = false;
+                    res.write_specific_thread_id(SpecificThreadId {
+                        pid: Some(SpecificIdKind::WithId(pid)),
+                        tid: SpecificIdKind::WithId(tid),
+                    })?;
+                    Ok(())
+                })();
+
+                if let Err(e) = e {
+                    err = Err(e)
+                }
+            })
+            .map_err(Error::TargetError)?;
+            err?;
+
+            if !first {
+                self.thread_list_cursor = Some(cursor);
+                return Ok(());
+            }
+        }
+    }
+
     // Used by `?` and `vAttach` to return a "reasonable" stop reason.
     //
     // This is a bit of an implementation wart, since this is really something
@@ -91,6 +232,7 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             // ------------------ Handshaking and Queries ------------------- //
             Base::qSupported(cmd) => {
                 use crate::protocol::commands::_qSupported::Feature;
+                use crate::target::ext::protocol_callbacks::ClientFeatures;
 
                 // perform incoming feature negotiation
                 for feature in cmd.features.into_iter() {
@@ -106,11 +248,29 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
                     match feature {
                         Feature::Multiprocess => self.features.set_multiprocess(supported),
+                        Feature::SwBreak => self.features.set_swbreak(supported),
+                        Feature::HwBreak => self.features.set_hwbreak(supported),
+                        Feature::ForkEvents => self.features.set_fork_events(supported),
+                        Feature::VforkEvents => self.features.set_vfork_events(supported),
+                        Feature::ExecEvents => self.features.set_exec_events(supported),
+                        Feature::PacketSize(size) => self.client_packet_size = Some(size),
                     }
                 }
 
+                if let Some(ops) = target.support_protocol_callbacks() {
+                    ops.on_client_features(ClientFeatures {
+                        multiprocess: self.features.multiprocess(),
+                        swbreak: self.features.swbreak(),
+                        hwbreak: self.features.hwbreak(),
+                        fork_events: self.features.fork_events(),
+                        vfork_events: self.features.vfork_events(),
+                        exec_events: self.features.exec_events(),
+                        packet_size: self.client_packet_size,
+                    });
+                }
+
                 res.write_str("PacketSize=")?;
-                res.write_num(cmd.packet_buffer_len)?;
+                res.write_num(self.packet_size.unwrap_or(cmd.packet_buffer_len))?;
 
                 // these are the few features that gdbstub unconditionally supports
                 res.write_str(concat!(";vContSupported+", ";multiprocess+",))?;
@@ -119,6 +279,32 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     res.write_str(";QStartNoAckMode+")?;
                 }
 
+                if let Some(ops) = target.support_fork_events() {
+                    res.write_str(";fork-events+")?;
+
+                    if ops.support_vfork_events().is_some() {
+                        res.write_str(";vfork-events+")?;
+                    }
+                }
+
+                if target.support_tracepoints().is_some() {
+                    res.write_str(";Tracepoints+")?;
+                }
+
+                if target.support_agent().is_some() {
+                    res.write_str(";QAgent+")?;
+                }
+
+                if target.support_exec_events().is_some() {
+                    res.write_str(";exec-events+")?;
+                }
+
+                if let Some(ResumeOps::MultiThread(ops)) = target.base_ops().resume_ops() {
+                    if ops.support_non_stop().is_some() {
+                        res.write_str(";QNonStop+")?;
+                    }
+                }
+
                 if let Some(resume_ops) = target.base_ops().resume_ops() {
                     let (reverse_cont, reverse_step) = match resume_ops {
                         ResumeOps::MultiThread(ops) => (
@@ -170,12 +356,28 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     {
                         res.write_str(";hwbreak+")?;
                     }
+
+                    if ops.support_conditional_breakpoints().is_some() {
+                        res.write_str(";ConditionalBreakpoints+")?;
+                    }
+
+                    if ops.support_breakpoint_commands().is_some() {
+                        res.write_str(";BreakpointCommands+")?;
+                    }
                 }
 
                 if target.support_catch_syscalls().is_some() {
                     res.write_str(";QCatchSyscalls+")?;
                 }
 
+                if target.support_pass_signals().is_some() {
+                    res.write_str(";QPassSignals+")?;
+                }
+
+                if target.support_program_signals().is_some() {
+                    res.write_str(";QProgramSignals+")?;
+                }
+
                 if target.use_target_description_xml()
                     && (T::Arch::target_description_xml().is_some()
                         || target.support_target_description_xml_override().is_some())
@@ -195,6 +397,24 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     res.write_str(";qXfer:auxv:read+")?;
                 }
 
+                if target.support_os_data().is_some() {
+                    res.write_str(";qXfer:osdata:read+")?;
+                }
+
+                if target.support_signal_info().is_some() {
+                    res.write_str(";qXfer:siginfo:read+")?;
+                }
+
+                if let BaseOps::MultiThread(ops) = target.base_ops() {
+                    if ops.support_thread_list().is_some() {
+                        res.write_str(";qXfer:threads:read+")?;
+                    }
+                }
+
+                if target.support_memory_tagging().is_some() {
+                    res.write_str(";memory-tagging+")?;
+                }
+
                 if target.support_libraries_svr4().is_some() {
                     res.write_str(";qXfer:libraries-svr4:read+")?;
                 }
@@ -215,7 +435,19 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     None => true, // assume attached to an existing process
                     // When running in extended mode, we must defer to the target
                     Some(ops) => {
-                        match cmd.pid {
+                        let pid = match cmd.pid {
+                            Some(pid) => Some(pid),
+                            // non-multiprocess clients omit the pid, and are asking about the
+                            // current process
+                            None => match ops.support_current_active_pid() {
+                                Some(ops) => {
+                                    Some(ops.current_active_pid().map_err(Error::TargetError)?)
+                                }
+                                None => None,
+                            },
+                        };
+
+                        match pid {
                             Some(pid) => ops.query_if_attached(pid).handle_error()?.was_attached(),
                             None => true, // assume attached to an existing process
                         }
@@ -224,15 +456,65 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 res.write_str(if is_attached { "1" } else { "0" })?;
                 HandlerStatus::Handled
             }
-            Base::g(_) => {
-                let mut regs: <T::Arch as Arch>::Registers = Default::default();
-                match target.base_ops() {
-                    BaseOps::SingleThread(ops) => ops.read_registers(&mut regs),
+            Base::qC(_) => {
+                let tid = match target.base_ops() {
+                    // single-threaded targets only have a thread worth reporting alongside the
+                    // pid of an extended-mode process
+                    BaseOps::SingleThread(_) => {
+                        let has_current_pid = target
+                            .support_extended_mode()
+                            .and_then(|ops| ops.support_current_active_pid())
+                            .is_some();
+                        if !has_current_pid {
+                            return Ok(HandlerStatus::Handled);
+                        }
+                        SINGLE_THREAD_TID
+                    }
                     BaseOps::MultiThread(ops) => {
-                        ops.read_registers(&mut regs, self.current_mem_tid)
+                        if let Some(tid) = ops.current_thread().map_err(Error::TargetError)? {
+                            tid
+                        // HACK: gdbstub should avoid using a sentinel value
+                        // here...
+                        } else if self.current_mem_tid == SINGLE_THREAD_TID {
+                            let mut first_tid = None;
+                            ops.list_active_threads(&mut |tid| {
+                                first_tid.get_or_insert(tid);
+                            })
+                            .map_err(Error::TargetError)?;
+                            first_tid.unwrap_or(SINGLE_THREAD_TID)
+                        } else {
+                            self.current_mem_tid
+                        }
                     }
-                }
-                .handle_error()?;
+                };
+                let pid = self.get_current_pid(target)?;
+
+                res.write_str("QC")?;
+                res.write_specific_thread_id(SpecificThreadId {
+                    pid: self
+                        .features
+                        .multiprocess()
+                        .then_some(SpecificIdKind::WithId(pid)),
+                    tid: SpecificIdKind::WithId(tid),
+                })?;
+                HandlerStatus::Handled
+            }
+            Base::g(_) => {
+                let regs = match self.register_cache.take() {
+                    Some((tid, regs)) if tid == self.current_mem_tid => regs,
+                    _ => {
+                        let mut regs: <T::Arch as Arch>::Registers = Default::default();
+                        match target.base_ops() {
+                            BaseOps::SingleThread(ops) => ops.read_registers(&mut regs),
+                            BaseOps::MultiThread(ops) => {
+                                self.ensure_thread_stopped(ops, self.current_mem_tid)?;
+                                ops.read_registers(&mut regs, self.current_mem_tid)
+                            }
+                        }
+                        .handle_error()?;
+                        regs
+                    }
+                };
 
                 let mut err = Ok(());
                 regs.gdb_serialize(|val| {
@@ -245,6 +527,13 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     }
                 });
                 err?;
+
+                // in non-stop mode, other threads may still be running (and modifying
+                // shared state), so registers are always re-read
+                if target.use_register_cache() && !self.features.non_stop() {
+                    self.register_cache = Some((self.current_mem_tid, regs));
+                }
+
                 HandlerStatus::Handled
             }
             Base::G(cmd) => {
@@ -254,7 +543,10 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
                 match target.base_ops() {
                     BaseOps::SingleThread(ops) => ops.write_registers(&regs),
-                    BaseOps::MultiThread(ops) => ops.write_registers(&regs, self.current_mem_tid),
+                    BaseOps::MultiThread(ops) => {
+                        self.ensure_thread_stopped(ops, self.current_mem_tid)?;
+                        ops.write_registers(&regs, self.current_mem_tid)
+                    }
                 }
                 .handle_error()?;
 
@@ -305,6 +597,18 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
                 HandlerStatus::NeedsOk
             }
+            // GDB uses this packet to check that unknown `v` packets are replied to with an
+            // empty response, so it must _always_ be answered with an empty packet (regardless
+            // of how unknown commands end up being handled).
+            Base::vMustReplyEmpty(_) => HandlerStatus::Handled,
+            Base::QListThreadsInStopReply(_) => match target.base_ops() {
+                // single-threaded targets don't have a thread list worth reporting
+                BaseOps::SingleThread(_) => HandlerStatus::Handled,
+                BaseOps::MultiThread(_) => {
+                    self.features.set_list_threads_in_stop_reply(true);
+                    HandlerStatus::NeedsOk
+                }
+            },
             Base::k(_) | Base::vKill(_) => {
                 match target.support_extended_mode() {
                     // When not running in extended mode, stop the `GdbStub` and disconnect.
@@ -319,6 +623,7 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                         };
 
                         let should_terminate = ops.kill(pid).handle_error()?;
+                        self.clear_hw_breakpoints();
                         if should_terminate.into_bool() {
                             // manually write OK, since we need to return a DisconnectReason
                             res.write_str("OK")?;
@@ -330,15 +635,35 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 }
             }
             Base::D(cmd) => {
-                // TODO: plumb-through Pid when exposing full multiprocess + extended mode
-                let _pid = cmd.pid;
-                res.write_str("OK")?; // manually write OK, since we need to return a DisconnectReason
-                HandlerStatus::Disconnect(DisconnectReason::Disconnect)
+                // When running in extended mode, detaching from one of several processes
+                // keeps the session alive.
+                let should_terminate = match target.support_extended_mode() {
+                    None => true,
+                    Some(ops) => ops.detach(cmd.pid).handle_error()?.into_bool(),
+                };
+                self.clear_hw_breakpoints();
+
+                if should_terminate {
+                    // manually write OK, since we need to return a DisconnectReason
+                    res.write_str("OK")?;
+                    HandlerStatus::Disconnect(DisconnectReason::Disconnect)
+                } else {
+                    HandlerStatus::NeedsOk
+                }
             }
 
             // ------------------- Multi-threading Support ------------------ //
             Base::H(cmd) => {
                 use crate::protocol::commands::_h_upcase::Op;
+
+                // a multiprocess thread-id may refer to a different process (e.g: the child of
+                // a recently reported fork event)
+                if let Some(IdKind::WithId(pid)) = cmd.thread.pid {
+                    if let Some(ops) = target.support_fork_events() {
+                        ops.set_current_process(pid).handle_error()?;
+                    }
+                }
+
                 match cmd.kind {
                     Op::Other => match cmd.thread.tid {
                         IdKind::Any => match self.get_sane_any_tid(target)? {
@@ -367,7 +692,13 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 }
                 HandlerStatus::NeedsOk
             }
+            Base::qfThreadInfo(_) if self.use_process_list(target) => {
+                self.thread_list_cursor = Some(0);
+                self.write_next_process_threads(res, target)?;
+                HandlerStatus::Handled
+            }
             Base::qfThreadInfo(_) => {
+                self.thread_list_cursor = None;
                 res.write_str("m")?;
                 let pid = self.get_current_pid(target)?;
 
@@ -411,18 +742,20 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 HandlerStatus::Handled
             }
             Base::qsThreadInfo(_) => {
-                res.write_str("l")?;
+                self.write_next_process_threads(res, target)?;
                 HandlerStatus::Handled
             }
             Base::T(cmd) => {
-                let alive = match cmd.thread.tid {
-                    IdKind::WithId(tid) => match target.base_ops() {
-                        BaseOps::SingleThread(_) => tid == SINGLE_THREAD_TID,
-                        BaseOps::MultiThread(ops) => {
-                            ops.is_thread_alive(tid).map_err(Error::TargetError)?
-                        }
-                    },
-                    _ => return Err(Error::PacketUnexpected),
+                // in multiprocess mode, the thread-id may refer to a thread in a different
+                // process than the current one
+                let other_pid = match cmd.thread.pid {
+                    Some(IdKind::WithId(pid)) if pid != self.get_current_pid(target)? => Some(pid),
+                    _ => None,
+                };
+
+                let alive = match other_pid {
+                    None => self.is_thread_alive(target, cmd.thread.tid)?,
+                    Some(pid) => self.is_process_thread_alive(target, pid, cmd.thread.tid)?,
                 };
                 if alive {
                     HandlerStatus::NeedsOk
@@ -435,3 +768,435 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Pid;
+    use crate::common::Tid;
+    use crate::stub::mock::*;
+    use crate::stub::state_machine::GdbStubStateMachine;
+    use crate::stub::DisconnectReason;
+    use crate::target::ext::base::multithread::MultiThreadBase;
+    use crate::target::ext::extended_mode::Args;
+    use crate::target::ext::extended_mode::AttachKind;
+    use crate::target::ext::extended_mode::CurrentActivePid;
+    use crate::target::ext::extended_mode::CurrentActivePidOps;
+    use crate::target::ext::extended_mode::ExtendedMode;
+    use crate::target::ext::extended_mode::ExtendedModeOps;
+    use crate::target::ext::extended_mode::ShouldTerminate;
+    use crate::target::ext::fork_events::ForkEvents;
+    use crate::target::ext::fork_events::ForkEventsOps;
+    use crate::target::TargetError;
+    use crate::target::TargetResult;
+
+    /// A multi-process target which can only inspect the threads of the
+    /// currently selected process.
+    struct ForkTarget {
+        current_pid: Pid,
+        /// Threads of processes 1 and 2
+        threads: [Vec<Tid>; 2],
+        calls: Vec<String>,
+    }
+
+    mock_target!(
+        ForkTarget: MultiThread,
+        support_extended_mode => ExtendedModeOps,
+        support_fork_events => ForkEventsOps,
+    );
+
+    impl MultiThreadBase for ForkTarget {
+        fn read_registers(&mut self, _regs: &mut MockRegs, _tid: Tid) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn write_registers(&mut self, _regs: &MockRegs, _tid: Tid) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn read_addrs(
+            &mut self,
+            _start_addr: u32,
+            data: &mut [u8],
+            _tid: Tid,
+        ) -> TargetResult<usize, Self> {
+            data.fill(0);
+            Ok(data.len())
+        }
+
+        fn write_addrs(
+            &mut self,
+            _start_addr: u32,
+            _data: &[u8],
+            _tid: Tid,
+        ) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn list_active_threads(&mut self, thread_is_active: &mut dyn FnMut(Tid)) -> Result<(), ()> {
+            for tid in &self.threads[self.current_pid.get() - 1] {
+                thread_is_active(*tid);
+            }
+            Ok(())
+        }
+    }
+
+    impl ForkEvents for ForkTarget {
+        fn set_current_process(&mut self, pid: Pid) -> TargetResult<(), Self> {
+            self.calls.push(format!("set_current_process {}", pid));
+            self.current_pid = pid;
+            Ok(())
+        }
+    }
+
+    impl ExtendedMode for ForkTarget {
+        fn run(
+            &mut self,
+            _filename: Option<&[u8]>,
+            _args: Args<'_, '_>,
+        ) -> TargetResult<Pid, Self> {
+            Err(TargetError::NonFatal)
+        }
+
+        fn attach(&mut self, _pid: Pid) -> TargetResult<(), Self> {
+            Err(TargetError::NonFatal)
+        }
+
+        fn query_if_attached(&mut self, _pid: Pid) -> TargetResult<AttachKind, Self> {
+            Ok(AttachKind::Run)
+        }
+
+        fn kill(&mut self, _pid: Option<Pid>) -> TargetResult<ShouldTerminate, Self> {
+            Ok(ShouldTerminate::Yes)
+        }
+
+        fn restart(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+
+        fn support_current_active_pid(&mut self) -> Option<CurrentActivePidOps<'_, Self>> {
+            Some(self)
+        }
+    }
+
+    impl CurrentActivePid for ForkTarget {
+        fn current_active_pid(&mut self) -> Result<Pid, ()> {
+            Ok(self.current_pid)
+        }
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn detach_one_of_two_processes() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            processes: vec![pid(1), pid(2)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("D;1"));
+        assert!(matches!(gdb, GdbStubStateMachine::Idle(_)));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+
+        // detaching from an unknown process is an error
+        let mut gdb = send(gdb, &mut target, &packet("D;1"));
+        assert_eq!(take_output(&mut gdb), packet("E79"));
+
+        let mut gdb = send(gdb, &mut target, &packet("D;2"));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+        assert_eq!(target.calls, ["detach 1", "detach 2"]);
+        match gdb {
+            GdbStubStateMachine::Disconnected(gdb) => {
+                assert_eq!(gdb.get_reason(), DisconnectReason::Disconnect)
+            }
+            _ => panic!("expected the session to end"),
+        }
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn kill_one_of_two_processes() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            processes: vec![pid(1), pid(2)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("vKill;1"));
+        assert!(matches!(gdb, GdbStubStateMachine::Idle(_)));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+
+        // killing an unknown process is an error
+        let mut gdb = send(gdb, &mut target, &packet("vKill;1"));
+        assert_eq!(take_output(&mut gdb), packet("E79"));
+
+        let mut gdb = send(gdb, &mut target, &packet("vKill;2"));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+        assert_eq!(target.calls, ["kill 1", "kill 2"]);
+        match gdb {
+            GdbStubStateMachine::Disconnected(gdb) => {
+                assert_eq!(gdb.get_reason(), DisconnectReason::Kill)
+            }
+            _ => panic!("expected the session to end"),
+        }
+    }
+
+    #[test]
+    fn kill_all_in_extended_mode() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            processes: vec![pid(1), pid(2)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("k"));
+        assert!(matches!(gdb, GdbStubStateMachine::Disconnected(_)));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+        assert_eq!(target.calls, ["kill all"]);
+    }
+
+    #[test]
+    fn kill_without_extended_mode_has_no_response() {
+        let mut target = MockSingleTarget::default();
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("k"));
+        assert!(matches!(gdb, GdbStubStateMachine::Disconnected(_)));
+        assert_eq!(take_output(&mut gdb), "");
+    }
+
+    #[test]
+    fn on_disconnect_callback() {
+        for (cmd, reason) in [("D", "Disconnect"), ("k", "Kill")] {
+            let mut target = MockSingleTarget {
+                protocol_callbacks: true,
+                ..Default::default()
+            };
+
+            let gdb = start(&mut target);
+            let gdb = send(gdb, &mut target, &packet(cmd));
+            assert!(matches!(gdb, GdbStubStateMachine::Disconnected(_)));
+            assert_eq!(
+                target.calls.last().unwrap(),
+                &format!("on_disconnect {}", reason)
+            );
+        }
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn responses_are_flushed_once() {
+        let mut target = MockSingleTarget::default();
+        let mut gdb = start(&mut target);
+
+        let mut flushes = match &mut gdb {
+            GdbStubStateMachine::Idle(gdb) => gdb.borrow_conn().flushes,
+            _ => panic!("expected the stub to be idle"),
+        };
+        for cmd in ["?", "qSupported:multiprocess+", "vMustReplyEmpty", "m0,4"] {
+            gdb = send(gdb, &mut target, &packet(cmd));
+            match &mut gdb {
+                GdbStubStateMachine::Idle(gdb) => {
+                    let conn = gdb.borrow_conn();
+                    assert_eq!(conn.flushes - flushes, 1, "{}", cmd);
+                    flushes = conn.flushes;
+                }
+                _ => panic!("expected the stub to be idle"),
+            }
+        }
+    }
+
+    #[test]
+    fn vmustreplyempty() {
+        let mut target = MockSingleTarget::default();
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("vMustReplyEmpty"));
+        assert_eq!(take_output(&mut gdb), packet(""));
+    }
+
+    #[test]
+    fn qattached() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            processes: vec![pid(1), pid(2)],
+            spawned: vec![pid(2)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qAttached:1"));
+        assert_eq!(take_output(&mut gdb), packet("1"));
+        let mut gdb = send(gdb, &mut target, &packet("qAttached:2"));
+        assert_eq!(take_output(&mut gdb), packet("0"));
+        let mut gdb = send(gdb, &mut target, &packet("qAttached:3"));
+        assert_eq!(take_output(&mut gdb), packet("E79"));
+    }
+
+    #[test]
+    fn qattached_without_extended_mode() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qAttached"));
+        assert_eq!(take_output(&mut gdb), packet("1"));
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn qc_without_extended_mode() {
+        use crate::stub::MultiThreadStopReason;
+
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            ..Default::default()
+        };
+
+        // before any thread is selected, the first thread is reported
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qC"));
+        assert_eq!(take_output(&mut gdb), packet("QC01"));
+
+        // thread 2 hits a breakpoint, without the stop reason specifying a thread
+        let gdb = match send(gdb, &mut target, &packet("vCont;c")) {
+            GdbStubStateMachine::Running(gdb) => gdb,
+            _ => panic!("expected the target to be running"),
+        };
+        target.current_thread = Some(tid(2));
+        let mut gdb = gdb
+            .report_stop(&mut target, MultiThreadStopReason::DoneStep)
+            .unwrap();
+        take_output(&mut gdb);
+
+        let gdb = send(gdb, &mut target, &packet("qSupported:multiprocess+"));
+        let mut gdb = send(gdb, &mut target, &packet("qC"));
+        assert!(take_output(&mut gdb).ends_with(&packet("QCp01.02")));
+
+        // single-threaded targets have no current thread to report
+        let mut target = MockSingleTarget::default();
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("qC"));
+        let mut gdb = send(gdb, &mut target, &packet("qCRC:0,4"));
+        assert_eq!(take_output(&mut gdb), packet("").repeat(2));
+    }
+
+    #[test]
+    fn registers_follow_selected_thread() {
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            ..Default::default()
+        };
+
+        // registers are serialized as a little-endian u32 PC
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("Hg1"));
+        let mut gdb = send(gdb, &mut target, &packet("g"));
+        assert_eq!(take_output(&mut gdb), packet("OK") + &packet("01003412"));
+
+        let gdb = send(gdb, &mut target, &packet("Hg2"));
+        let mut gdb = send(gdb, &mut target, &packet("g"));
+        assert_eq!(take_output(&mut gdb), packet("OK") + &packet("02003412"));
+
+        let mut gdb = send(gdb, &mut target, &packet("G01003412"));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+        assert_eq!(target.calls, ["write_registers 2 0x12340001"]);
+    }
+
+    #[test]
+    fn thread_list_spans_processes() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            process_threads: vec![
+                (pid(1), vec![tid(1), tid(2)]),
+                (pid(2), vec![]),
+                (pid(3), vec![tid(7)]),
+            ],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qSupported:multiprocess+"));
+        take_output(&mut gdb);
+        let mut gdb = send(gdb, &mut target, &packet("qfThreadInfo"));
+        assert_eq!(take_output(&mut gdb), packet("mp01.01,p01.02"));
+
+        // process 2 has no threads, and is skipped over
+        for expected in ["mp03.07", "l", "l"] {
+            gdb = send(gdb, &mut target, &packet("qsThreadInfo"));
+            assert_eq!(take_output(&mut gdb), packet(expected));
+        }
+
+        // a new sequence starts over from the first process
+        let mut gdb = send(gdb, &mut target, &packet("qfThreadInfo"));
+        assert_eq!(take_output(&mut gdb), packet("mp01.01,p01.02"));
+    }
+
+    #[test]
+    fn detach_without_extended_mode() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("D"));
+        assert!(matches!(gdb, GdbStubStateMachine::Disconnected(_)));
+    }
+
+    #[test]
+    fn thread_alive_in_listed_process() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            processes: vec![pid(1), pid(2)],
+            process_threads: vec![(pid(1), vec![tid(1)]), (pid(2), vec![tid(5)])],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qSupported:multiprocess+"));
+        take_output(&mut gdb);
+        for (pkt, expected) in [
+            ("Tp2.5", "OK"),
+            ("Tp2.6", "E01"),
+            ("Tp2.-1", "OK"),
+            ("Tp3.1", "E01"),
+            ("Tp1.1", "OK"),
+            ("Tp1.5", "E01"),
+        ] {
+            gdb = send(gdb, &mut target, &packet(pkt));
+            assert_eq!(take_output(&mut gdb), packet(expected), "{}", pkt);
+        }
+        assert!(target.calls.is_empty());
+    }
+
+    #[test]
+    fn thread_alive_in_other_process_restores_current_process() {
+        let mut target = ForkTarget {
+            current_pid: pid(1),
+            threads: [vec![tid(1)], vec![tid(5)]],
+            calls: Vec::new(),
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qSupported:multiprocess+"));
+        take_output(&mut gdb);
+        let gdb = send(gdb, &mut target, &packet("Tp2.5"));
+        let mut gdb = send(gdb, &mut target, &packet("Tp2.1"));
+        assert_eq!(take_output(&mut gdb), packet("OK") + &packet("E01"));
+        assert_eq!(target.current_pid, pid(1));
+        assert_eq!(
+            target.calls,
+            [
+                "set_current_process 2",
+                "set_current_process 1",
+                "set_current_process 2",
+                "set_current_process 1",
+            ]
+        );
+    }
+}
@@ -2,12 +2,89 @@ use super::prelude::*;
 use crate::arch::Arch;
 use crate::arch::BreakpointKind;
 use crate::protocol::commands::ext::Breakpoints;
+use crate::target::ext::breakpoints::AgentExprs;
+use crate::target::TargetResult;
 
 enum CmdKind {
     Add,
     Remove,
 }
 
+/// Maximum hardware breakpoint (or watchpoint) limit which can be enforced by
+/// `gdbstub`. Larger limits are left to the target to enforce.
+const HW_SLOTS_LEN: usize = 16;
+
+/// `(type, addr, kind / len)` of a hardware breakpoint or watchpoint.
+type HwSlot<U> = (u8, U, U);
+
+/// Hardware breakpoints / watchpoints which are currently set.
+///
+/// Slots are identified by their `(type, addr, kind)`, as GDB re-inserts
+/// existing breakpoints (e.g: when updating their conditions), which mustn't
+/// take up another slot.
+pub(crate) struct HwSlots<U> {
+    slots: [Option<HwSlot<U>>; HW_SLOTS_LEN],
+}
+
+impl<U: Copy + PartialEq> HwSlots<U> {
+    pub(crate) fn new() -> Self {
+        HwSlots {
+            slots: [None; HW_SLOTS_LEN],
+        }
+    }
+
+    /// Forget about all set breakpoints (e.g: after the target is restarted).
+    pub(crate) fn clear(&mut self) {
+        *self = HwSlots::new();
+    }
+
+    fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    fn find(&mut self, slot: Option<HwSlot<U>>) -> Option<&mut Option<HwSlot<U>>> {
+        self.slots.iter_mut().find(|s| **s == slot)
+    }
+}
+
+/// Invoke `f` to add/remove a hardware breakpoint or watchpoint, keeping
+/// `slots` up to date, and rejecting new additions past the target's `limit`.
+fn track_in_use<T: Target, C>(
+    slots: &mut HwSlots<<T::Arch as Arch>::Usize>,
+    slot: HwSlot<<T::Arch as Arch>::Usize>,
+    limit: Option<usize>,
+    cmd_kind: &CmdKind,
+    f: impl FnOnce() -> TargetResult<bool, T>,
+) -> Result<TargetResult<bool, T>, Error<T::Error, C>> {
+    let limit = match limit {
+        Some(limit) if limit <= HW_SLOTS_LEN => limit,
+        _ => return Ok(f()),
+    };
+
+    let is_set = slots.find(Some(slot)).is_some();
+    if matches!(cmd_kind, CmdKind::Add) && !is_set && slots.len() >= limit {
+        return Err(Error::NonFatalError(22));
+    }
+
+    let res = f();
+    if let Ok(true) = res {
+        match cmd_kind {
+            CmdKind::Add if !is_set => {
+                if let Some(free) = slots.find(None) {
+                    *free = Some(slot)
+                }
+            }
+            CmdKind::Add => {}
+            CmdKind::Remove => {
+                if let Some(set) = slots.find(Some(slot)) {
+                    *set = None
+                }
+            }
+        }
+    }
+    Ok(res)
+}
+
 impl<T: Target, C: Connection> GdbStubImpl<T, C> {
     #[inline(always)]
     fn handle_breakpoint_common(
@@ -39,10 +116,18 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             1 if ops.support_hw_breakpoint().is_some() => {
                 let ops = ops.support_hw_breakpoint().unwrap();
                 let bp_kind = bp_kind!();
-                match cmd_kind {
-                    CmdKind::Add => ops.add_hw_breakpoint(addr, bp_kind),
-                    CmdKind::Remove => ops.remove_hw_breakpoint(addr, bp_kind),
-                }
+                let limit = ops.hw_breakpoint_count();
+                let kind = BeBytes::from_be_bytes(cmd.kind).ok_or(Error::TargetMismatch)?;
+                track_in_use::<T, C::Error>(
+                    &mut self.hw_breakpoints,
+                    (cmd.type_, addr, kind),
+                    limit,
+                    &cmd_kind,
+                    || match cmd_kind {
+                        CmdKind::Add => ops.add_hw_breakpoint(addr, bp_kind),
+                        CmdKind::Remove => ops.remove_hw_breakpoint(addr, bp_kind),
+                    },
+                )?
             }
             2 | 3 | 4 if ops.support_hw_watchpoint().is_some() => {
                 use crate::target::ext::breakpoints::WatchKind;
@@ -56,10 +141,17 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 let len = <T::Arch as Arch>::Usize::from_be_bytes(cmd.kind)
                     .ok_or(Error::TargetMismatch)?;
                 let ops = ops.support_hw_watchpoint().unwrap();
-                match cmd_kind {
-                    CmdKind::Add => ops.add_hw_watchpoint(addr, len, kind),
-                    CmdKind::Remove => ops.remove_hw_watchpoint(addr, len, kind),
-                }
+                let limit = ops.hw_watchpoint_count();
+                track_in_use::<T, C::Error>(
+                    &mut self.hw_watchpoints,
+                    (cmd.type_, addr, len),
+                    limit,
+                    &cmd_kind,
+                    || match cmd_kind {
+                        CmdKind::Add => ops.add_hw_watchpoint(addr, len, kind),
+                        CmdKind::Remove => ops.remove_hw_watchpoint(addr, len, kind),
+                    },
+                )?
             }
             // explicitly handle unguarded variants of known breakpoint types
             0 | 1 | 2 | 3 | 4 => return Ok(HandlerStatus::Handled),
@@ -92,9 +184,448 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         let handler_status = match command {
             Breakpoints::z(cmd) => self.handle_breakpoint_common(ops, cmd, CmdKind::Remove)?,
             Breakpoints::Z(cmd) => self.handle_breakpoint_common(ops, cmd, CmdKind::Add)?,
-            // TODO: handle ZWithBytecode once agent expressions are implemented
-            _ => HandlerStatus::Handled,
+            Breakpoints::ZWithBytecode(cmd) => {
+                let crate::protocol::commands::breakpoint::BytecodeBreakpoint {
+                    base,
+                    conds,
+                    cmds_persist,
+                } = cmd;
+
+                // agent expressions only apply to software and hardware breakpoints
+                let (type_, addr, kind) = (base.type_, base.addr, base.kind);
+                let is_breakpoint = matches!(type_, 0 | 1);
+
+                // make sure GDB doesn't assume commands were installed when they weren't
+                if cmds_persist.is_some()
+                    && (!is_breakpoint || ops.support_breakpoint_commands().is_none())
+                {
+                    return Err(Error::NonFatalError(22));
+                }
+
+                let handler_status = self.handle_breakpoint_common(ops, base, CmdKind::Add)?;
+                if !matches!(handler_status, HandlerStatus::NeedsOk) || !is_breakpoint {
+                    return Ok(handler_status);
+                }
+
+                let addr =
+                    <T::Arch as Arch>::Usize::from_be_bytes(addr).ok_or(Error::TargetMismatch)?;
+                // `BreakpointKind` isn't `Clone`, so it's decoded separately for each call
+                let decode_kind = || {
+                    BeBytes::from_be_bytes(kind)
+                        .and_then(<T::Arch as Arch>::BreakpointKind::from_usize)
+                        .ok_or(Error::TargetMismatch)
+                };
+
+                let ops = match target.support_breakpoints() {
+                    Some(ops) => ops,
+                    None => return Ok(handler_status),
+                };
+
+                if let Some(ops) = ops.support_conditional_breakpoints() {
+                    let mut conds = conds.into_iter().flat_map(|conds| conds.into_iter());
+                    ops.set_breakpoint_conditions(
+                        addr,
+                        decode_kind()?,
+                        AgentExprs::new(&mut conds),
+                    )
+                    .handle_error()?;
+                }
+
+                if let Some(ops) = ops.support_breakpoint_commands() {
+                    let (cmds, persist) = match cmds_persist {
+                        Some((cmds, persist)) => (Some(cmds), persist),
+                        None => (None, false),
+                    };
+                    let mut cmds = cmds.into_iter().flat_map(|cmds| cmds.into_iter());
+                    ops.set_breakpoint_commands(
+                        addr,
+                        decode_kind()?,
+                        AgentExprs::new(&mut cmds),
+                        persist,
+                    )
+                    .handle_error()?;
+                }
+
+                handler_status
+            }
         };
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Pid;
+    use crate::stub::mock::*;
+    use crate::target::ext::breakpoints::AgentExprs;
+    use crate::target::ext::breakpoints::BreakpointCommands;
+    use crate::target::ext::breakpoints::BreakpointCommandsOps;
+    use crate::target::ext::breakpoints::Breakpoints;
+    use crate::target::ext::breakpoints::BreakpointsOps;
+    use crate::target::ext::breakpoints::ConditionalBreakpoints;
+    use crate::target::ext::breakpoints::ConditionalBreakpointsOps;
+    use crate::target::ext::breakpoints::HwBreakpoint;
+    use crate::target::ext::breakpoints::HwBreakpointOps;
+    use crate::target::ext::breakpoints::HwWatchpoint;
+    use crate::target::ext::breakpoints::HwWatchpointOps;
+    use crate::target::ext::breakpoints::SwBreakpoint;
+    use crate::target::ext::breakpoints::SwBreakpointOps;
+    use crate::target::ext::breakpoints::WatchKind;
+    use crate::target::ext::extended_mode::Args;
+    use crate::target::ext::extended_mode::AttachKind;
+    use crate::target::ext::extended_mode::ExtendedMode;
+    use crate::target::ext::extended_mode::ExtendedModeOps;
+    use crate::target::ext::extended_mode::ShouldTerminate;
+    use crate::target::TargetError;
+    use crate::target::TargetResult;
+
+    /// A target which records every breakpoint operation made on it.
+    #[derive(Default)]
+    struct BreakpointTarget {
+        /// Value returned from `hw_breakpoint_count`.
+        hw_breakpoint_limit: Option<usize>,
+        /// Whether the target supports breakpoint commands.
+        commands: bool,
+        calls: Vec<String>,
+    }
+
+    mock_target!(BreakpointTarget: SingleThread, support_breakpoints => BreakpointsOps);
+    zeroed_single_thread_base!(BreakpointTarget);
+
+    impl Breakpoints for BreakpointTarget {
+        fn support_sw_breakpoint(&mut self) -> Option<SwBreakpointOps<'_, Self>> {
+            Some(self)
+        }
+
+        fn support_conditional_breakpoints(
+            &mut self,
+        ) -> Option<ConditionalBreakpointsOps<'_, Self>> {
+            Some(self)
+        }
+
+        fn support_hw_breakpoint(&mut self) -> Option<HwBreakpointOps<'_, Self>> {
+            Some(self)
+        }
+
+        fn support_hw_watchpoint(&mut self) -> Option<HwWatchpointOps<'_, Self>> {
+            Some(self)
+        }
+
+        fn support_breakpoint_commands(&mut self) -> Option<BreakpointCommandsOps<'_, Self>> {
+            if self.commands {
+                Some(self)
+            } else {
+                None
+            }
+        }
+    }
+
+    impl SwBreakpoint for BreakpointTarget {
+        fn add_sw_breakpoint(&mut self, addr: u32, kind: usize) -> TargetResult<bool, Self> {
+            self.calls
+                .push(format!("add_sw_breakpoint {:#x} {}", addr, kind));
+            Ok(true)
+        }
+
+        fn remove_sw_breakpoint(&mut self, addr: u32, kind: usize) -> TargetResult<bool, Self> {
+            self.calls
+                .push(format!("remove_sw_breakpoint {:#x} {}", addr, kind));
+            Ok(true)
+        }
+    }
+
+    impl ConditionalBreakpoints for BreakpointTarget {
+        fn set_breakpoint_conditions(
+            &mut self,
+            addr: u32,
+            _kind: usize,
+            conds: AgentExprs<'_, '_>,
+        ) -> TargetResult<(), Self> {
+            let conds = conds.map(|c| format!("{:02x?}", c)).collect::<Vec<_>>();
+            self.calls
+                .push(format!("set_breakpoint_conditions {:#x} {:?}", addr, conds));
+            Ok(())
+        }
+    }
+
+    impl HwBreakpoint for BreakpointTarget {
+        fn add_hw_breakpoint(&mut self, addr: u32, kind: usize) -> TargetResult<bool, Self> {
+            self.calls
+                .push(format!("add_hw_breakpoint {:#x} {}", addr, kind));
+            Ok(true)
+        }
+
+        fn remove_hw_breakpoint(&mut self, addr: u32, kind: usize) -> TargetResult<bool, Self> {
+            self.calls
+                .push(format!("remove_hw_breakpoint {:#x} {}", addr, kind));
+            Ok(true)
+        }
+
+        fn hw_breakpoint_count(&self) -> Option<usize> {
+            self.hw_breakpoint_limit
+        }
+    }
+
+    impl HwWatchpoint for BreakpointTarget {
+        fn add_hw_watchpoint(
+            &mut self,
+            addr: u32,
+            len: u32,
+            kind: WatchKind,
+        ) -> TargetResult<bool, Self> {
+            self.calls
+                .push(format!("add_hw_watchpoint {:#x} {} {:?}", addr, len, kind));
+            Ok(true)
+        }
+
+        fn remove_hw_watchpoint(
+            &mut self,
+            addr: u32,
+            len: u32,
+            kind: WatchKind,
+        ) -> TargetResult<bool, Self> {
+            self.calls.push(format!(
+                "remove_hw_watchpoint {:#x} {} {:?}",
+                addr, len, kind
+            ));
+            Ok(true)
+        }
+    }
+
+    impl BreakpointCommands for BreakpointTarget {
+        fn set_breakpoint_commands(
+            &mut self,
+            addr: u32,
+            _kind: usize,
+            cmds: AgentExprs<'_, '_>,
+            persist: bool,
+        ) -> TargetResult<(), Self> {
+            let cmds = cmds.map(|c| format!("{:02x?}", c)).collect::<Vec<_>>();
+            self.calls.push(format!(
+                "set_breakpoint_commands {:#x} {:?} {}",
+                addr, cmds, persist
+            ));
+            Ok(())
+        }
+    }
+
+    /// An extended-mode target with a single hardware breakpoint slot.
+    #[derive(Default)]
+    struct OneSlotTarget {
+        calls: Vec<String>,
+    }
+
+    mock_target!(
+        OneSlotTarget: SingleThread,
+        support_breakpoints => BreakpointsOps,
+        support_extended_mode => ExtendedModeOps,
+    );
+    zeroed_single_thread_base!(OneSlotTarget);
+
+    impl Breakpoints for OneSlotTarget {
+        fn support_hw_breakpoint(&mut self) -> Option<HwBreakpointOps<'_, Self>> {
+            Some(self)
+        }
+    }
+
+    impl HwBreakpoint for OneSlotTarget {
+        fn add_hw_breakpoint(&mut self, addr: u32, kind: usize) -> TargetResult<bool, Self> {
+            self.calls
+                .push(format!("add_hw_breakpoint {:#x} {}", addr, kind));
+            Ok(true)
+        }
+
+        fn remove_hw_breakpoint(&mut self, addr: u32, kind: usize) -> TargetResult<bool, Self> {
+            self.calls
+                .push(format!("remove_hw_breakpoint {:#x} {}", addr, kind));
+            Ok(true)
+        }
+
+        fn hw_breakpoint_count(&self) -> Option<usize> {
+            Some(1)
+        }
+    }
+
+    impl ExtendedMode for OneSlotTarget {
+        fn run(
+            &mut self,
+            _filename: Option<&[u8]>,
+            _args: Args<'_, '_>,
+        ) -> TargetResult<Pid, Self> {
+            Err(TargetError::NonFatal)
+        }
+
+        fn attach(&mut self, _pid: Pid) -> TargetResult<(), Self> {
+            Err(TargetError::NonFatal)
+        }
+
+        fn query_if_attached(&mut self, _pid: Pid) -> TargetResult<AttachKind, Self> {
+            Ok(AttachKind::Run)
+        }
+
+        fn kill(&mut self, _pid: Option<Pid>) -> TargetResult<ShouldTerminate, Self> {
+            self.calls.push("kill".into());
+            Ok(ShouldTerminate::No)
+        }
+
+        fn restart(&mut self) -> Result<(), ()> {
+            self.calls.push("restart".into());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn conditional_breakpoints() {
+        let mut target = BreakpointTarget::default();
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qSupported:swbreak+"));
+        assert!(take_output(&mut gdb).contains(";ConditionalBreakpoints+"));
+
+        let gdb = send(gdb, &mut target, &packet("Z0,1000,4;X3,220027X1,27"));
+        let mut gdb = send(gdb, &mut target, &packet("Z0,2000,4"));
+        assert_eq!(take_output(&mut gdb), packet("OK").repeat(2));
+        assert_eq!(
+            target.calls,
+            [
+                "add_sw_breakpoint 0x1000 4",
+                r#"set_breakpoint_conditions 0x1000 ["[22, 00, 27]", "[27]"]"#,
+                "add_sw_breakpoint 0x2000 4",
+                "set_breakpoint_conditions 0x2000 []",
+            ]
+        );
+    }
+
+    #[test]
+    fn breakpoint_commands() {
+        let mut target = BreakpointTarget {
+            commands: true,
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qSupported:swbreak+"));
+        assert!(take_output(&mut gdb).contains(";BreakpointCommands+"));
+
+        let mut gdb = send(gdb, &mut target, &packet("Z0,1000,4;cmds:1,X1,27"));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+        assert_eq!(
+            target.calls,
+            [
+                "add_sw_breakpoint 0x1000 4",
+                "set_breakpoint_conditions 0x1000 []",
+                r#"set_breakpoint_commands 0x1000 ["[27]"] true"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn breakpoint_commands_unsupported() {
+        let mut target = BreakpointTarget::default();
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qSupported:swbreak+"));
+        assert!(!take_output(&mut gdb).contains(";BreakpointCommands+"));
+
+        // commands are rejected, instead of being silently dropped
+        let mut gdb = send(gdb, &mut target, &packet("Z0,1000,4;cmds:1,X1,27"));
+        assert_eq!(take_output(&mut gdb), packet("E16"));
+        assert!(target.calls.is_empty());
+    }
+
+    #[test]
+    fn watchpoint_kinds() {
+        let mut target = BreakpointTarget::default();
+
+        let mut gdb = start(&mut target);
+        for pkt in ["Z2,1000,4", "Z3,2000,2", "Z4,3000,1", "z2,1000,4"] {
+            gdb = send(gdb, &mut target, &packet(pkt));
+            assert_eq!(take_output(&mut gdb), packet("OK"));
+        }
+        assert_eq!(
+            target.calls,
+            [
+                "add_hw_watchpoint 0x1000 4 Write",
+                "add_hw_watchpoint 0x2000 2 Read",
+                "add_hw_watchpoint 0x3000 1 ReadWrite",
+                "remove_hw_watchpoint 0x1000 4 Write",
+            ]
+        );
+    }
+
+    #[test]
+    fn hw_breakpoint_limit() {
+        let mut target = BreakpointTarget {
+            hw_breakpoint_limit: Some(2),
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("Z1,1000,4"));
+        let gdb = send(gdb, &mut target, &packet("Z1,2000,4"));
+        let mut gdb = send(gdb, &mut target, &packet("Z1,3000,4"));
+        assert_eq!(
+            take_output(&mut gdb),
+            packet("OK").repeat(2) + &packet("E16")
+        );
+
+        // freeing up a slot allows another breakpoint to be set
+        let gdb = send(gdb, &mut target, &packet("z1,1000,4"));
+        let mut gdb = send(gdb, &mut target, &packet("Z1,3000,4"));
+        assert_eq!(take_output(&mut gdb), packet("OK").repeat(2));
+        target
+            .calls
+            .retain(|call| !call.starts_with("set_breakpoint_conditions"));
+        assert_eq!(
+            target.calls,
+            [
+                "add_hw_breakpoint 0x1000 4",
+                "add_hw_breakpoint 0x2000 4",
+                "remove_hw_breakpoint 0x1000 4",
+                "add_hw_breakpoint 0x3000 4",
+            ]
+        );
+    }
+
+    #[test]
+    fn hw_breakpoint_readd_keeps_slot() {
+        let mut target = OneSlotTarget::default();
+
+        // GDB re-inserts breakpoints whenever their conditions change
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("Z1,1000,4"));
+        let gdb = send(gdb, &mut target, &packet("Z1,1000,4;X1,27"));
+        let mut gdb = send(gdb, &mut target, &packet("Z1,2000,4"));
+        assert_eq!(
+            take_output(&mut gdb),
+            packet("OK").repeat(2) + &packet("E16")
+        );
+        assert_eq!(
+            target.calls,
+            ["add_hw_breakpoint 0x1000 4", "add_hw_breakpoint 0x1000 4"]
+        );
+    }
+
+    #[test]
+    fn hw_breakpoint_slots_reset_on_restart_and_kill() {
+        let mut target = OneSlotTarget::default();
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("Z1,1000,4"));
+        let gdb = send(gdb, &mut target, &packet("R00"));
+        let gdb = send(gdb, &mut target, &packet("Z1,2000,4"));
+        let gdb = send(gdb, &mut target, &packet("k"));
+        let mut gdb = send(gdb, &mut target, &packet("Z1,3000,4"));
+        assert_eq!(take_output(&mut gdb), packet("OK").repeat(4));
+        assert_eq!(
+            target.calls,
+            [
+                "add_hw_breakpoint 0x1000 4",
+                "restart",
+                "add_hw_breakpoint 0x2000 4",
+                "kill",
+                "add_hw_breakpoint 0x3000 4",
+            ]
+        );
+    }
+}
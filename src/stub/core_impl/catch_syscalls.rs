@@ -47,3 +47,87 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Signal;
+    use crate::stub::mock::*;
+    use crate::stub::state_machine::GdbStubStateMachine;
+    use crate::stub::SingleThreadStopReason;
+    use crate::target::ext::base::singlethread::SingleThreadResume;
+    use crate::target::ext::base::singlethread::SingleThreadResumeOps;
+    use crate::target::ext::catch_syscalls::CatchSyscallPosition;
+    use crate::target::ext::catch_syscalls::CatchSyscalls;
+    use crate::target::ext::catch_syscalls::CatchSyscallsOps;
+    use crate::target::ext::catch_syscalls::SyscallNumbers;
+    use crate::target::TargetResult;
+
+    #[derive(Default)]
+    struct CatchSyscallsTarget {
+        calls: Vec<String>,
+    }
+
+    mock_target!(CatchSyscallsTarget: SingleThread, support_catch_syscalls => CatchSyscallsOps);
+    zeroed_single_thread_base!(CatchSyscallsTarget, support_resume => SingleThreadResumeOps);
+
+    impl SingleThreadResume for CatchSyscallsTarget {
+        fn resume(&mut self, _signal: Option<Signal>) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    impl CatchSyscalls for CatchSyscallsTarget {
+        fn enable_catch_syscalls(
+            &mut self,
+            filter: Option<SyscallNumbers<'_, u32>>,
+        ) -> TargetResult<(), Self> {
+            let filter = filter.map(|filter| filter.collect::<Vec<_>>());
+            self.calls
+                .push(format!("enable_catch_syscalls {:x?}", filter));
+            Ok(())
+        }
+
+        fn disable_catch_syscalls(&mut self) -> TargetResult<(), Self> {
+            self.calls.push("disable_catch_syscalls".into());
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn catch_syscalls() {
+        let mut target = CatchSyscallsTarget::default();
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("QCatchSyscalls:1"));
+        let gdb = send(gdb, &mut target, &packet("QCatchSyscalls:1;3c;e7"));
+        let mut gdb = send(gdb, &mut target, &packet("QCatchSyscalls:0"));
+        assert_eq!(take_output(&mut gdb), packet("OK").repeat(3));
+        assert_eq!(
+            target.calls,
+            [
+                "enable_catch_syscalls None",
+                "enable_catch_syscalls Some([3c, e7])",
+                "disable_catch_syscalls",
+            ]
+        );
+
+        for (position, expected) in [
+            (CatchSyscallPosition::Entry, "T05syscall_entry:e7;"),
+            (CatchSyscallPosition::Return, "T05syscall_return:e7;"),
+        ] {
+            let stop_reason = SingleThreadStopReason::CatchSyscall {
+                tid: None,
+                number: 0xe7,
+                position,
+            };
+            gdb = match send(gdb, &mut target, &packet("c")) {
+                GdbStubStateMachine::Running(gdb) => {
+                    gdb.report_stop(&mut target, stop_reason).unwrap()
+                }
+                _ => panic!("expected the target to be running"),
+            };
+            assert_eq!(take_output(&mut gdb), packet(expected));
+        }
+    }
+}
@@ -17,8 +17,9 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
         let handler_status = match command {
             ExecFile::qXferExecFileRead(cmd) => {
+                let length = self.xfer_read_len(cmd.length, cmd.buf.len());
                 let ret = ops
-                    .get_exec_file(cmd.annex.pid, cmd.offset, cmd.length, cmd.buf)
+                    .get_exec_file(cmd.annex.pid, cmd.offset, length, cmd.buf)
                     .handle_error()?;
                 if ret == 0 {
                     res.write_str("l")?;
@@ -34,3 +35,54 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Pid;
+    use crate::stub::mock::*;
+    use crate::target::ext::copy_range_to_buf;
+    use crate::target::ext::exec_file::ExecFile;
+    use crate::target::ext::exec_file::ExecFileOps;
+    use crate::target::TargetResult;
+
+    struct ExecFileTarget;
+
+    mock_target!(ExecFileTarget: SingleThread, support_exec_file => ExecFileOps);
+    zeroed_single_thread_base!(ExecFileTarget);
+
+    impl ExecFile for ExecFileTarget {
+        fn get_exec_file(
+            &self,
+            _pid: Option<Pid>,
+            offset: u64,
+            length: usize,
+            buf: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            Ok(copy_range_to_buf(b"/bin/true", offset, length, buf))
+        }
+    }
+
+    #[test]
+    fn xfer_respects_client_packet_size() {
+        let mut target = ExecFileTarget;
+        let mut gdb = start(&mut target);
+
+        // without a client-advertised packet size, the entire file fits
+        gdb = send(gdb, &mut target, &packet("qXfer:exec-file:read::0,100"));
+        assert_eq!(take_output(&mut gdb), packet("m/bin/true"));
+
+        // a client packet size of 13 fits `(13 - 5) / 2 = 4` bytes per chunk
+        gdb = send(gdb, &mut target, &packet("qSupported:PacketSize=d"));
+        take_output(&mut gdb);
+        let mut chunks = Vec::new();
+        for offset in [0, 4, 8, 9] {
+            let cmd = format!("qXfer:exec-file:read::{:x},100", offset);
+            gdb = send(gdb, &mut target, &packet(&cmd));
+            chunks.push(take_output(&mut gdb));
+        }
+        assert_eq!(
+            chunks,
+            [packet("m/bin"), packet("m/tru"), packet("me"), packet("l")]
+        );
+    }
+}
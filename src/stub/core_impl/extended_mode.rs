@@ -1,8 +1,6 @@
 use super::prelude::*;
 use crate::protocol::commands::ext::ExtendedMode;
 use crate::protocol::SpecificIdKind;
-use crate::protocol::SpecificThreadId;
-use crate::target::ext::base::BaseOps;
 use crate::SINGLE_THREAD_TID;
 
 impl<T: Target, C: Connection> GdbStubImpl<T, C> {
@@ -26,7 +24,14 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             }
             ExtendedMode::R(_cmd) => {
                 ops.restart().map_err(Error::TargetError)?;
-                HandlerStatus::Handled
+                self.clear_hw_breakpoints();
+
+                // any previously selected threads are stale after a restart
+                self.current_mem_tid = SINGLE_THREAD_TID;
+                self.current_resume_tid = SpecificIdKind::WithId(SINGLE_THREAD_TID);
+
+                // "There is no reply to this command"
+                HandlerStatus::NoResponse
             }
             ExtendedMode::vAttach(cmd) => {
                 if ops.support_current_active_pid().is_none() {
@@ -36,64 +41,20 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 ops.attach(cmd.pid).handle_error()?;
                 self.report_reasonable_stop_reason(res, target)?
             }
-            ExtendedMode::qC(_cmd) if ops.support_current_active_pid().is_some() => {
-                let ops = ops.support_current_active_pid().unwrap();
-
-                res.write_str("QC")?;
-                let pid = ops.current_active_pid().map_err(Error::TargetError)?;
-                let tid = match target.base_ops() {
-                    BaseOps::SingleThread(_) => SINGLE_THREAD_TID,
-                    BaseOps::MultiThread(ops) => {
-                        // HACK: gdbstub should avoid using a sentinel value here...
-                        if self.current_mem_tid == SINGLE_THREAD_TID {
-                            let mut err: Result<_, Error<T::Error, C::Error>> = Ok(());
-                            let mut first_tid = None;
-                            ops.list_active_threads(&mut |tid| {
-                                // TODO: replace this with a try block (once stabilized)
-                                let e = (|| {
-                                    if first_tid.is_some() {
-                                        return Ok(());
-                                    }
-                                    first_tid = Some(tid);
-                                    Ok(())
-                                })();
-
-                                if let Err(e) = e {
-                                    err = Err(e)
-                                }
-                            })
-                            .map_err(Error::TargetError)?;
-                            err?;
-                            first_tid.unwrap_or(SINGLE_THREAD_TID)
-                        } else {
-                            self.current_mem_tid
-                        }
-                    }
-                };
-
-                res.write_specific_thread_id(SpecificThreadId {
-                    pid: self
-                        .features
-                        .multiprocess()
-                        .then_some(SpecificIdKind::WithId(pid)),
-                    tid: SpecificIdKind::WithId(tid),
-                })?;
-
-                HandlerStatus::Handled
-            }
             ExtendedMode::vRun(cmd) => {
                 use crate::target::ext::extended_mode::Args;
 
                 let _pid = ops
-                    .run(cmd.filename, Args::new(&mut cmd.args.into_iter()))
+                    .run(cmd.filename, Args::new(&mut cmd.args.into_program_args()))
                     .handle_error()?;
+                self.clear_hw_breakpoints();
 
                 self.report_reasonable_stop_reason(res, target)?
             }
             // --------- ASLR --------- //
             ExtendedMode::QDisableRandomization(cmd) if ops.support_configure_aslr().is_some() => {
                 let ops = ops.support_configure_aslr().unwrap();
-                ops.cfg_aslr(cmd.value).handle_error()?;
+                ops.cfg_disable_randomization(cmd.value).handle_error()?;
                 HandlerStatus::NeedsOk
             }
             // --------- Environment --------- //
@@ -133,3 +94,77 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+    use crate::stub::state_machine::GdbStubStateMachine;
+
+    #[test]
+    fn restart_has_no_response() {
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            processes: vec![pid(1)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("Hg2"));
+        let mut gdb = send(gdb, &mut target, &packet("R00"));
+        assert!(matches!(gdb, GdbStubStateMachine::Idle(_)));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+        assert_eq!(target.calls, ["restart"]);
+    }
+
+    #[test]
+    fn disable_randomization() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            processes: vec![pid(1)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("QDisableRandomization:1"));
+        let mut gdb = send(gdb, &mut target, &packet("QDisableRandomization:0"));
+        assert_eq!(take_output(&mut gdb), packet("OK").repeat(2));
+        assert_eq!(
+            target.calls,
+            [
+                "cfg_disable_randomization true",
+                "cfg_disable_randomization false"
+            ]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn qc_reports_stopped_thread() {
+        use crate::stub::MultiThreadStopReason;
+
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            processes: vec![pid(1)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("Hg1"));
+        let mut gdb = send(gdb, &mut target, &packet("qC"));
+        assert_eq!(take_output(&mut gdb), packet("OK") + &packet("QC01"));
+
+        // thread 2 stops, without the stop reason specifying a thread
+        let gdb = match send(gdb, &mut target, &packet("vCont;c:1")) {
+            GdbStubStateMachine::Running(gdb) => gdb,
+            _ => panic!("expected the target to be running"),
+        };
+        target.current_thread = Some(tid(2));
+        let mut gdb = gdb
+            .report_stop(&mut target, MultiThreadStopReason::DoneStep)
+            .unwrap();
+        take_output(&mut gdb);
+
+        let mut gdb = send(gdb, &mut target, &packet("qC"));
+        assert_eq!(take_output(&mut gdb), packet("QC02"));
+    }
+}
@@ -0,0 +1,106 @@
+use super::prelude::*;
+use crate::arch::Arch;
+use crate::protocol::commands::ext::FlashOperations;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_flash_operations(
+        &mut self,
+        _res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: FlashOperations<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_flash_operations() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("flash_operations", "impl");
+
+        let handler_status = match command {
+            FlashOperations::vFlashErase(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+                let length = <T::Arch as Arch>::Usize::from_be_bytes(cmd.length)
+                    .ok_or(Error::TargetMismatch)?;
+
+                ops.flash_erase(addr, length).handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+            FlashOperations::vFlashWrite(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                ops.flash_write(addr, cmd.data).handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+            FlashOperations::vFlashDone(_) => {
+                ops.flash_done().handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+    use crate::target::ext::flash_operations::FlashOperations;
+    use crate::target::ext::flash_operations::FlashOperationsOps;
+    use crate::target::TargetError;
+    use crate::target::TargetResult;
+
+    /// A target which records every flash operation, and rejects empty
+    /// erases.
+    #[derive(Default)]
+    struct FlashTarget {
+        calls: Vec<String>,
+    }
+
+    mock_target!(FlashTarget: SingleThread, support_flash_operations => FlashOperationsOps);
+    zeroed_single_thread_base!(FlashTarget);
+
+    impl FlashOperations for FlashTarget {
+        fn flash_erase(&mut self, start_addr: u32, length: u32) -> TargetResult<(), Self> {
+            if length == 0 {
+                return Err(TargetError::Errno(22));
+            }
+            self.calls
+                .push(format!("flash_erase {:#x} {:#x}", start_addr, length));
+            Ok(())
+        }
+
+        fn flash_write(&mut self, start_addr: u32, data: &[u8]) -> TargetResult<(), Self> {
+            self.calls
+                .push(format!("flash_write {:#x} {:02x?}", start_addr, data));
+            Ok(())
+        }
+
+        fn flash_done(&mut self) -> TargetResult<(), Self> {
+            self.calls.push("flash_done".into());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn flash_programming_sequence() {
+        let mut target = FlashTarget::default();
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("vFlashErase:08000000,800"));
+        let gdb = send(gdb, &mut target, &packet("vFlashWrite:08000000:\x01}\x03"));
+        let mut gdb = send(gdb, &mut target, &packet("vFlashDone"));
+        assert_eq!(take_output(&mut gdb), packet("OK").repeat(3));
+        assert_eq!(
+            target.calls,
+            [
+                "flash_erase 0x8000000 0x800",
+                "flash_write 0x8000000 [01, 23]",
+                "flash_done"
+            ]
+        );
+
+        let mut gdb = send(gdb, &mut target, &packet("vFlashErase:08000000,0"));
+        assert_eq!(take_output(&mut gdb), packet("E16"));
+    }
+}
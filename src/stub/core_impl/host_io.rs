@@ -4,6 +4,37 @@ use crate::protocol::commands::ext::HostIo;
 use crate::target::ext::host_io::HostIoError;
 use crate::target::ext::host_io::HostIoStat;
 
+/// Size of the `struct stat` GDB expects in response to `vFile:fstat`.
+const HOST_IO_STAT_SIZE: usize = 64;
+
+/// Serialize a [`HostIoStat`] using the (big-endian) layout of GDB's
+/// [`struct stat`](https://sourceware.org/gdb/current/onlinedocs/gdb/struct-stat.html#struct-stat).
+fn encode_stat(stat: &HostIoStat) -> [u8; HOST_IO_STAT_SIZE] {
+    let mut buf = [0; HOST_IO_STAT_SIZE];
+    let fields: [&[u8]; 13] = [
+        &stat.st_dev.to_be_bytes(),
+        &stat.st_ino.to_be_bytes(),
+        &stat.st_mode.bits().to_be_bytes(),
+        &stat.st_nlink.to_be_bytes(),
+        &stat.st_uid.to_be_bytes(),
+        &stat.st_gid.to_be_bytes(),
+        &stat.st_rdev.to_be_bytes(),
+        &stat.st_size.to_be_bytes(),
+        &stat.st_blksize.to_be_bytes(),
+        &stat.st_blocks.to_be_bytes(),
+        &stat.st_atime.to_be_bytes(),
+        &stat.st_mtime.to_be_bytes(),
+        &stat.st_ctime.to_be_bytes(),
+    ];
+
+    let mut i = 0;
+    for field in fields.iter() {
+        buf[i..i + field.len()].copy_from_slice(field);
+        i += field.len();
+    }
+    buf
+}
+
 impl<T: Target, C: Connection> GdbStubImpl<T, C> {
     pub(crate) fn handle_host_io(
         &mut self,
@@ -80,23 +111,10 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 let ops = ops.support_fstat().unwrap();
                 handle_hostio_result! {
                     if let Ok(stat) = ops.fstat(cmd.fd) => {
-                        let size = core::mem::size_of::<HostIoStat>();
                         res.write_str("F")?;
-                        res.write_num(size)?;
+                        res.write_num(HOST_IO_STAT_SIZE)?;
                         res.write_str(";")?;
-                        res.write_binary(&stat.st_dev.to_be_bytes())?;
-                        res.write_binary(&stat.st_ino.to_be_bytes())?;
-                        res.write_binary(&(stat.st_mode.bits()).to_be_bytes())?;
-                        res.write_binary(&stat.st_nlink.to_be_bytes())?;
-                        res.write_binary(&stat.st_uid.to_be_bytes())?;
-                        res.write_binary(&stat.st_gid.to_be_bytes())?;
-                        res.write_binary(&stat.st_rdev.to_be_bytes())?;
-                        res.write_binary(&stat.st_size.to_be_bytes())?;
-                        res.write_binary(&stat.st_blksize.to_be_bytes())?;
-                        res.write_binary(&stat.st_blocks.to_be_bytes())?;
-                        res.write_binary(&stat.st_atime.to_be_bytes())?;
-                        res.write_binary(&stat.st_mtime.to_be_bytes())?;
-                        res.write_binary(&stat.st_ctime.to_be_bytes())?;
+                        res.write_binary(&encode_stat(&stat))?;
                     }
                 };
                 HandlerStatus::Handled
@@ -138,3 +156,48 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::target::ext::host_io::HostIoOpenMode;
+    use core::convert::TryInto;
+
+    #[test]
+    fn fstat_layout() {
+        let stat = HostIoStat {
+            st_dev: 1,
+            st_ino: 2,
+            st_mode: HostIoOpenMode::S_IFREG | HostIoOpenMode::S_IRUSR,
+            st_nlink: 3,
+            st_uid: 4,
+            st_gid: 5,
+            st_rdev: 6,
+            st_size: 0x0123_4567_89ab_cdef,
+            st_blksize: 4096,
+            st_blocks: 8,
+            st_atime: 9,
+            st_mtime: 10,
+            st_ctime: 11,
+        };
+
+        let buf = encode_stat(&stat);
+
+        let u32_at = |i: usize| u32::from_be_bytes(buf[i..i + 4].try_into().unwrap());
+        let u64_at = |i: usize| u64::from_be_bytes(buf[i..i + 8].try_into().unwrap());
+
+        assert_eq!(u32_at(0), 1);
+        assert_eq!(u32_at(4), 2);
+        assert_eq!(u32_at(8), 0o100400);
+        assert_eq!(u32_at(12), 3);
+        assert_eq!(u32_at(16), 4);
+        assert_eq!(u32_at(20), 5);
+        assert_eq!(u32_at(24), 6);
+        assert_eq!(u64_at(28), 0x0123_4567_89ab_cdef);
+        assert_eq!(u64_at(36), 4096);
+        assert_eq!(u64_at(44), 8);
+        assert_eq!(u32_at(52), 9);
+        assert_eq!(u32_at(56), 10);
+        assert_eq!(u32_at(60), 11);
+    }
+}
@@ -1,5 +1,8 @@
 use super::prelude::*;
+use super::xfer_window::XferWindow;
+use crate::arch::Arch;
 use crate::protocol::commands::ext::LibrariesSvr4;
+use crate::target::ext::libraries::LibrarySvr4;
 
 impl<T: Target, C: Connection> GdbStubImpl<T, C> {
     pub(crate) fn handle_libraries_svr4(
@@ -17,16 +20,42 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
         let handler_status = match command {
             LibrariesSvr4::qXferLibrariesSvr4Read(cmd) => {
-                let ret = ops
-                    .get_libraries_svr4(cmd.offset, cmd.length, cmd.buf)
-                    .handle_error()?;
-                if ret == 0 {
-                    res.write_str("l")?;
-                } else {
-                    res.write_str("m")?;
-                    // TODO: add more specific error variant?
-                    res.write_binary(cmd.buf.get(..ret).ok_or(Error::PacketBufferOverflow)?)?;
+                let len = self.xfer_read_len(cmd.length, cmd.buf.len());
+                let mut xml = XferWindow::new(&mut cmd.buf[..len], cmd.offset);
+
+                xml.write(b"<library-list-svr4 version=\"1.0\"");
+                if let Some(main_lm) = ops.main_lm().handle_error()? {
+                    xml.write(b" main-lm=\"");
+                    xml.write_hex_num(main_lm);
+                    xml.write(b"\"");
                 }
+                xml.write(b">\n");
+                ops.list_libraries_svr4(&mut |lib: LibrarySvr4<'_, <T::Arch as Arch>::Usize>| {
+                    xml.write(b"<library name=\"");
+                    xml.write_escaped(lib.name);
+                    xml.write(b"\" lm=\"");
+                    xml.write_hex_num(lib.lm);
+                    xml.write(b"\" l_addr=\"");
+                    xml.write_hex_num(lib.l_addr);
+                    xml.write(b"\" l_ld=\"");
+                    xml.write_hex_num(lib.l_ld);
+                    xml.write(b"\"");
+                    if let Some(lmid) = lib.lmid {
+                        xml.write(b" lmid=\"");
+                        xml.write_hex_num(lmid);
+                        xml.write(b"\"");
+                    }
+                    xml.write(b"/>\n");
+                })
+                .handle_error()?;
+                xml.write(b"</library-list-svr4>\n");
+
+                let is_last = xml.is_last();
+                let written = xml.written();
+
+                res.write_str(if is_last { "l" } else { "m" })?;
+                res.write_binary(&cmd.buf[..written])?;
+
                 HandlerStatus::Handled
             }
         };
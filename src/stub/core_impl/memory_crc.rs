@@ -0,0 +1,143 @@
+use super::prelude::*;
+use crate::arch::Arch;
+use crate::protocol::commands::ext::MemoryCrc;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_memory_crc(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: MemoryCrc<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_memory_crc() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("memory_crc", "impl");
+
+        let handler_status = match command {
+            MemoryCrc::qCRC(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+                let len = <T::Arch as Arch>::Usize::from_be_bytes(cmd.len)
+                    .ok_or(Error::TargetMismatch)?;
+
+                let crc = ops.compute_crc(addr, len).handle_error()?;
+
+                res.write_str("C")?;
+                res.write_num(crc)?;
+
+                HandlerStatus::Handled
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Pid;
+    use crate::stub::mock::*;
+    use crate::target::ext::extended_mode::Args;
+    use crate::target::ext::extended_mode::AttachKind;
+    use crate::target::ext::extended_mode::ExtendedMode;
+    use crate::target::ext::extended_mode::ExtendedModeOps;
+    use crate::target::ext::extended_mode::ShouldTerminate;
+    use crate::target::ext::memory_crc::crc32;
+    use crate::target::ext::memory_crc::MemoryCrc;
+    use crate::target::ext::memory_crc::MemoryCrcOps;
+    use crate::target::TargetError;
+    use crate::target::TargetResult;
+
+    /// A target whose memory is all zeroes.
+    #[derive(Default)]
+    struct CrcTarget {
+        calls: Vec<String>,
+    }
+
+    mock_target!(CrcTarget: SingleThread, support_memory_crc => MemoryCrcOps);
+    zeroed_single_thread_base!(CrcTarget);
+
+    impl MemoryCrc for CrcTarget {
+        fn compute_crc(&mut self, addr: u32, len: u32) -> TargetResult<u32, Self> {
+            self.calls
+                .push(format!("compute_crc {:#x} {:#x}", addr, len));
+            Ok(crc32(0xffffffff, &vec![0; len as usize]))
+        }
+    }
+
+    /// An extended-mode target, whose `qC` handler must not shadow `qCRC`.
+    #[derive(Default)]
+    struct ExtendedCrcTarget {
+        calls: Vec<String>,
+    }
+
+    mock_target!(
+        ExtendedCrcTarget: SingleThread,
+        support_memory_crc => MemoryCrcOps,
+        support_extended_mode => ExtendedModeOps,
+    );
+    zeroed_single_thread_base!(ExtendedCrcTarget);
+
+    impl MemoryCrc for ExtendedCrcTarget {
+        fn compute_crc(&mut self, addr: u32, len: u32) -> TargetResult<u32, Self> {
+            self.calls
+                .push(format!("compute_crc {:#x} {:#x}", addr, len));
+            Ok(0x1234)
+        }
+    }
+
+    impl ExtendedMode for ExtendedCrcTarget {
+        fn run(
+            &mut self,
+            _filename: Option<&[u8]>,
+            _args: Args<'_, '_>,
+        ) -> TargetResult<Pid, Self> {
+            Err(TargetError::NonFatal)
+        }
+
+        fn attach(&mut self, _pid: Pid) -> TargetResult<(), Self> {
+            Err(TargetError::NonFatal)
+        }
+
+        fn query_if_attached(&mut self, _pid: Pid) -> TargetResult<AttachKind, Self> {
+            Ok(AttachKind::Run)
+        }
+
+        fn kill(&mut self, _pid: Option<Pid>) -> TargetResult<ShouldTerminate, Self> {
+            Ok(ShouldTerminate::Yes)
+        }
+
+        fn restart(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn qcrc() {
+        let mut target = CrcTarget::default();
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qCRC:8000,4"));
+        assert_eq!(take_output(&mut gdb), packet("Cc704dd7b"));
+        assert_eq!(target.calls, ["compute_crc 0x8000 0x4"]);
+    }
+
+    #[test]
+    fn qcrc_unsupported() {
+        let mut target = MockSingleTarget::default();
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qCRC:8000,4"));
+        assert_eq!(take_output(&mut gdb), packet(""));
+    }
+
+    #[test]
+    fn qcrc_with_extended_mode() {
+        let mut target = ExtendedCrcTarget::default();
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qCRC:8000,4"));
+        assert_eq!(take_output(&mut gdb), packet("C1234"));
+        assert_eq!(target.calls, ["compute_crc 0x8000 0x4"]);
+    }
+}
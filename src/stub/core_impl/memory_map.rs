@@ -17,15 +17,26 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
         let handler_status = match command {
             MemoryMap::qXferMemoryMapRead(cmd) => {
+                let length = self.xfer_read_len(cmd.length, cmd.buf.len());
                 let ret = ops
-                    .memory_map_xml(cmd.offset, cmd.length, cmd.buf)
+                    .memory_map_xml(cmd.offset, length, cmd.buf)
                     .handle_error()?;
                 if ret == 0 {
                     res.write_str("l")?;
                 } else {
                     res.write_str("m")?;
                     // TODO: add more specific error variant?
-                    res.write_binary(cmd.buf.get(..ret).ok_or(Error::PacketBufferOverflow)?)?;
+                    let data = cmd.buf.get(..ret).ok_or(Error::PacketBufferOverflow)?;
+                    #[cfg(all(feature = "validate-memory-map", debug_assertions))]
+                    #[allow(clippy::panic)]
+                    if let Err((tag, reason)) = validate_memory_elements(data) {
+                        panic!(
+                            "target returned malformed memory-map XML ({}): {}",
+                            reason,
+                            core::str::from_utf8(tag).unwrap_or("<invalid utf-8>")
+                        );
+                    }
+                    res.write_binary(data)?;
                 }
                 HandlerStatus::Handled
             }
@@ -34,3 +45,176 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+/// Check that every complete `<memory>` element in `xml` has a valid `type`,
+/// and numeric `start` and `length` attributes.
+///
+/// Elements which are split across `qXfer` chunks are not checked.
+#[cfg(any(test, all(feature = "validate-memory-map", debug_assertions)))]
+fn validate_memory_elements(xml: &[u8]) -> Result<(), (&[u8], &'static str)> {
+    fn attr<'a>(tag: &'a [u8], name: &[u8]) -> Option<&'a [u8]> {
+        let pos = tag
+            .windows(name.len() + 1)
+            .position(|w| w[..name.len()] == *name && w[name.len()] == b'=')?;
+        if !tag[pos.checked_sub(1)?].is_ascii_whitespace() {
+            return None;
+        }
+        let val = &tag[pos + name.len() + 1..];
+        let quote = *val.first().filter(|q| **q == b'"' || **q == b'\'')?;
+        let val = &val[1..];
+        Some(&val[..val.iter().position(|b| *b == quote)?])
+    }
+
+    fn is_number(val: &[u8]) -> bool {
+        match val.strip_prefix(b"0x").or_else(|| val.strip_prefix(b"0X")) {
+            Some(hex) => !hex.is_empty() && hex.iter().all(u8::is_ascii_hexdigit),
+            None => !val.is_empty() && val.iter().all(u8::is_ascii_digit),
+        }
+    }
+
+    let mut rest = xml;
+    while let Some(start) = rest.windows(7).position(|w| w == b"<memory") {
+        let elem = &rest[start..];
+        let tag = match elem.iter().position(|b| *b == b'>') {
+            Some(end) => &elem[..=end],
+            None => break,
+        };
+        rest = &elem[tag.len()..];
+
+        // skip over `<memory-map>`
+        if !tag[7].is_ascii_whitespace() {
+            continue;
+        }
+
+        match attr(tag, b"type") {
+            Some(b"ram") | Some(b"rom") | Some(b"flash") => {}
+            _ => return Err((tag, "invalid `type`")),
+        }
+        if !attr(tag, b"start").is_some_and(is_number) {
+            return Err((tag, "invalid `start`"));
+        }
+        if !attr(tag, b"length").is_some_and(is_number) {
+            return Err((tag, "invalid `length`"));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_memory_elements;
+    use crate::stub::mock::*;
+    use crate::target::ext::copy_range_to_buf;
+    use crate::target::ext::memory_map::MemoryMap;
+    use crate::target::ext::memory_map::MemoryMapOps;
+    use crate::target::TargetResult;
+
+    /// A target which reports the given memory-map XML.
+    struct MemoryMapTarget(String);
+
+    mock_target!(MemoryMapTarget: SingleThread, support_memory_map => MemoryMapOps);
+    zeroed_single_thread_base!(MemoryMapTarget);
+
+    impl MemoryMap for MemoryMapTarget {
+        fn memory_map_xml(
+            &self,
+            offset: u64,
+            length: usize,
+            buf: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            Ok(copy_range_to_buf(self.0.as_bytes(), offset, length, buf))
+        }
+    }
+
+    /// Strip the packet framing from `reply`, undoing any run-length
+    /// encoding.
+    fn decode(reply: &str) -> Vec<u8> {
+        let body = &reply.as_bytes()[1..reply.len() - 3];
+        let mut out = Vec::new();
+        let mut bytes = body.iter();
+        while let Some(&b) = bytes.next() {
+            if b == b'*' {
+                let repeat = (bytes.next().unwrap() - 29) as usize;
+                let last = *out.last().unwrap();
+                out.resize(out.len() + repeat, last);
+            } else {
+                out.push(b);
+            }
+        }
+        out
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn large_memory_map_is_chunked() {
+        let mut xml = String::from("<memory-map>\n");
+        for i in 0..100u64 {
+            xml += &format!(
+                "<memory type=\"ram\" start=\"{:#x}\" length=\"0x1000\"/>\n",
+                i * 0x10000
+            );
+        }
+        xml += "</memory-map>";
+        assert!(xml.len() > 4096);
+
+        let mut target = MemoryMapTarget(xml.clone());
+        let mut gdb = start(&mut target);
+
+        let mut chunks = 0;
+        let mut out = Vec::new();
+        loop {
+            let cmd = format!("qXfer:memory-map:read::{:x},ffff", out.len());
+            gdb = send(gdb, &mut target, &packet(&cmd));
+            let reply = decode(&take_output(&mut gdb));
+            match reply.split_first() {
+                Some((b'm', data)) => out.extend_from_slice(data),
+                Some((b'l', [])) => break,
+                _ => panic!("unexpected reply: {:?}", reply),
+            }
+            chunks += 1;
+        }
+
+        assert!(chunks > 1);
+        assert_eq!(String::from_utf8(out).unwrap(), xml);
+    }
+
+    #[test]
+    fn memory_elements_are_validated() {
+        let ok = br#"<memory-map>
+<memory type="ram" start="0x0" length="0x1000"/>
+<memory type='flash' start="4096" length="0X2000">
+<property name="blocksize">0x400</property>
+</memory>
+<memory type="rom" st"#;
+        assert_eq!(validate_memory_elements(ok), Ok(()));
+
+        let bad_start = br#"<memory type="ram" start="0xg" length="0x1000"/>"#;
+        assert_eq!(
+            validate_memory_elements(bad_start),
+            Err((&bad_start[..], "invalid `start`"))
+        );
+
+        let bad_length = br#"<memory type="ram" start="0x0"/>"#;
+        assert_eq!(
+            validate_memory_elements(bad_length),
+            Err((&bad_length[..], "invalid `length`"))
+        );
+
+        let bad_type = br#"<memory type="sram" start="0x0" length="0x10"/>"#;
+        assert_eq!(
+            validate_memory_elements(bad_type),
+            Err((&bad_type[..], "invalid `type`"))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "validate-memory-map", debug_assertions))]
+    #[should_panic(expected = "malformed memory-map XML (invalid `start`)")]
+    fn malformed_memory_map_panics() {
+        let mut target =
+            MemoryMapTarget(r#"<memory type="ram" start="zero" length="0x10"/>"#.into());
+        let gdb = start(&mut target);
+        send(gdb, &mut target, &packet("qXfer:memory-map:read::0,ffff"));
+    }
+}
@@ -0,0 +1,40 @@
+use super::prelude::*;
+use crate::arch::Arch;
+use crate::protocol::commands::ext::MemorySearch;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_memory_search(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: MemorySearch<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_memory_search() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("memory_search", "impl");
+
+        let handler_status = match command {
+            MemorySearch::qSearchMemory(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+                let len = <T::Arch as Arch>::Usize::from_be_bytes(cmd.len)
+                    .ok_or(Error::TargetMismatch)?;
+
+                match ops.search_memory(addr, len, cmd.pattern).handle_error()? {
+                    Some(addr) => {
+                        res.write_str("1,")?;
+                        res.write_num(addr)?;
+                    }
+                    None => res.write_str("0")?,
+                }
+
+                HandlerStatus::Handled
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
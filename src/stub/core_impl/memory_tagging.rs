@@ -0,0 +1,107 @@
+use super::prelude::*;
+use crate::arch::Arch;
+use crate::protocol::commands::ext::MemoryTagging;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_memory_tagging(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: MemoryTagging<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_memory_tagging() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("memory_tagging", "impl");
+
+        let handler_status = match command {
+            MemoryTagging::qMemTags(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                // the reply is hex-encoded, so only half of the buffer can be filled
+                let buf_len = self.xfer_read_len(cmd.buf.len() / 2, cmd.buf.len());
+                let buf = &mut cmd.buf[..buf_len];
+
+                let ret = ops
+                    .read_memory_tags(addr, cmd.len, cmd.ty, buf)
+                    .handle_error()?;
+                // TODO: add more specific error variant?
+                let data = buf.get(..ret).ok_or(Error::PacketBufferOverflow)?;
+
+                res.write_str("m")?;
+                res.write_hex_buf(data)?;
+                HandlerStatus::Handled
+            }
+            MemoryTagging::QMemTags(cmd) => {
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                ops.write_memory_tags(addr, cmd.len, cmd.ty, cmd.tags)
+                    .handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+    use crate::target::ext::memory_tagging::MemoryTagging;
+    use crate::target::ext::memory_tagging::MemoryTaggingOps;
+    use crate::target::TargetResult;
+
+    /// A target which fills whatever buffer it is given with tags.
+    #[derive(Default)]
+    struct TagTarget {
+        buf_len: usize,
+    }
+
+    mock_target!(TagTarget: SingleThread, support_memory_tagging => MemoryTaggingOps);
+    zeroed_single_thread_base!(TagTarget);
+
+    impl MemoryTagging for TagTarget {
+        fn read_memory_tags(
+            &mut self,
+            _start_addr: u32,
+            _len: usize,
+            _ty: i32,
+            buf: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            self.buf_len = buf.len();
+            buf[0] = 0x12;
+            Ok(1)
+        }
+
+        fn write_memory_tags(
+            &mut self,
+            _start_addr: u32,
+            _len: usize,
+            _ty: i32,
+            _tags: &[u8],
+        ) -> TargetResult<(), Self> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn read_memory_tags_fits_in_reply() {
+        let mut target = TagTarget::default();
+        let gdb = start(&mut target);
+
+        let mut gdb = send(gdb, &mut target, &packet("qMemTags:1000,2000:1"));
+        assert_eq!(take_output(&mut gdb), packet("m12"));
+        // the hex-encoded reply must fit in the (default, 4096 byte) packet buffer
+        assert!(target.buf_len > 0 && target.buf_len * 2 + 5 <= 4096);
+
+        let gdb = send(gdb, &mut target, &packet("qSupported:PacketSize=20"));
+        let gdb = send(gdb, &mut target, &packet("qMemTags:1000,2000:1"));
+        drop(gdb);
+        assert_eq!(target.buf_len, (0x20 - 5) / 2);
+    }
+}
@@ -19,15 +19,22 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         let handler_status = match command {
             MonitorCmd::qRcmd(cmd) => {
                 let use_rle = ops.use_rle();
+                // leave room for the `$O` prefix, and the `#xx` checksum suffix
+                let max_chunk_len = match self.client_packet_size {
+                    Some(size) => (size.saturating_sub(5) / 2).max(1),
+                    None => usize::MAX,
+                };
 
                 let mut err: Result<_, Error<T::Error, C::Error>> = Ok(());
                 let mut callback = |msg: &[u8]| {
                     // TODO: replace this with a try block (once stabilized)
                     let e = (|| {
-                        let mut res = ResponseWriter::new(res.as_conn(), use_rle);
-                        res.write_str("O")?;
-                        res.write_hex_buf(msg)?;
-                        res.flush()?;
+                        for chunk in msg.chunks(max_chunk_len) {
+                            let mut res = ResponseWriter::new(res.as_conn(), use_rle);
+                            res.write_str("O")?;
+                            res.write_hex_buf(chunk)?;
+                            res.flush()?;
+                        }
                         Ok(())
                     })();
 
@@ -36,9 +43,10 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     }
                 };
 
-                ops.handle_monitor_cmd(cmd.hex_cmd, ConsoleOutput::new(&mut callback))
-                    .map_err(Error::TargetError)?;
+                let res = ops.handle_monitor_cmd(cmd.hex_cmd, ConsoleOutput::new(&mut callback));
+                // any output must make it to the client before an error is reported
                 err?;
+                res.handle_error()?;
 
                 HandlerStatus::NeedsOk
             }
@@ -47,3 +55,117 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+    use crate::target::ext::monitor_cmd::ConsoleOutput;
+    use crate::target::ext::monitor_cmd::MonitorCmd;
+    use crate::target::ext::monitor_cmd::MonitorCmdOps;
+    use crate::target::ext::protocol_callbacks::ClientFeatures;
+    use crate::target::ext::protocol_callbacks::ProtocolCallbacks;
+    use crate::target::ext::protocol_callbacks::ProtocolCallbacksOps;
+    use crate::target::TargetError;
+    use crate::target::TargetResult;
+
+    /// A target which echoes each word of a monitor command on its own line,
+    /// except for `fail`.
+    #[derive(Default)]
+    struct MonitorTarget {
+        calls: Vec<String>,
+    }
+
+    mock_target!(
+        MonitorTarget: SingleThread,
+        support_monitor_cmd => MonitorCmdOps,
+        support_protocol_callbacks => ProtocolCallbacksOps,
+    );
+    zeroed_single_thread_base!(MonitorTarget);
+
+    impl MonitorCmd for MonitorTarget {
+        fn handle_monitor_cmd(
+            &mut self,
+            cmd: &[u8],
+            mut out: ConsoleOutput<'_>,
+        ) -> TargetResult<(), Self> {
+            self.calls
+                .push(format!("monitor {}", String::from_utf8_lossy(cmd)));
+            if cmd == b"fail" {
+                out.write_raw(b"oops\n");
+                return Err(TargetError::NonFatal);
+            }
+            // each line is flushed as soon as it is written
+            for line in cmd.split(|b| *b == b' ') {
+                out.write_raw(line);
+                out.write_raw(b"\n");
+                out.flush();
+            }
+            Ok(())
+        }
+    }
+
+    impl ProtocolCallbacks for MonitorTarget {
+        fn on_client_features(&mut self, features: ClientFeatures) {
+            self.calls
+                .push(format!("packet_size={:?}", features.packet_size()));
+        }
+    }
+
+    #[test]
+    fn monitor_output_is_streamed() {
+        let mut target = MonitorTarget::default();
+
+        // "reset sleep"
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qRcmd,726573657420736c656570"));
+        assert_eq!(
+            take_output(&mut gdb),
+            packet("O72657365740a") + &packet("O736c6565700a") + &packet("OK")
+        );
+        assert_eq!(target.calls, ["monitor reset sleep"]);
+    }
+
+    #[test]
+    fn monitor_output_is_binary_safe() {
+        let mut target = MonitorTarget::default();
+
+        // non-UTF-8 bytes are hex-encoded as-is
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qRcmd,00ff80"));
+        assert_eq!(take_output(&mut gdb), packet("O00ff800a") + &packet("OK"));
+    }
+
+    #[test]
+    fn monitor_cmd_failure() {
+        let mut target = MonitorTarget::default();
+
+        // "fail"
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qRcmd,6661696c"));
+        assert_eq!(
+            take_output(&mut gdb),
+            packet("O6f6f70730a") + &packet("E79")
+        );
+    }
+
+    #[test]
+    fn monitor_output_fits_client_packet_size() {
+        let mut target = MonitorTarget::default();
+
+        let gdb = start(&mut target);
+        let mut gdb = send(
+            gdb,
+            &mut target,
+            &packet("qSupported:PacketSize=b;multiprocess+"),
+        );
+        take_output(&mut gdb);
+        assert_eq!(target.calls, ["packet_size=Some(11)"]);
+
+        // "reset"
+        let mut gdb = send(gdb, &mut target, &packet("qRcmd,7265736574"));
+        assert_eq!(
+            take_output(&mut gdb),
+            packet("O726573") + &packet("O65740a") + &packet("OK")
+        );
+    }
+}
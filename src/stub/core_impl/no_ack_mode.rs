@@ -23,3 +23,48 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+    use crate::stub::GdbStub;
+
+    #[test]
+    fn no_ack_mode_callback() {
+        let mut target = MockSingleTarget {
+            protocol_callbacks: true,
+            ..Default::default()
+        };
+
+        let gdb = GdbStub::new(MockConnection::default())
+            .run_state_machine(&mut target)
+            .unwrap();
+        let gdb = send(gdb, &mut target, &packet("qAttached"));
+        assert!(target.calls.is_empty());
+
+        let mut gdb = send(gdb, &mut target, &packet("QStartNoAckMode"));
+        assert_eq!(target.calls, ["on_no_ack_mode_enabled"]);
+        assert!(take_output(&mut gdb).ends_with(&format!("+{}", packet("OK"))));
+
+        // the callback only fires on the transition
+        send(gdb, &mut target, &packet("QStartNoAckMode"));
+        assert_eq!(target.calls, ["on_no_ack_mode_enabled"]);
+    }
+
+    #[test]
+    fn assume_no_ack_mode() {
+        use crate::stub::GdbStubBuilder;
+
+        let mut target = MockSingleTarget::default();
+
+        let gdb = GdbStubBuilder::new(MockConnection::default())
+            .assume_no_ack_mode()
+            .build()
+            .unwrap()
+            .run_state_machine(&mut target)
+            .unwrap();
+        // the very first packet isn't acked
+        let mut gdb = send(gdb, &mut target, &packet("qAttached"));
+        assert_eq!(take_output(&mut gdb), packet("1"));
+    }
+}
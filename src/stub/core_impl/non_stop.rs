@@ -0,0 +1,330 @@
+use super::prelude::*;
+use super::FinishExecStatus;
+use crate::common::Tid;
+use crate::protocol::commands::ext::NonStop;
+use crate::stub::MultiThreadStopReason;
+use crate::target::ext::base::multithread::MultiThreadBase;
+use crate::target::ext::base::ResumeOps;
+
+/// Maximum number of stop events which can be queued up (in non-stop mode)
+/// while waiting for the client to acknowledge an earlier stop notification.
+const STOP_QUEUE_LEN: usize = 8;
+
+/// Stop events which have yet to be reported to the client.
+pub(crate) struct StopQueue<U> {
+    events: [Option<MultiThreadStopReason<U>>; STOP_QUEUE_LEN],
+    head: usize,
+    len: usize,
+    /// A `%Stop` notification has been sent, and the client has yet to drain
+    /// the queue via `vStopped`.
+    notification_in_flight: bool,
+}
+
+impl<U: Copy> StopQueue<U> {
+    pub(crate) fn new() -> Self {
+        StopQueue {
+            events: [None; STOP_QUEUE_LEN],
+            head: 0,
+            len: 0,
+            notification_in_flight: false,
+        }
+    }
+
+    fn push(&mut self, event: MultiThreadStopReason<U>) -> Result<(), ()> {
+        if self.len == STOP_QUEUE_LEN {
+            return Err(());
+        }
+        self.events[(self.head + self.len) % STOP_QUEUE_LEN] = Some(event);
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<MultiThreadStopReason<U>> {
+        if self.len == 0 {
+            return None;
+        }
+        let event = self.events[self.head].take();
+        self.head = (self.head + 1) % STOP_QUEUE_LEN;
+        self.len -= 1;
+        event
+    }
+
+    fn clear(&mut self) {
+        *self = StopQueue::new();
+    }
+}
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_non_stop(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: NonStop,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.base_ops().resume_ops() {
+            Some(ResumeOps::MultiThread(ops)) => match ops.support_non_stop() {
+                Some(ops) => ops,
+                None => return Ok(HandlerStatus::Handled),
+            },
+            _ => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("non_stop", "impl");
+
+        let handler_status = match command {
+            NonStop::QNonStop(cmd) => {
+                ops.set_non_stop(cmd.enabled).handle_error()?;
+                self.features.set_non_stop(cmd.enabled);
+                self.stop_queue.clear();
+                HandlerStatus::NeedsOk
+            }
+            NonStop::vCtrlC(_) => {
+                if let Some(ops) = target.support_interrupt() {
+                    ops.interrupt().handle_error()?;
+                }
+                HandlerStatus::CtrlCInterrupt
+            }
+            NonStop::vStopped(_) => match self.stop_queue.pop() {
+                Some(stop_reason) => match self.finish_exec(res, target, stop_reason)? {
+                    FinishExecStatus::Handled => HandlerStatus::Handled,
+                    FinishExecStatus::Disconnect(reason) => HandlerStatus::Disconnect(reason),
+                },
+                None => {
+                    self.stop_queue.notification_in_flight = false;
+                    HandlerStatus::NeedsOk
+                }
+            },
+        };
+
+        Ok(handler_status)
+    }
+
+    /// In non-stop mode, ensure that thread `tid` is stopped before accessing
+    /// its registers.
+    pub(crate) fn ensure_thread_stopped(
+        &self,
+        ops: &mut dyn MultiThreadBase<Arch = T::Arch, Error = T::Error>,
+        tid: Tid,
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        if !self.features.non_stop() {
+            return Ok(());
+        }
+
+        let ops = match ops.support_resume().and_then(|ops| ops.support_non_stop()) {
+            Some(ops) => ops,
+            None => return Ok(()),
+        };
+
+        if ops.is_thread_stopped(tid).map_err(Error::TargetError)? {
+            Ok(())
+        } else {
+            // EBUSY
+            Err(Error::NonFatalError(16))
+        }
+    }
+
+    /// Whether the client has enabled non-stop mode.
+    pub(crate) fn is_non_stop(&self) -> bool {
+        self.features.non_stop()
+    }
+
+    /// Whether the stop queue is full, in which case no further stop events
+    /// can be reported until the client drains the queue via `vStopped`.
+    pub(crate) fn is_stop_queue_full(&self) -> bool {
+        self.is_non_stop()
+            && self.stop_queue.notification_in_flight
+            && self.stop_queue.len == STOP_QUEUE_LEN
+    }
+
+    /// Report a stop event to the client while in non-stop mode.
+    ///
+    /// If the client has yet to acknowledge a previous stop notification, the
+    /// event is queued, and will be reported in response to `vStopped`.
+    pub(crate) fn report_stop_non_stop(
+        &mut self,
+        conn: &mut C,
+        target: &mut T,
+        stop_reason: MultiThreadStopReason<<T::Arch as crate::arch::Arch>::Usize>,
+    ) -> Result<FinishExecStatus, Error<T::Error, C::Error>> {
+        if self.stop_queue.notification_in_flight {
+            self.stop_queue
+                .push(stop_reason)
+                .map_err(|_| Error::StopQueueFull)?;
+            return Ok(FinishExecStatus::Handled);
+        }
+
+        let mut res = ResponseWriter::new_notification(conn, target.use_rle());
+        res.write_str("Stop:")?;
+        let status = self.finish_exec(&mut res, target, stop_reason)?;
+        res.flush()?;
+
+        self.stop_queue.notification_in_flight = true;
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stop_queue_fifo() {
+        let mut queue = StopQueue::<u32>::new();
+        for i in 0..STOP_QUEUE_LEN as u8 {
+            queue.push(MultiThreadStopReason::Exited(i.into())).unwrap();
+        }
+        assert!(queue.push(MultiThreadStopReason::DoneStep).is_err());
+
+        for i in 0..STOP_QUEUE_LEN as u8 {
+            assert_eq!(queue.pop(), Some(MultiThreadStopReason::Exited(i.into())));
+            queue
+                .push(MultiThreadStopReason::Exited((i + 100).into()))
+                .unwrap();
+        }
+        for i in 0..STOP_QUEUE_LEN as u8 {
+            assert_eq!(
+                queue.pop(),
+                Some(MultiThreadStopReason::Exited((i + 100).into()))
+            );
+        }
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn vctrlc_ok_precedes_stop_notification() {
+        use crate::common::Signal;
+        use crate::stub::mock::*;
+        use crate::stub::state_machine::GdbStubStateMachine;
+
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            non_stop: true,
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("QNonStop:1"));
+        let mut gdb = send(gdb, &mut target, &packet("vCont;c"));
+        assert!(matches!(gdb, GdbStubStateMachine::Running(_)));
+        assert_eq!(take_output(&mut gdb), packet("OK").repeat(2));
+
+        let gdb = match send(gdb, &mut target, &packet("vCtrlC")) {
+            GdbStubStateMachine::CtrlCInterrupt(gdb) => gdb,
+            _ => panic!("expected a Ctrl-C interrupt"),
+        };
+
+        // only a single thread ends up stopping
+        let stop_reason = MultiThreadStopReason::SignalWithThread {
+            tid: tid(2),
+            signal: Signal::SIGINT,
+        };
+        let mut gdb = gdb
+            .interrupt_handled(&mut target, Some(stop_reason))
+            .unwrap();
+        assert!(matches!(gdb, GdbStubStateMachine::Running(_)));
+
+        let output = take_output(&mut gdb);
+        let notification = packet("Stop:T02thread:02;").replacen('$', "%", 1);
+        assert_eq!(output, packet("OK") + &notification);
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn stop_queue_fills_until_client_acknowledges() {
+        use crate::common::Signal;
+        use crate::stub::mock::*;
+        use crate::stub::state_machine::GdbStubStateMachine;
+
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            non_stop: true,
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("QNonStop:1"));
+        let mut gdb = send(gdb, &mut target, &packet("vCont;c"));
+        take_output(&mut gdb);
+
+        let stop_reason = MultiThreadStopReason::SignalWithThread {
+            tid: tid(1),
+            signal: Signal::SIGTRAP,
+        };
+        // one event is sent as a notification, the rest are queued
+        for _ in 0..=STOP_QUEUE_LEN {
+            gdb = match gdb {
+                GdbStubStateMachine::Running(gdb) => {
+                    assert!(!gdb.is_stop_queue_full());
+                    gdb.report_stop(&mut target, stop_reason).unwrap()
+                }
+                _ => panic!("expected the target to keep running"),
+            };
+        }
+        match &gdb {
+            GdbStubStateMachine::Running(gdb) => assert!(gdb.is_stop_queue_full()),
+            _ => panic!("expected the target to keep running"),
+        }
+
+        let gdb = send(gdb, &mut target, &packet("vStopped"));
+        match gdb {
+            GdbStubStateMachine::Running(gdb) => assert!(!gdb.is_stop_queue_full()),
+            _ => panic!("expected the target to keep running"),
+        }
+    }
+
+    #[test]
+    fn interrupt_hook_fires_on_ctrlc() {
+        use crate::stub::mock::*;
+        use crate::stub::state_machine::GdbStubStateMachine;
+
+        for (non_stop, interrupt) in [(false, "\x03".into()), (true, packet("vCtrlC"))] {
+            let mut target = MockTarget {
+                threads: vec![tid(1)],
+                non_stop,
+                ..Default::default()
+            };
+
+            let mut gdb = start(&mut target);
+            if non_stop {
+                gdb = send(gdb, &mut target, &packet("QNonStop:1"));
+            }
+            let gdb = send(gdb, &mut target, &packet("vCont;c"));
+            target.calls.clear();
+
+            let gdb = send(gdb, &mut target, &interrupt);
+            assert!(matches!(gdb, GdbStubStateMachine::CtrlCInterrupt(_)));
+            assert_eq!(target.calls, ["interrupt"]);
+        }
+    }
+
+    #[test]
+    fn running_thread_registers_are_inaccessible() {
+        use crate::stub::mock::*;
+
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            non_stop: true,
+            running: vec![tid(2)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("QNonStop:1"));
+        let mut gdb = send(gdb, &mut target, &packet("Hg2"));
+        take_output(&mut gdb);
+
+        for cmd in ["g", "G00000000", "p0", "P0=00000000"] {
+            gdb = send(gdb, &mut target, &packet(cmd));
+            assert_eq!(take_output(&mut gdb), packet("E10"), "{}", cmd);
+        }
+        assert!(!target.calls.iter().any(|c| c.starts_with("write_")));
+
+        // stopped threads remain accessible
+        let gdb = send(gdb, &mut target, &packet("Hg1"));
+        let mut gdb = send(gdb, &mut target, &packet("g"));
+        assert_eq!(take_output(&mut gdb), packet("OK") + &packet("01003412"));
+        let mut gdb = send(gdb, &mut target, &packet("p0;thread:01;"));
+        assert_eq!(take_output(&mut gdb), packet("01003412"));
+    }
+}
@@ -0,0 +1,106 @@
+use super::prelude::*;
+use crate::protocol::commands::ext::OsData;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_os_data(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: OsData<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_os_data() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("os_data", "impl");
+
+        let handler_status = match command {
+            OsData::qXferOsDataRead(cmd) => {
+                let length = self.xfer_read_len(cmd.length, cmd.buf.len());
+                let ret = ops
+                    .get_os_data(cmd.annex.kind, cmd.offset, length, cmd.buf)
+                    .handle_error()?;
+                if ret == 0 {
+                    res.write_str("l")?;
+                } else {
+                    res.write_str("m")?;
+                    // TODO: add more specific error variant?
+                    res.write_binary(cmd.buf.get(..ret).ok_or(Error::PacketBufferOverflow)?)?;
+                }
+                HandlerStatus::Handled
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+    use crate::target::ext::copy_range_to_buf;
+    use crate::target::ext::os_data::OsData;
+    use crate::target::ext::os_data::OsDataOps;
+    use crate::target::TargetError;
+    use crate::target::TargetResult;
+
+    struct OsDataTarget;
+
+    mock_target!(OsDataTarget: SingleThread, support_os_data => OsDataOps);
+    zeroed_single_thread_base!(OsDataTarget);
+
+    impl OsData for OsDataTarget {
+        fn get_os_data(
+            &mut self,
+            annex: &[u8],
+            offset: u64,
+            length: usize,
+            buf: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            let doc: &[u8] = match annex {
+                b"" => b"<osdata type=\"types\"><item><column name=\"Type\">processes</column></item></osdata>",
+                b"processes" => b"<osdata type=\"processes\"><item><column name=\"pid\">1</column></item></osdata>",
+                _ => return Err(TargetError::NonFatal),
+            };
+            Ok(copy_range_to_buf(doc, offset, length, buf))
+        }
+    }
+
+    #[test]
+    fn os_data() {
+        let mut target = OsDataTarget;
+        let gdb = start(&mut target);
+
+        let mut gdb = send(gdb, &mut target, &packet("qSupported:multiprocess+"));
+        assert!(take_output(&mut gdb).contains(";qXfer:osdata:read+"));
+
+        let mut gdb = send(gdb, &mut target, &packet("qXfer:osdata:read::0,fff"));
+        assert_eq!(
+            take_output(&mut gdb),
+            packet(
+                "m<osdata type=\"types\"><item><column name=\"Type\">processes</column></item></osdata>"
+            )
+        );
+
+        let mut gdb = send(
+            gdb,
+            &mut target,
+            &packet("qXfer:osdata:read:processes:10,fff"),
+        );
+        assert_eq!(
+            take_output(&mut gdb),
+            packet("mocesses\"><item><column name=\"pid\">1</column></item></osdata>")
+        );
+
+        let mut gdb = send(
+            gdb,
+            &mut target,
+            &packet("qXfer:osdata:read:processes:fff,fff"),
+        );
+        assert_eq!(take_output(&mut gdb), packet("l"));
+
+        let mut gdb = send(gdb, &mut target, &packet("qXfer:osdata:read:sockets:0,fff"));
+        assert_eq!(take_output(&mut gdb), packet("E79"));
+    }
+}
@@ -0,0 +1,79 @@
+use super::prelude::*;
+use crate::common::Signal;
+use crate::protocol::commands::ext::PassSignals;
+
+/// Decode a list of hex-encoded signal numbers into `buf`.
+///
+/// Signal numbers are a single byte, so a buffer of 256 entries is large enough
+/// to hold any well-formed list. Malformed lists are rejected with a non-fatal
+/// error.
+pub(super) fn decode_signal_list<'a, 'b, T, C>(
+    list: impl Iterator<Item = &'a [u8]>,
+    buf: &'b mut [Signal; 256],
+) -> Result<&'b [Signal], Error<T, C>> {
+    let mut len = 0;
+    for sig in list {
+        let sig = <u8 as BeBytes>::from_be_bytes(sig).ok_or(Error::NonFatalError(22))?;
+        *buf.get_mut(len).ok_or(Error::NonFatalError(22))? = Signal(sig);
+        len += 1;
+    }
+    Ok(&buf[..len])
+}
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_pass_signals(
+        &mut self,
+        _res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: PassSignals<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_pass_signals() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("pass_signals", "impl");
+
+        let handler_status = match command {
+            PassSignals::QPassSignals(cmd) => {
+                let mut buf = [Signal(0); 256];
+                let signals = decode_signal_list(cmd.signals.into_iter(), &mut buf)?;
+                ops.pass_signals(signals).handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(list: &[&[u8]]) -> Result<Vec<u8>, Error<(), ()>> {
+        let mut buf = [Signal(0); 256];
+        let signals = decode_signal_list(list.iter().copied(), &mut buf)?;
+        Ok(signals.iter().map(|sig| sig.0).collect())
+    }
+
+    #[test]
+    fn decode_signals() {
+        assert_eq!(decode(&[]).unwrap(), []);
+        assert_eq!(decode(&[&[0x0e], &[0x00, 0x1f]]).unwrap(), [0x0e, 0x1f]);
+    }
+
+    #[test]
+    fn malformed_signal_list_is_non_fatal() {
+        // signal numbers don't fit in a byte
+        assert!(matches!(
+            decode(&[&[0x0e], &[0x01, 0x00]]),
+            Err(Error::NonFatalError(22))
+        ));
+        // more signals than there are signal numbers
+        assert!(matches!(
+            decode(&[&[0x01][..]; 257]),
+            Err(Error::NonFatalError(22))
+        ));
+    }
+}
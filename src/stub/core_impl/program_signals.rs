@@ -0,0 +1,31 @@
+use super::pass_signals::decode_signal_list;
+use super::prelude::*;
+use crate::common::Signal;
+use crate::protocol::commands::ext::ProgramSignals;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_program_signals(
+        &mut self,
+        _res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: ProgramSignals<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_program_signals() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("program_signals", "impl");
+
+        let handler_status = match command {
+            ProgramSignals::QProgramSignals(cmd) => {
+                let mut buf = [Signal(0); 256];
+                let signals = decode_signal_list(cmd.signals.into_iter(), &mut buf)?;
+                ops.program_signals(signals).handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
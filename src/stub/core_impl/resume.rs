@@ -1,6 +1,7 @@
 use super::prelude::*;
 use super::DisconnectReason;
 use crate::arch::Arch;
+use crate::common::Pid;
 use crate::common::Signal;
 use crate::common::Tid;
 use crate::protocol::commands::_vCont::Actions;
@@ -9,6 +10,7 @@ use crate::protocol::SpecificIdKind;
 use crate::protocol::SpecificThreadId;
 use crate::stub::MultiThreadStopReason;
 use crate::target::ext::base::reverse_exec::ReplayLogPosition;
+use crate::target::ext::base::BaseOps;
 use crate::target::ext::base::ResumeOps;
 use crate::target::ext::catch_syscalls::CatchSyscallPosition;
 
@@ -19,11 +21,16 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         target: &mut T,
         command: Resume<'_>,
     ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let emulate_range_step = target.use_emulated_range_step();
+
         let mut ops = match target.base_ops().resume_ops() {
             Some(ops) => ops,
             None => return Ok(HandlerStatus::Handled),
         };
 
+        // a fresh resume request always cancels any in-progress emulated range step
+        self.emulated_range_step = None;
+
         let actions = match command {
             Resume::vCont(cmd) => {
                 use crate::protocol::commands::_vCont::vCont;
@@ -42,12 +49,22 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
                         // Range stepping is optional
                         if match &mut ops {
-                            ResumeOps::SingleThread(ops) => ops.support_range_step().is_some(),
+                            ResumeOps::SingleThread(ops) => {
+                                ops.support_range_step().is_some()
+                                    || (emulate_range_step && ops.support_single_step().is_some())
+                            }
                             ResumeOps::MultiThread(ops) => ops.support_range_step().is_some(),
                         } {
                             res.write_str(";r")?;
                         }
 
+                        // Stopping threads is only supported in non-stop mode
+                        if let ResumeOps::MultiThread(ops) = &mut ops {
+                            if ops.support_non_stop().is_some() {
+                                res.write_str(";t")?;
+                            }
+                        }
+
                         // doesn't actually invoke vCont
                         return Ok(HandlerStatus::Handled);
                     }
@@ -77,7 +94,15 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             }
         };
 
-        self.do_vcont(ops, actions)
+        let handler_status = self.do_vcont(ops, actions, emulate_range_step)?;
+
+        // in non-stop mode, the client expects resume packets to be acknowledged
+        // immediately, with any stop events being reported via notifications.
+        if self.features.non_stop() {
+            res.write_str("OK")?;
+        }
+
+        Ok(handler_status)
     }
 
     fn do_vcont_single_thread(
@@ -146,7 +171,8 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     .map_err(Error::TargetError)?;
                 Ok(())
             }
-            // TODO: update this case when non-stop mode is implemented
+            // "Stop" is only valid in non-stop mode, which single threaded targets
+            // do not support.
             VContKind::Stop => Err(Error::PacketUnexpected),
 
             // Instead of using `_ =>`, explicitly list out any remaining unguarded cases.
@@ -157,12 +183,111 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         }
     }
 
+    /// If `actions` consists of a single range step, kick off an emulated range
+    /// step by single stepping the target, returning the range.
+    fn start_emulated_range_step(
+        ops: &mut dyn crate::target::ext::base::singlethread::SingleThreadResume<
+            Arch = T::Arch,
+            Error = T::Error,
+        >,
+        actions: &Actions<'_>,
+    ) -> Result<
+        Option<(<T::Arch as Arch>::Usize, <T::Arch as Arch>::Usize)>,
+        Error<T::Error, C::Error>,
+    > {
+        use crate::protocol::commands::_vCont::VContKind;
+
+        let mut iter = actions.iter();
+        let first_action = iter.next();
+        // GDB typically resumes all other threads with a trailing `c` action, which
+        // (much like in `do_vcont_single_thread`) is meaningless for single threaded
+        // targets.
+        let valid_rest = match iter.next() {
+            None => true,
+            Some(Some(act)) => matches!(act.kind, VContKind::Continue) && iter.next().is_none(),
+            Some(None) => false,
+        };
+        let (start, end) = match first_action {
+            Some(Some(action)) if valid_rest => match action.kind {
+                VContKind::RangeStep(start, end) => (start, end),
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+
+        let ops = match ops.support_single_step() {
+            Some(ops) => ops,
+            None => return Ok(None),
+        };
+
+        let start = start.decode().map_err(|_| Error::TargetMismatch)?;
+        let end = end.decode().map_err(|_| Error::TargetMismatch)?;
+
+        ops.step(None).map_err(Error::TargetError)?;
+        Ok(Some((start, end)))
+    }
+
+    /// Called prior to reporting a stop reason to the client.
+    ///
+    /// If an emulated range step is in progress, and the target has just
+    /// completed a step which left the PC within the requested range, the
+    /// target is stepped again, and `true` is returned to indicate that the
+    /// stop reason should _not_ be reported.
+    pub(crate) fn continue_emulated_range_step(
+        &mut self,
+        target: &mut T,
+        stop_reason: &MultiThreadStopReason<<T::Arch as Arch>::Usize>,
+    ) -> Result<bool, Error<T::Error, C::Error>> {
+        use crate::arch::Registers;
+        use crate::target::ext::base::BaseOps;
+        use crate::target::TargetError;
+
+        let (start, end) = match self.emulated_range_step.take() {
+            Some(range) => range,
+            None => return Ok(false),
+        };
+
+        if !matches!(stop_reason, MultiThreadStopReason::DoneStep) {
+            return Ok(false);
+        }
+
+        let ops = match target.base_ops() {
+            BaseOps::SingleThread(ops) => ops,
+            BaseOps::MultiThread(_) => return Ok(false),
+        };
+
+        let mut regs: <T::Arch as Arch>::Registers = Default::default();
+        match ops.read_registers(&mut regs) {
+            Ok(()) => {}
+            Err(TargetError::Fatal(e)) => return Err(Error::TargetError(e)),
+            // if the PC can't be determined, simply hand control back to the client
+            Err(_) => return Ok(false),
+        }
+
+        let pc = regs.pc();
+        if pc < start || pc >= end {
+            return Ok(false);
+        }
+
+        match ops
+            .support_resume()
+            .and_then(|ops| ops.support_single_step())
+        {
+            Some(ops) => ops.step(None).map_err(Error::TargetError)?,
+            None => return Ok(false),
+        }
+
+        self.emulated_range_step = Some((start, end));
+        Ok(true)
+    }
+
     fn do_vcont_multi_thread(
         ops: &mut dyn crate::target::ext::base::multithread::MultiThreadResume<
             Arch = T::Arch,
             Error = T::Error,
         >,
         actions: &Actions<'_>,
+        non_stop: bool,
     ) -> Result<(), Error<T::Error, C::Error>> {
         ops.clear_resume_actions().map_err(Error::TargetError)?;
 
@@ -184,7 +309,14 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                         // An action with no thread-id matches all threads
                         None | Some(SpecificIdKind::All) => {
                             // Target API contract specifies that the default
-                            // resume action for all threads is continue.
+                            // resume action for all threads is continue, except
+                            // in non-stop mode, where it must be explicitly set.
+                            if non_stop {
+                                if let Some(ops) = ops.support_non_stop() {
+                                    ops.set_resume_action_continue_all()
+                                        .map_err(Error::TargetError)?;
+                                }
+                            }
                         }
                         Some(SpecificIdKind::WithId(tid)) => ops
                             .set_resume_action_continue(tid, signal)
@@ -232,8 +364,16 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                         }
                     };
                 }
-                // TODO: update this case when non-stop mode is implemented
-                VContKind::Stop => return Err(Error::PacketUnexpected),
+                VContKind::Stop if non_stop && ops.support_non_stop().is_some() => {
+                    let ops = ops.support_non_stop().unwrap();
+
+                    match action.thread.map(|thread| thread.tid) {
+                        // An action with no thread-id matches all threads
+                        None | Some(SpecificIdKind::All) => ops.set_resume_action_stop_all(),
+                        Some(SpecificIdKind::WithId(tid)) => ops.set_resume_action_stop(tid),
+                    }
+                    .map_err(Error::TargetError)?;
+                }
 
                 // GDB doesn't always respect `vCont?` responses that omit `;s;S`, and will try to
                 // send step packets regardless. Inform the user of this bug by issuing a
@@ -244,7 +384,7 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 }
 
                 // Instead of using `_ =>`, explicitly list out any remaining unguarded cases.
-                VContKind::RangeStep(..) => {
+                VContKind::RangeStep(..) | VContKind::Stop => {
                     error!("GDB client sent resume action not reported by `vCont?`");
                     return Err(Error::PacketUnexpected);
                 }
@@ -258,10 +398,21 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         &mut self,
         ops: ResumeOps<'_, T::Arch, T::Error>,
         actions: Actions<'_>,
+        emulate_range_step: bool,
     ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
         match ops {
-            ResumeOps::SingleThread(ops) => Self::do_vcont_single_thread(ops, &actions)?,
-            ResumeOps::MultiThread(ops) => Self::do_vcont_multi_thread(ops, &actions)?,
+            ResumeOps::SingleThread(ops) => {
+                if emulate_range_step && ops.support_range_step().is_none() {
+                    if let Some(range) = Self::start_emulated_range_step(ops, &actions)? {
+                        self.emulated_range_step = Some(range);
+                        return Ok(HandlerStatus::DeferredStopReason);
+                    }
+                }
+                Self::do_vcont_single_thread(ops, &actions)?
+            }
+            ResumeOps::MultiThread(ops) => {
+                Self::do_vcont_multi_thread(ops, &actions, self.features.non_stop())?
+            }
         };
 
         Ok(HandlerStatus::DeferredStopReason)
@@ -290,6 +441,100 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 tid: SpecificIdKind::WithId(tid),
             })?;
             res.write_str(";")?;
+
+            if let BaseOps::MultiThread(ops) = target.base_ops() {
+                if let Some(core) = ops.thread_core(tid).map_err(Error::TargetError)? {
+                    res.write_str("core:")?;
+                    res.write_num(core)?;
+                    res.write_str(";")?;
+                }
+            }
+        }
+
+        if let Some(ops) = target.support_stop_registers() {
+            let mut err: Result<_, Error<T::Error, C::Error>> = Ok(());
+            ops.stop_registers(tid.unwrap_or(self.current_mem_tid), &mut |reg, val| {
+                // TODO: replace this with a try block (once stabilized)
+                let e = (|| {
+                    res.write_num(reg)?;
+                    res.write_str(":")?;
+                    res.write_hex_buf(val)?;
+                    res.write_str(";")?;
+                    Ok(())
+                })();
+
+                if let Err(e) = e {
+                    err = Err(e)
+                }
+            })
+            .map_err(Error::TargetError)?;
+            err?;
+        }
+
+        if self.features.list_threads_in_stop_reply() {
+            // with multiprocess extensions, each thread is qualified by its owning process
+            let current_pid = match self.features.multiprocess() {
+                true => Some(self.get_current_pid(target)?),
+                false => None,
+            };
+            let use_process_list = self.use_process_list(target);
+
+            if let BaseOps::MultiThread(ops) = target.base_ops() {
+                res.write_str("threads:")?;
+
+                let mut first = true;
+                let mut write_thread = |res: &mut ResponseWriter<'_, C>, pid: Option<Pid>, tid| {
+                    if !first {
+                        res.write_str(",")?
+                    }
+                    first = false;
+                    res.write_specific_thread_id(SpecificThreadId {
+                        pid: pid.map(SpecificIdKind::WithId),
+                        tid: SpecificIdKind::WithId(tid),
+                    })
+                };
+
+                let mut err: Result<_, Error<T::Error, C::Error>> = Ok(());
+                match ops.support_process_list() {
+                    Some(ops) if use_process_list => {
+                        let mut cursor = 0;
+                        loop {
+                            let mut idx = 0;
+                            let mut next_pid = None;
+                            ops.list_processes(&mut |pid| {
+                                if idx == cursor {
+                                    next_pid = Some(pid);
+                                }
+                                idx += 1;
+                            })
+                            .map_err(Error::TargetError)?;
+
+                            let pid = match next_pid {
+                                Some(pid) => pid,
+                                None => break,
+                            };
+                            ops.list_process_threads(pid, &mut |tid| {
+                                if let Err(e) = write_thread(res, Some(pid), tid) {
+                                    err = Err(e.into())
+                                }
+                            })
+                            .map_err(Error::TargetError)?;
+                            cursor += 1;
+                        }
+                    }
+                    _ => {
+                        ops.list_active_threads(&mut |tid| {
+                            if let Err(e) = write_thread(res, current_pid, tid) {
+                                err = Err(e.into())
+                            }
+                        })
+                        .map_err(Error::TargetError)?;
+                    }
+                }
+                err?;
+
+                res.write_str(";")?;
+            }
         }
 
         Ok(())
@@ -331,12 +576,30 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
             };
         }
 
+        macro_rules! guard_vfork {
+            () => {
+                target
+                    .support_fork_events()
+                    .and_then(|ops| ops.support_vfork_events())
+                    .is_some()
+            };
+        }
+
         macro_rules! guard_catch_syscall {
             () => {
                 target.support_catch_syscalls().is_some()
             };
         }
 
+        macro_rules! guard_thread_events {
+            () => {
+                match target.base_ops() {
+                    BaseOps::SingleThread(_) => false,
+                    BaseOps::MultiThread(ops) => ops.support_thread_events().is_some(),
+                }
+            };
+        }
+
         let status = match stop_reason {
             MultiThreadStopReason::DoneStep => {
                 res.write_str("S")?;
@@ -366,14 +629,20 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 crate::__dead_code_marker!("sw_breakpoint", "stop_reason");
 
                 self.write_stop_common(res, target, Some(tid), Signal::SIGTRAP)?;
-                res.write_str("swbreak:;")?;
+                // GDB clients that don't understand the `swbreak` stop reason must not
+                // receive it (falling back to the client's own PC adjustment logic).
+                if self.features.swbreak() {
+                    res.write_str("swbreak:;")?;
+                }
                 FinishExecStatus::Handled
             }
             MultiThreadStopReason::HwBreak(tid) if guard_break!(support_hw_breakpoint) => {
                 crate::__dead_code_marker!("hw_breakpoint", "stop_reason");
 
                 self.write_stop_common(res, target, Some(tid), Signal::SIGTRAP)?;
-                res.write_str("hwbreak:;")?;
+                if self.features.hwbreak() {
+                    res.write_str("hwbreak:;")?;
+                }
                 FinishExecStatus::Handled
             }
             MultiThreadStopReason::Watch { tid, kind, addr }
@@ -425,13 +694,136 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
                 FinishExecStatus::Handled
             }
+            MultiThreadStopReason::Fork {
+                tid,
+                child_pid,
+                child_tid,
+            } if target.support_fork_events().is_some() => {
+                crate::__dead_code_marker!("fork_events", "stop_reason");
+
+                self.write_stop_common(res, target, Some(tid), Signal::SIGTRAP)?;
+
+                if self.features.fork_events() {
+                    res.write_str("fork:")?;
+                    res.write_specific_thread_id(SpecificThreadId {
+                        pid: Some(SpecificIdKind::WithId(child_pid)),
+                        tid: SpecificIdKind::WithId(child_tid),
+                    })?;
+                    res.write_str(";")?;
+                }
+
+                FinishExecStatus::Handled
+            }
+            MultiThreadStopReason::Vfork {
+                tid,
+                child_pid,
+                child_tid,
+            } if guard_vfork!() => {
+                crate::__dead_code_marker!("vfork_events", "stop_reason");
+
+                self.write_stop_common(res, target, Some(tid), Signal::SIGTRAP)?;
+
+                if self.features.vfork_events() {
+                    res.write_str("vfork:")?;
+                    res.write_specific_thread_id(SpecificThreadId {
+                        pid: Some(SpecificIdKind::WithId(child_pid)),
+                        tid: SpecificIdKind::WithId(child_tid),
+                    })?;
+                    res.write_str(";")?;
+                }
+
+                FinishExecStatus::Handled
+            }
+            MultiThreadStopReason::VforkDone(tid) if guard_vfork!() => {
+                crate::__dead_code_marker!("vfork_events", "stop_reason");
+
+                // must be reported on the thread which called `vfork`, as GDB uses it to
+                // determine which inferior's breakpoints should be re-inserted.
+                self.write_stop_common(res, target, Some(tid), Signal::SIGTRAP)?;
+
+                if self.features.vfork_events() {
+                    res.write_str("vforkdone:;")?;
+                }
+
+                FinishExecStatus::Handled
+            }
+            MultiThreadStopReason::Exec(tid) if target.support_exec_events().is_some() => {
+                crate::__dead_code_marker!("exec_events", "stop_reason");
+
+                self.write_stop_common(res, target, Some(tid), Signal::SIGTRAP)?;
+
+                match target.support_exec_events() {
+                    Some(ops) if self.features.exec_events() => {
+                        res.write_str("exec:")?;
+
+                        // the pathname may contain arbitrary bytes, so it gets hex-encoded in
+                        // fixed-size chunks (as there's no spare buffer to read it into)
+                        let mut buf = [0; 32];
+                        let mut offset = 0;
+                        loop {
+                            let len = ops
+                                .get_exec_event_path(tid, offset, buf.len(), &mut buf)
+                                .handle_error()?;
+                            if len == 0 {
+                                break;
+                            }
+                            // TODO: add more specific error variant?
+                            res.write_hex_buf(buf.get(..len).ok_or(Error::PacketBufferOverflow)?)?;
+                            offset += len as u64;
+                        }
+
+                        res.write_str(";")?;
+                    }
+                    _ => {}
+                }
+
+                FinishExecStatus::Handled
+            }
+            MultiThreadStopReason::ThreadCreated(tid) if guard_thread_events!() => {
+                crate::__dead_code_marker!("thread_events", "stop_reason");
+
+                self.write_stop_common(res, target, Some(tid), Signal::SIGTRAP)?;
+
+                if self.features.thread_events() {
+                    res.write_str("create:;")?;
+                }
+
+                FinishExecStatus::Handled
+            }
+            MultiThreadStopReason::ThreadExited { tid, code } if guard_thread_events!() => {
+                crate::__dead_code_marker!("thread_events", "stop_reason");
+
+                if self.features.thread_events() {
+                    // the exited thread is gone, so it mustn't become the current thread
+                    res.write_str("w")?;
+                    res.write_num(code)?;
+                    res.write_str(";")?;
+                    res.write_specific_thread_id(SpecificThreadId {
+                        pid: self
+                            .features
+                            .multiprocess()
+                            .then_some(SpecificIdKind::WithId(self.get_current_pid(target)?)),
+                        tid: SpecificIdKind::WithId(tid),
+                    })?;
+                } else {
+                    self.write_stop_common(res, target, Some(tid), Signal::SIGTRAP)?;
+                }
+
+                FinishExecStatus::Handled
+            }
             // Explicitly avoid using `_ =>` to handle the "unguarded" variants, as doing so would
             // squelch the useful compiler error that crops up whenever stop reasons are added.
             MultiThreadStopReason::SwBreak(_)
             | MultiThreadStopReason::HwBreak(_)
             | MultiThreadStopReason::Watch { .. }
             | MultiThreadStopReason::ReplayLog { .. }
-            | MultiThreadStopReason::CatchSyscall { .. } => {
+            | MultiThreadStopReason::CatchSyscall { .. }
+            | MultiThreadStopReason::Fork { .. }
+            | MultiThreadStopReason::Vfork { .. }
+            | MultiThreadStopReason::VforkDone(_)
+            | MultiThreadStopReason::Exec(_)
+            | MultiThreadStopReason::ThreadCreated(_)
+            | MultiThreadStopReason::ThreadExited { .. } => {
                 return Err(Error::UnsupportedStopReason);
             }
         };
@@ -444,3 +836,572 @@ pub(crate) enum FinishExecStatus {
     Handled,
     Disconnect(DisconnectReason),
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Signal;
+    use crate::common::Tid;
+    use crate::stub::mock::*;
+    use crate::target::ext::base::multithread::MultiThreadNonStop;
+    use crate::target::ext::base::multithread::MultiThreadNonStopOps;
+    use crate::target::ext::base::multithread::MultiThreadRangeStepping;
+    use crate::target::ext::base::multithread::MultiThreadRangeSteppingOps;
+    use crate::target::ext::base::multithread::MultiThreadResume;
+    use crate::target::ext::base::multithread::MultiThreadResumeOps;
+    use crate::target::ext::base::multithread::MultiThreadSingleStep;
+    use crate::target::ext::base::multithread::MultiThreadSingleStepOps;
+    use crate::target::ext::base::singlethread::SingleThreadBase;
+    use crate::target::ext::base::singlethread::SingleThreadResume;
+    use crate::target::ext::base::singlethread::SingleThreadResumeOps;
+    use crate::target::ext::base::singlethread::SingleThreadSingleStep;
+    use crate::target::ext::base::singlethread::SingleThreadSingleStepOps;
+    use crate::target::ext::base::BaseOps;
+    use crate::target::ext::breakpoints::Breakpoints;
+    use crate::target::ext::breakpoints::BreakpointsOps;
+    use crate::target::ext::breakpoints::HwWatchpoint;
+    use crate::target::ext::breakpoints::HwWatchpointOps;
+    use crate::target::ext::breakpoints::SwBreakpoint;
+    use crate::target::ext::breakpoints::SwBreakpointOps;
+    use crate::target::ext::breakpoints::WatchKind;
+    use crate::target::ext::stop_registers::StopRegisters;
+    use crate::target::ext::stop_registers::StopRegistersOps;
+    use crate::target::Target;
+    use crate::target::TargetResult;
+
+    /// A multi-threaded target which supports every kind of step, and
+    /// non-stop mode.
+    struct SteppingTarget;
+
+    mock_target!(SteppingTarget: MultiThread);
+    zeroed_multi_thread_base!(SteppingTarget, support_resume => MultiThreadResumeOps);
+
+    impl MultiThreadResume for SteppingTarget {
+        fn resume(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+
+        fn clear_resume_actions(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+
+        fn set_resume_action_continue(
+            &mut self,
+            _tid: Tid,
+            _signal: Option<Signal>,
+        ) -> Result<(), ()> {
+            Ok(())
+        }
+
+        fn support_single_step(&mut self) -> Option<MultiThreadSingleStepOps<'_, Self>> {
+            Some(self)
+        }
+
+        fn support_range_step(&mut self) -> Option<MultiThreadRangeSteppingOps<'_, Self>> {
+            Some(self)
+        }
+
+        fn support_non_stop(&mut self) -> Option<MultiThreadNonStopOps<'_, Self>> {
+            Some(self)
+        }
+    }
+
+    impl MultiThreadSingleStep for SteppingTarget {
+        fn set_resume_action_step(&mut self, _tid: Tid, _signal: Option<Signal>) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    impl MultiThreadRangeStepping for SteppingTarget {
+        fn set_resume_action_range_step(
+            &mut self,
+            _tid: Tid,
+            _start: u32,
+            _end: u32,
+        ) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    impl MultiThreadNonStop for SteppingTarget {
+        fn set_non_stop(&mut self, _enabled: bool) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn set_resume_action_stop(&mut self, _tid: Tid) -> Result<(), ()> {
+            Ok(())
+        }
+
+        fn set_resume_action_stop_all(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+
+        fn set_resume_action_continue_all(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+
+        fn is_thread_stopped(&mut self, _tid: Tid) -> Result<bool, ()> {
+            Ok(true)
+        }
+    }
+
+    /// A single-threaded target which relies on the stub to emulate range
+    /// stepping, where each single step advances the PC by 4.
+    #[derive(Default)]
+    struct RangeStepTarget {
+        pc: u32,
+        calls: Vec<String>,
+    }
+
+    impl Target for RangeStepTarget {
+        type Arch = MockArch;
+        type Error = ();
+
+        fn base_ops(&mut self) -> BaseOps<'_, MockArch, ()> {
+            BaseOps::SingleThread(self)
+        }
+
+        fn guard_rail_implicit_sw_breakpoints(&self) -> bool {
+            true
+        }
+
+        fn use_emulated_range_step(&self) -> bool {
+            true
+        }
+    }
+
+    impl SingleThreadBase for RangeStepTarget {
+        fn read_registers(&mut self, regs: &mut MockRegs) -> TargetResult<(), Self> {
+            regs.pc = self.pc;
+            Ok(())
+        }
+
+        fn write_registers(&mut self, regs: &MockRegs) -> TargetResult<(), Self> {
+            self.pc = regs.pc;
+            Ok(())
+        }
+
+        fn read_addrs(&mut self, _start_addr: u32, data: &mut [u8]) -> TargetResult<usize, Self> {
+            data.fill(0);
+            Ok(data.len())
+        }
+
+        fn write_addrs(&mut self, _start_addr: u32, _data: &[u8]) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn support_resume(&mut self) -> Option<SingleThreadResumeOps<'_, Self>> {
+            Some(self)
+        }
+    }
+
+    impl SingleThreadResume for RangeStepTarget {
+        fn resume(&mut self, _signal: Option<Signal>) -> Result<(), ()> {
+            self.calls.push("resume".into());
+            Ok(())
+        }
+
+        fn support_single_step(&mut self) -> Option<SingleThreadSingleStepOps<'_, Self>> {
+            Some(self)
+        }
+    }
+
+    impl SingleThreadSingleStep for RangeStepTarget {
+        fn step(&mut self, _signal: Option<Signal>) -> Result<(), ()> {
+            self.calls.push(format!("step {:#x}", self.pc));
+            self.pc += 4;
+            Ok(())
+        }
+    }
+
+    /// A single-threaded target which supports software breakpoints and
+    /// hardware watchpoints.
+    #[derive(Default)]
+    struct StopReplyTarget {
+        /// PC reported (as register `0xf`) in stop replies, if any.
+        stop_pc: Option<u32>,
+    }
+
+    mock_target!(
+        StopReplyTarget: SingleThread,
+        support_breakpoints => BreakpointsOps,
+        support_stop_registers => StopRegistersOps,
+    );
+    zeroed_single_thread_base!(StopReplyTarget, support_resume => SingleThreadResumeOps);
+
+    impl SingleThreadResume for StopReplyTarget {
+        fn resume(&mut self, _signal: Option<Signal>) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    impl Breakpoints for StopReplyTarget {
+        fn support_sw_breakpoint(&mut self) -> Option<SwBreakpointOps<'_, Self>> {
+            Some(self)
+        }
+
+        fn support_hw_watchpoint(&mut self) -> Option<HwWatchpointOps<'_, Self>> {
+            Some(self)
+        }
+    }
+
+    impl SwBreakpoint for StopReplyTarget {
+        fn add_sw_breakpoint(&mut self, _addr: u32, _kind: usize) -> TargetResult<bool, Self> {
+            Ok(true)
+        }
+
+        fn remove_sw_breakpoint(&mut self, _addr: u32, _kind: usize) -> TargetResult<bool, Self> {
+            Ok(true)
+        }
+    }
+
+    impl HwWatchpoint for StopReplyTarget {
+        fn add_hw_watchpoint(
+            &mut self,
+            _addr: u32,
+            _len: u32,
+            _kind: WatchKind,
+        ) -> TargetResult<bool, Self> {
+            Ok(true)
+        }
+
+        fn remove_hw_watchpoint(
+            &mut self,
+            _addr: u32,
+            _len: u32,
+            _kind: WatchKind,
+        ) -> TargetResult<bool, Self> {
+            Ok(true)
+        }
+    }
+
+    impl StopRegisters for StopReplyTarget {
+        fn stop_registers(
+            &mut self,
+            _tid: Tid,
+            report: &mut dyn FnMut(usize, &[u8]),
+        ) -> Result<(), ()> {
+            if let Some(pc) = self.stop_pc {
+                report(0xf, &pc.to_le_bytes());
+            }
+            Ok(())
+        }
+    }
+
+    fn vcont_query<T: Target<Error = ()>>(mut target: T) -> String {
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("vCont?"));
+        take_output(&mut gdb)
+    }
+
+    #[test]
+    fn vcont_query_reflects_target() {
+        let target = MockTarget {
+            threads: vec![tid(1)],
+            ..Default::default()
+        };
+        assert_eq!(vcont_query(target), packet("vCont;c;C"));
+        assert_eq!(
+            vcont_query(MockSingleTarget::default()),
+            packet("vCont;c;C;s;S")
+        );
+        assert_eq!(vcont_query(SteppingTarget), packet("vCont;c;C;s;S;r;t"));
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn emulated_range_step() {
+        use crate::stub::state_machine::GdbStubStateMachine;
+        use crate::stub::SingleThreadStopReason;
+
+        let mut target = RangeStepTarget {
+            pc: 0x1000,
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("vCont?"));
+        assert_eq!(take_output(&mut gdb), packet("vCont;c;C;s;S;r"));
+
+        let mut gdb = send(gdb, &mut target, &packet("vCont;r1000,100c"));
+        assert_eq!(target.calls, ["step 0x1000"]);
+
+        // steps which land within the range are never reported to the client
+        for _ in 0..2 {
+            gdb = match gdb {
+                GdbStubStateMachine::Running(gdb) => gdb
+                    .report_stop(&mut target, SingleThreadStopReason::DoneStep)
+                    .unwrap(),
+                _ => panic!("expected the target to be running"),
+            };
+            assert_eq!(take_output(&mut gdb), "");
+        }
+        assert_eq!(target.calls, ["step 0x1000", "step 0x1004", "step 0x1008"]);
+
+        let mut gdb = match gdb {
+            GdbStubStateMachine::Running(gdb) => gdb
+                .report_stop(&mut target, SingleThreadStopReason::DoneStep)
+                .unwrap(),
+            _ => panic!("expected the target to be running"),
+        };
+        assert!(matches!(gdb, GdbStubStateMachine::Idle(_)));
+        assert_eq!(take_output(&mut gdb), packet("S05"));
+        assert_eq!(target.pc, 0x100c);
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn emulated_range_step_with_trailing_continue() {
+        use crate::stub::state_machine::GdbStubStateMachine;
+        use crate::stub::SingleThreadStopReason;
+
+        let mut target = RangeStepTarget {
+            pc: 0x1000,
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("vCont;r1000,100c:1;c"));
+        assert_eq!(target.calls, ["step 0x1000"]);
+
+        let mut gdb = match gdb {
+            GdbStubStateMachine::Running(gdb) => gdb
+                .report_stop(&mut target, SingleThreadStopReason::DoneStep)
+                .unwrap(),
+            _ => panic!("expected the target to be running"),
+        };
+        assert_eq!(take_output(&mut gdb), "");
+        assert_eq!(target.calls, ["step 0x1000", "step 0x1004"]);
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn report_deferred_stop() {
+        use crate::stub::state_machine::GdbStubStateMachine;
+        use crate::stub::DisconnectReason;
+        use crate::stub::SingleThreadStopReason;
+
+        for (reason, expected, disconnect) in [
+            (SingleThreadStopReason::Signal(Signal::SIGSEGV), "S0b", None),
+            (
+                SingleThreadStopReason::SwBreak(()),
+                "T05thread:01;swbreak:;",
+                None,
+            ),
+            (
+                SingleThreadStopReason::Exited(3),
+                "W03",
+                Some(DisconnectReason::TargetExited(3)),
+            ),
+            // exit statuses aren't truncated to 8 bits
+            (
+                SingleThreadStopReason::Exited(256),
+                "W0100",
+                Some(DisconnectReason::TargetExited(256)),
+            ),
+            (
+                SingleThreadStopReason::Terminated(Signal::SIGKILL),
+                "X09",
+                Some(DisconnectReason::TargetTerminated(Signal::SIGKILL)),
+            ),
+        ] {
+            let mut target = StopReplyTarget::default();
+
+            let gdb = start(&mut target);
+            let mut gdb = send(gdb, &mut target, &packet("qSupported:swbreak+"));
+            take_output(&mut gdb);
+
+            // the resume packet isn't responded to until the stop is reported
+            let mut gdb = send(gdb, &mut target, &packet("c"));
+            assert_eq!(take_output(&mut gdb), "");
+            let mut gdb = match gdb {
+                GdbStubStateMachine::Running(gdb) => gdb.report_stop(&mut target, reason).unwrap(),
+                _ => panic!("expected the target to be running"),
+            };
+            assert_eq!(take_output(&mut gdb), packet(expected));
+
+            match (gdb, disconnect) {
+                (GdbStubStateMachine::Idle(_), None) => {}
+                (GdbStubStateMachine::Disconnected(gdb), Some(reason)) => {
+                    assert_eq!(gdb.get_reason(), reason)
+                }
+                _ => panic!("unexpected state after reporting {}", expected),
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn stop_registers_in_stop_reply() {
+        use crate::stub::state_machine::GdbStubStateMachine;
+        use crate::stub::SingleThreadStopReason;
+
+        let mut target = StopReplyTarget {
+            stop_pc: Some(0x1234_5678),
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("c"));
+        let mut gdb = match gdb {
+            GdbStubStateMachine::Running(gdb) => gdb
+                .report_stop(&mut target, SingleThreadStopReason::SwBreak(()))
+                .unwrap(),
+            _ => panic!("expected the target to be running"),
+        };
+        // register values are sent in target byte order
+        assert_eq!(take_output(&mut gdb), packet("T05thread:01;0f:78563412;"));
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn watchpoint_stop_reply() {
+        use crate::stub::state_machine::GdbStubStateMachine;
+        use crate::stub::SingleThreadStopReason;
+
+        let mut target = StopReplyTarget::default();
+
+        let mut gdb = start(&mut target);
+        for (kind, expected) in [
+            (WatchKind::Write, "T05thread:01;watch:2468ace0;"),
+            (WatchKind::Read, "T05thread:01;rwatch:2468ace0;"),
+            (WatchKind::ReadWrite, "T05thread:01;awatch:2468ace0;"),
+        ] {
+            let stop_reason = SingleThreadStopReason::Watch {
+                tid: (),
+                kind,
+                addr: 0x2468ace0,
+            };
+            gdb = match send(gdb, &mut target, &packet("c")) {
+                GdbStubStateMachine::Running(gdb) => {
+                    gdb.report_stop(&mut target, stop_reason).unwrap()
+                }
+                _ => panic!("expected the target to be running"),
+            };
+            assert_eq!(take_output(&mut gdb), packet(expected));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn list_threads_in_stop_reply() {
+        use crate::stub::state_machine::GdbStubStateMachine;
+        use crate::stub::MultiThreadStopReason;
+
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2), tid(0x1a)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("QListThreadsInStopReply"));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+
+        let gdb = match send(gdb, &mut target, &packet("vCont;c")) {
+            GdbStubStateMachine::Running(gdb) => gdb,
+            _ => panic!("expected the target to be running"),
+        };
+        let stop_reason = MultiThreadStopReason::SignalWithThread {
+            tid: tid(2),
+            signal: Signal::SIGINT,
+        };
+        let mut gdb = gdb.report_stop(&mut target, stop_reason).unwrap();
+        assert_eq!(
+            take_output(&mut gdb),
+            packet("T02thread:02;threads:01,02,1a;")
+        );
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn list_threads_in_stop_reply_multiprocess() {
+        use crate::stub::state_machine::GdbStubStateMachine;
+        use crate::stub::MultiThreadStopReason;
+
+        fn stop_reply(mut target: MockTarget) -> String {
+            let gdb = start(&mut target);
+            let gdb = send(gdb, &mut target, &packet("qSupported:multiprocess+"));
+            let mut gdb = send(gdb, &mut target, &packet("QListThreadsInStopReply"));
+            take_output(&mut gdb);
+
+            let gdb = match send(gdb, &mut target, &packet("vCont;c")) {
+                GdbStubStateMachine::Running(gdb) => gdb,
+                _ => panic!("expected the target to be running"),
+            };
+            let stop_reason = MultiThreadStopReason::SignalWithThread {
+                tid: tid(2),
+                signal: Signal::SIGINT,
+            };
+            let mut gdb = gdb.report_stop(&mut target, stop_reason).unwrap();
+            take_output(&mut gdb)
+        }
+
+        // threads are qualified by the current process
+        let target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            processes: vec![pid(3)],
+            ..Default::default()
+        };
+        assert_eq!(
+            stop_reply(target),
+            packet("T02thread:p03.02;threads:p03.01,p03.02;")
+        );
+
+        // ...or by their owning process, when every process is listed
+        let target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            processes: vec![pid(3), pid(4)],
+            process_threads: vec![(pid(3), vec![tid(1), tid(2)]), (pid(4), vec![tid(5)])],
+            ..Default::default()
+        };
+        assert_eq!(
+            stop_reply(target),
+            packet("T02thread:p03.02;threads:p03.01,p03.02,p04.05;")
+        );
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn thread_core_in_stop_reply() {
+        use crate::stub::state_machine::GdbStubStateMachine;
+        use crate::stub::MultiThreadStopReason;
+
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            thread_cores: vec![(tid(2), 0x1b)],
+            ..Default::default()
+        };
+
+        let mut gdb = start(&mut target);
+        for (thread, expected) in [(2, "T05thread:02;core:1b;"), (1, "T05thread:01;")] {
+            let running = match send(gdb, &mut target, &packet("vCont;c")) {
+                GdbStubStateMachine::Running(gdb) => gdb,
+                _ => panic!("expected the target to be running"),
+            };
+            let stop_reason = MultiThreadStopReason::SignalWithThread {
+                tid: tid(thread),
+                signal: Signal::SIGTRAP,
+            };
+            gdb = running.report_stop(&mut target, stop_reason).unwrap();
+            assert_eq!(take_output(&mut gdb), packet(expected));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn malformed_vcont_is_a_protocol_error() {
+        use crate::stub::state_machine::GdbStubStateMachine;
+        use crate::stub::DisconnectReason;
+        use crate::stub::ProtocolError;
+
+        for pkt in ["vCont", "vCont;x", "vCont;c;x"] {
+            let mut target = MockSingleTarget::default();
+            let gdb = start(&mut target);
+            match send(gdb, &mut target, &packet(pkt)) {
+                GdbStubStateMachine::Disconnected(gdb) => assert_eq!(
+                    gdb.get_reason(),
+                    DisconnectReason::ProtocolError(ProtocolError::MalformedPacket)
+                ),
+                _ => panic!("expected {} to end the session", pkt),
+            }
+        }
+    }
+}
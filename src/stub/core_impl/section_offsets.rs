@@ -55,3 +55,54 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+    use crate::target::ext::section_offsets::Offsets;
+    use crate::target::ext::section_offsets::SectionOffsets;
+    use crate::target::ext::section_offsets::SectionOffsetsOps;
+
+    struct OffsetsTarget(Offsets<u32>);
+
+    mock_target!(OffsetsTarget: SingleThread, support_section_offsets => SectionOffsetsOps);
+    zeroed_single_thread_base!(OffsetsTarget);
+
+    impl SectionOffsets for OffsetsTarget {
+        fn get_section_offsets(&mut self) -> Result<Offsets<u32>, ()> {
+            Ok(self.0)
+        }
+    }
+
+    fn query(offsets: Offsets<u32>) -> String {
+        let mut target = OffsetsTarget(offsets);
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("qOffsets"));
+        take_output(&mut gdb)
+    }
+
+    #[test]
+    fn qoffsets_sections() {
+        let offsets = Offsets::Sections {
+            text: 0x1000,
+            data: 0x2000,
+            bss: None,
+        };
+        assert_eq!(query(offsets), packet("Text=1000;Data=2000;Bss=2000"));
+    }
+
+    #[test]
+    fn qoffsets_segments() {
+        let offsets = Offsets::Segments {
+            text_seg: 0x412340,
+            data_seg: Some(0x612340),
+        };
+        assert_eq!(query(offsets), packet("TextSeg=412340;DataSeg=612340"));
+
+        let offsets = Offsets::Segments {
+            text_seg: 0x412340,
+            data_seg: None,
+        };
+        assert_eq!(query(offsets), packet("TextSeg=412340"));
+    }
+}
@@ -0,0 +1,119 @@
+use super::prelude::*;
+use crate::protocol::commands::ext::SignalInfo;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_signal_info(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: SignalInfo<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_signal_info() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("signal_info", "impl");
+
+        let handler_status = match command {
+            SignalInfo::qXferSiginfoRead(cmd) => {
+                let length = self.xfer_read_len(cmd.length, cmd.buf.len());
+                let ret = ops
+                    .get_siginfo(self.current_mem_tid, cmd.offset, length, cmd.buf)
+                    .handle_error()?;
+                if ret == 0 {
+                    res.write_str("l")?;
+                } else {
+                    res.write_str("m")?;
+                    // TODO: add more specific error variant?
+                    res.write_binary(cmd.buf.get(..ret).ok_or(Error::PacketBufferOverflow)?)?;
+                }
+                HandlerStatus::Handled
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Tid;
+    use crate::stub::mock::*;
+    use crate::target::ext::signal_info::SignalInfo;
+    use crate::target::ext::signal_info::SignalInfoOps;
+    use crate::target::TargetResult;
+
+    /// A target stopped by `SIGSEGV` (`SEGV_MAPERR`) at `0x12345678`.
+    struct SiginfoTarget;
+
+    mock_target!(SiginfoTarget: SingleThread, support_signal_info => SignalInfoOps);
+    zeroed_single_thread_base!(SiginfoTarget);
+
+    impl SignalInfo for SiginfoTarget {
+        fn get_siginfo(
+            &mut self,
+            _tid: Tid,
+            offset: u64,
+            length: usize,
+            buf: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            // si_signo = SIGSEGV, si_errno = 0, si_code = SEGV_MAPERR, si_addr = 0x12345678
+            let siginfo = b"\x0b\0\0\0\0\0\0\0\x01\0\0\0\x78\x56\x34\x12";
+            Ok(crate::target::ext::copy_range_to_buf(
+                siginfo, offset, length, buf,
+            ))
+        }
+    }
+
+    /// A two-threaded target, where each thread's `siginfo` is its tid.
+    struct ThreadSiginfoTarget;
+
+    mock_target!(ThreadSiginfoTarget: MultiThread, support_signal_info => SignalInfoOps);
+    zeroed_multi_thread_base!(ThreadSiginfoTarget);
+
+    impl SignalInfo for ThreadSiginfoTarget {
+        fn get_siginfo(
+            &mut self,
+            tid: Tid,
+            offset: u64,
+            length: usize,
+            buf: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            let siginfo = [b'0' + tid.get() as u8];
+            Ok(crate::target::ext::copy_range_to_buf(
+                &siginfo, offset, length, buf,
+            ))
+        }
+    }
+
+    #[test]
+    fn siginfo_chunked() {
+        let mut target = SiginfoTarget;
+        let gdb = start(&mut target);
+
+        let mut gdb = send(gdb, &mut target, &packet("qSupported:multiprocess+"));
+        assert!(take_output(&mut gdb).contains(";qXfer:siginfo:read+"));
+
+        let mut gdb = send(gdb, &mut target, &packet("qXfer:siginfo:read::c,8"));
+        assert_eq!(take_output(&mut gdb), packet("mxV4\x12"));
+
+        let mut gdb = send(gdb, &mut target, &packet("qXfer:siginfo:read::10,8"));
+        assert_eq!(take_output(&mut gdb), packet("l"));
+    }
+
+    #[test]
+    fn siginfo_of_selected_thread() {
+        let mut target = ThreadSiginfoTarget;
+        let mut gdb = start(&mut target);
+        for (pkt, expected) in [
+            ("Hg1", "OK"),
+            ("qXfer:siginfo:read::0,8", "m1"),
+            ("Hg2", "OK"),
+            ("qXfer:siginfo:read::0,8", "m2"),
+        ] {
+            gdb = send(gdb, &mut target, &packet(pkt));
+            assert_eq!(take_output(&mut gdb), packet(expected));
+        }
+    }
+}
@@ -2,6 +2,7 @@ use super::prelude::*;
 use crate::arch::Arch;
 use crate::arch::RegId;
 use crate::protocol::commands::ext::SingleRegisterAccess;
+use crate::protocol::IdKind;
 use crate::target::ext::base::BaseOps;
 
 impl<T: Target, C: Connection> GdbStubImpl<T, C> {
@@ -53,6 +54,8 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 }
                 HandlerStatus::Handled
             }
+            // the thread suffix itself is resolved by `handle_single_register_access`
+            SingleRegisterAccess::QThreadSuffixSupported(_) => HandlerStatus::NeedsOk,
             SingleRegisterAccess::P(p) => {
                 let reg = <T::Arch as Arch>::RegId::from_raw_id(p.reg_id);
                 match reg {
@@ -78,10 +81,67 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 None => Ok(HandlerStatus::Handled),
                 Some(ops) => Self::inner(res, ops, command, ()),
             },
-            BaseOps::MultiThread(ops) => match ops.support_single_register_access() {
-                None => Ok(HandlerStatus::Handled),
-                Some(ops) => Self::inner(res, ops, command, self.current_mem_tid),
-            },
+            BaseOps::MultiThread(ops) => {
+                if ops.support_single_register_access().is_none() {
+                    return Ok(HandlerStatus::Handled);
+                }
+
+                // an explicit `;thread:<tid>` suffix takes precedence over the
+                // thread selected via `Hg`
+                let thread = match &command {
+                    SingleRegisterAccess::p(p) => p.thread,
+                    SingleRegisterAccess::P(p) => p.thread,
+                    SingleRegisterAccess::QThreadSuffixSupported(_) => None,
+                };
+                let tid = match thread.map(|thread| thread.tid) {
+                    Some(IdKind::WithId(tid)) => tid,
+                    _ => self.current_mem_tid,
+                };
+
+                if !matches!(command, SingleRegisterAccess::QThreadSuffixSupported(_)) {
+                    self.ensure_thread_stopped(ops, tid)?;
+                }
+
+                match ops.support_single_register_access() {
+                    Some(ops) => Self::inner(res, ops, command, tid),
+                    None => Ok(HandlerStatus::Handled),
+                }
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+
+    #[test]
+    fn thread_suffix() {
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("QThreadSuffixSupported"));
+        let gdb = send(gdb, &mut target, &packet("Hgp01.02"));
+        // without a suffix, the Hg-selected thread is used
+        let gdb = send(gdb, &mut target, &packet("p0"));
+        let mut gdb = send(gdb, &mut target, &packet("p0;thread:p01.01;"));
+        assert_eq!(
+            take_output(&mut gdb),
+            packet("OK") + &packet("OK") + &packet("02003412") + &packet("01003412")
+        );
+
+        let gdb = send(gdb, &mut target, &packet("P1=aabbccdd"));
+        let mut gdb = send(gdb, &mut target, &packet("P1=aabbccdd;thread:01;"));
+        assert_eq!(take_output(&mut gdb), packet("OK").repeat(2));
+        assert_eq!(
+            target.calls,
+            [
+                "write_register 2 1 [aa, bb, cc, dd]",
+                "write_register 1 1 [aa, bb, cc, dd]",
+            ]
+        );
+    }
+}
@@ -15,9 +15,19 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
 
         let handler_status = match command {
             TargetXml::qXferFeaturesRead(cmd) => {
+                // the client may request more data than can fit in the remaining packet
+                // buffer, in which case it'll simply request the rest in another packet
+                let length = self.xfer_read_len(cmd.length, cmd.buf.len());
+
                 let ret = if let Some(ops) = target.support_target_description_xml_override() {
-                    ops.target_description_xml(cmd.annex.name, cmd.offset, cmd.length, cmd.buf)
-                        .handle_error()?
+                    ops.thread_target_description_xml(
+                        self.current_mem_tid,
+                        cmd.annex.name,
+                        cmd.offset,
+                        length,
+                        cmd.buf,
+                    )
+                    .handle_error()?
                 } else if let Some(xml) = T::Arch::target_description_xml() {
                     if cmd.annex.name != b"target.xml" {
                         // TODO: not the best error... should probably report to the user the
@@ -29,7 +39,7 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                     let xml_len = xml.len();
 
                     let start = xml_len.min(cmd.offset as usize);
-                    let end = xml_len.min((cmd.offset as usize).saturating_add(cmd.length));
+                    let end = xml_len.min((cmd.offset as usize).saturating_add(length));
 
                     // LLVM isn't smart enough to realize that `start <= end`, and fails to elide a
                     // `slice_end_index_len_fail` check unless we include this seemingly useless
@@ -60,3 +70,69 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Tid;
+    use crate::stub::mock::*;
+    use crate::target::ext::copy_range_to_buf;
+    use crate::target::ext::target_description_xml_override::TargetDescriptionXmlOverride;
+    use crate::target::ext::target_description_xml_override::TargetDescriptionXmlOverrideOps;
+    use crate::target::TargetResult;
+
+    /// A target which describes a 64-bit register layout for even threads,
+    /// and a 32-bit layout for odd threads.
+    struct HeterogeneousTarget;
+
+    mock_target!(
+        HeterogeneousTarget: MultiThread,
+        support_target_description_xml_override => TargetDescriptionXmlOverrideOps,
+    );
+    zeroed_multi_thread_base!(HeterogeneousTarget);
+
+    impl TargetDescriptionXmlOverride for HeterogeneousTarget {
+        fn target_description_xml(
+            &mut self,
+            _annex: &[u8],
+            offset: u64,
+            length: usize,
+            buf: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            Ok(copy_range_to_buf(b"<target/>", offset, length, buf))
+        }
+
+        fn thread_target_description_xml(
+            &mut self,
+            tid: Tid,
+            _annex: &[u8],
+            offset: u64,
+            length: usize,
+            buf: &mut [u8],
+        ) -> TargetResult<usize, Self> {
+            let xml: &[u8] = match tid.get() % 2 {
+                0 => b"<target><architecture>aarch64</architecture></target>",
+                _ => b"<target><architecture>arm</architecture></target>",
+            };
+            Ok(copy_range_to_buf(xml, offset, length, buf))
+        }
+    }
+
+    #[test]
+    fn per_thread_target_description() {
+        let mut target = HeterogeneousTarget;
+        let mut gdb = start(&mut target);
+
+        for (thread, arch) in [(2, "aarch64"), (1, "arm")] {
+            gdb = send(gdb, &mut target, &packet(&format!("Hg{}", thread)));
+            assert_eq!(take_output(&mut gdb), packet("OK"));
+
+            gdb = send(
+                gdb,
+                &mut target,
+                &packet("qXfer:features:read:target.xml:0,fff"),
+            );
+            let expected = format!("m<target><architecture>{}</architecture></target>", arch);
+            assert_eq!(take_output(&mut gdb), packet(&expected));
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use super::prelude::*;
+use crate::arch::Arch;
+use crate::protocol::commands::ext::ThreadEvents;
+use crate::stub::MultiThreadStopReason;
+use crate::target::ext::base::BaseOps;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_thread_events(
+        &mut self,
+        _res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: ThreadEvents,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.base_ops() {
+            BaseOps::MultiThread(ops) => match ops.support_thread_events() {
+                Some(ops) => ops,
+                None => return Ok(HandlerStatus::Handled),
+            },
+            BaseOps::SingleThread(_) => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("thread_events", "impl");
+
+        let handler_status = match command {
+            ThreadEvents::QThreadEvents(cmd) => {
+                ops.set_thread_events(cmd.enabled).handle_error()?;
+                self.features.set_thread_events(cmd.enabled);
+                HandlerStatus::NeedsOk
+            }
+        };
+
+        Ok(handler_status)
+    }
+
+    /// Called prior to reporting a stop reason to the client.
+    ///
+    /// Returns `true` if `stop_reason` is a thread event which the client
+    /// hasn't enabled, in which case it should _not_ be reported.
+    pub(crate) fn is_unwanted_thread_event(
+        &self,
+        target: &mut T,
+        stop_reason: &MultiThreadStopReason<<T::Arch as Arch>::Usize>,
+    ) -> bool {
+        if self.features.thread_events()
+            || !matches!(
+                stop_reason,
+                MultiThreadStopReason::ThreadCreated(_)
+                    | MultiThreadStopReason::ThreadExited { .. }
+            )
+        {
+            return false;
+        }
+
+        // unsupported thread events are left for `finish_exec` to reject
+        match target.base_ops() {
+            BaseOps::MultiThread(ops) => ops.support_thread_events().is_some(),
+            BaseOps::SingleThread(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+    use crate::stub::state_machine::GdbStubStateMachine;
+    use crate::stub::MultiThreadStopReason;
+
+    #[allow(clippy::panic)]
+    fn report(
+        gdb: MockStateMachine,
+        target: &mut MockTarget,
+        stop_reason: MultiThreadStopReason<u32>,
+    ) -> (MockStateMachine, String) {
+        let gdb = match send(gdb, target, &packet("vCont;c")) {
+            GdbStubStateMachine::Running(gdb) => gdb,
+            _ => panic!("expected the target to be running"),
+        };
+        let mut gdb = gdb.report_stop(target, stop_reason).unwrap();
+        let output = take_output(&mut gdb);
+        (gdb, output)
+    }
+
+    #[test]
+    fn thread_events() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("QThreadEvents:1"));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+
+        let (gdb, output) = report(
+            gdb,
+            &mut target,
+            MultiThreadStopReason::ThreadCreated(tid(3)),
+        );
+        assert_eq!(output, packet("T05thread:03;create:;"));
+
+        let stop_reason = MultiThreadStopReason::ThreadExited {
+            tid: tid(3),
+            code: 2,
+        };
+        let (gdb, output) = report(gdb, &mut target, stop_reason);
+        assert_eq!(output, packet("w02;03"));
+
+        // exit statuses aren't limited to a single byte
+        let stop_reason = MultiThreadStopReason::ThreadExited {
+            tid: tid(3),
+            code: 0x1234,
+        };
+        let (gdb, output) = report(gdb, &mut target, stop_reason);
+        assert_eq!(output, packet("w1234;03"));
+
+        // once disabled, thread events are no longer reported
+        let mut gdb = send(gdb, &mut target, &packet("QThreadEvents:0"));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+        let (gdb, output) = report(
+            gdb,
+            &mut target,
+            MultiThreadStopReason::ThreadCreated(tid(4)),
+        );
+        assert!(matches!(gdb, GdbStubStateMachine::Running(_)));
+        assert_eq!(output, "");
+
+        target
+            .calls
+            .retain(|call| call.starts_with("set_thread_events"));
+        assert_eq!(
+            target.calls,
+            ["set_thread_events true", "set_thread_events false"]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn thread_events_not_enabled() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            ..Default::default()
+        };
+
+        // without `QThreadEvents:1`, thread events are dropped while the target
+        // keeps running
+        let gdb = start(&mut target);
+        let gdb = match send(gdb, &mut target, &packet("vCont;c")) {
+            GdbStubStateMachine::Running(gdb) => gdb,
+            _ => panic!("expected the target to be running"),
+        };
+        let gdb = match gdb
+            .report_stop(&mut target, MultiThreadStopReason::ThreadCreated(tid(2)))
+            .unwrap()
+        {
+            GdbStubStateMachine::Running(gdb) => gdb,
+            _ => panic!("expected the target to keep running"),
+        };
+        let stop_reason = MultiThreadStopReason::ThreadExited {
+            tid: tid(2),
+            code: 0,
+        };
+        let gdb = match gdb.report_stop(&mut target, stop_reason).unwrap() {
+            GdbStubStateMachine::Running(gdb) => gdb,
+            _ => panic!("expected the target to keep running"),
+        };
+
+        let stop_reason = MultiThreadStopReason::SignalWithThread {
+            tid: tid(1),
+            signal: crate::common::Signal::SIGINT,
+        };
+        let mut gdb = gdb.report_stop(&mut target, stop_reason).unwrap();
+        assert_eq!(take_output(&mut gdb), packet("T02thread:01;"));
+        assert!(!target
+            .calls
+            .iter()
+            .any(|c| c.starts_with("set_thread_events")));
+    }
+
+    #[test]
+    fn thread_events_unsupported() {
+        let mut target = MockSingleTarget::default();
+
+        let gdb = start(&mut target);
+        let mut gdb = send(gdb, &mut target, &packet("QThreadEvents:1"));
+        assert_eq!(take_output(&mut gdb), packet(""));
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn thread_exit_non_stop() {
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2), tid(3)],
+            non_stop: true,
+            ..Default::default()
+        };
+
+        let gdb = start(&mut target);
+        let gdb = send(gdb, &mut target, &packet("QNonStop:1"));
+        let gdb = send(gdb, &mut target, &packet("QThreadEvents:1"));
+        let gdb = match send(gdb, &mut target, &packet("vCont;c")) {
+            GdbStubStateMachine::Running(gdb) => gdb,
+            _ => panic!("expected the target to be running"),
+        };
+
+        // the first exit is sent as a notification, and the second is queued
+        let stop_reason = MultiThreadStopReason::ThreadExited {
+            tid: tid(2),
+            code: 0x1f,
+        };
+        let gdb = match gdb.report_stop(&mut target, stop_reason).unwrap() {
+            GdbStubStateMachine::Running(gdb) => gdb,
+            _ => panic!("expected the target to keep running"),
+        };
+        let stop_reason = MultiThreadStopReason::ThreadExited {
+            tid: tid(3),
+            code: 0,
+        };
+        let mut gdb = gdb.report_stop(&mut target, stop_reason).unwrap();
+        assert!(matches!(gdb, GdbStubStateMachine::Running(_)));
+        let notification = packet("Stop:w1f;02").replacen('$', "%", 1);
+        assert_eq!(
+            take_output(&mut gdb),
+            packet("OK").repeat(3) + &notification
+        );
+
+        let gdb = send(gdb, &mut target, &packet("vStopped"));
+        let mut gdb = send(gdb, &mut target, &packet("vStopped"));
+        assert_eq!(take_output(&mut gdb), packet("w00;03") + &packet("OK"));
+    }
+}
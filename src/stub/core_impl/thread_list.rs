@@ -0,0 +1,71 @@
+use super::prelude::*;
+use super::xfer_window::XferWindow;
+use crate::protocol::commands::ext::ThreadList;
+use crate::target::ext::base::BaseOps;
+use crate::target::ext::thread_list::ThreadInfo;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_thread_list(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: ThreadList<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let pid = match self.features.multiprocess() {
+            true => Some(self.get_current_pid(target)?),
+            false => None,
+        };
+
+        let ops = match target.base_ops() {
+            BaseOps::SingleThread(_) => return Ok(HandlerStatus::Handled),
+            BaseOps::MultiThread(ops) => match ops.support_thread_list() {
+                Some(ops) => ops,
+                None => return Ok(HandlerStatus::Handled),
+            },
+        };
+
+        crate::__dead_code_marker!("thread_list", "impl");
+
+        let handler_status = match command {
+            ThreadList::qXferThreadsRead(cmd) => {
+                let len = self.xfer_read_len(cmd.length, cmd.buf.len());
+                let mut xml = XferWindow::new(&mut cmd.buf[..len], cmd.offset);
+
+                xml.write(b"<?xml version=\"1.0\"?>\n<threads>\n");
+                ops.list_threads(&mut |info: ThreadInfo<'_>| {
+                    xml.write(b"<thread id=\"");
+                    if let Some(pid) = pid {
+                        xml.write(b"p");
+                        xml.write_num(pid.get(), 16);
+                        xml.write(b".");
+                    }
+                    xml.write_num(info.tid.get(), 16);
+                    xml.write(b"\"");
+                    if let Some(core) = info.core {
+                        xml.write(b" core=\"");
+                        xml.write_num(core, 10);
+                        xml.write(b"\"");
+                    }
+                    if let Some(name) = info.name {
+                        xml.write(b" name=\"");
+                        xml.write_escaped(name);
+                        xml.write(b"\"");
+                    }
+                    xml.write(b"/>\n");
+                })
+                .map_err(Error::TargetError)?;
+                xml.write(b"</threads>\n");
+
+                let is_last = xml.is_last();
+                let written = xml.written();
+
+                res.write_str(if is_last { "l" } else { "m" })?;
+                res.write_binary(&cmd.buf[..written])?;
+
+                HandlerStatus::Handled
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
@@ -0,0 +1,90 @@
+use super::prelude::*;
+use crate::arch::Arch;
+use crate::protocol::commands::ext::ThreadLocalStorage;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_thread_local_storage(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: ThreadLocalStorage<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_thread_local_storage() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("thread_local_storage", "impl");
+
+        let handler_status = match command {
+            ThreadLocalStorage::qGetTLSAddr(cmd) => {
+                // TODO: plumb through PID when true multi-process support is added
+                let _pid = cmd.id.pid;
+
+                let offset = <T::Arch as Arch>::Usize::from_be_bytes(cmd.offset)
+                    .ok_or(Error::TargetMismatch)?;
+                let lm =
+                    <T::Arch as Arch>::Usize::from_be_bytes(cmd.lm).ok_or(Error::TargetMismatch)?;
+
+                let addr = ops.get_tls_addr(cmd.id.tid, offset, lm).handle_error()?;
+
+                res.write_num(addr)?;
+
+                HandlerStatus::Handled
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Tid;
+    use crate::stub::mock::*;
+    use crate::target::ext::thread_local_storage::ThreadLocalStorage;
+    use crate::target::ext::thread_local_storage::ThreadLocalStorageOps;
+    use crate::target::TargetError;
+    use crate::target::TargetResult;
+
+    #[derive(Default)]
+    struct TlsTarget {
+        calls: Vec<String>,
+    }
+
+    mock_target!(TlsTarget: MultiThread, support_thread_local_storage => ThreadLocalStorageOps);
+    zeroed_multi_thread_base!(TlsTarget);
+
+    impl ThreadLocalStorage for TlsTarget {
+        fn get_tls_addr(
+            &mut self,
+            tid: Tid,
+            offset: u32,
+            load_module: u32,
+        ) -> TargetResult<u32, Self> {
+            self.calls.push(format!(
+                "get_tls_addr {} {:#x} {:#x}",
+                tid, offset, load_module
+            ));
+            if tid.get() > 2 {
+                return Err(TargetError::NonFatal);
+            }
+            // each thread's TLS block lives right after the module's `link_map`
+            Ok(load_module + 0x1000 * (tid.get() as u32 - 1) + offset)
+        }
+    }
+
+    #[test]
+    fn qgettlsaddr() {
+        let mut target = TlsTarget::default();
+        let gdb = start(&mut target);
+
+        let mut gdb = send(gdb, &mut target, &packet("qGetTLSAddr:2,10,7d9000"));
+        assert_eq!(take_output(&mut gdb), packet("7da010"));
+        assert_eq!(target.calls, ["get_tls_addr 2 0x10 0x7d9000"]);
+
+        // the TLS block of dead threads can't be resolved
+        let mut gdb = send(gdb, &mut target, &packet("qGetTLSAddr:3,10,0"));
+        assert_eq!(take_output(&mut gdb), packet("E79"));
+    }
+}
@@ -0,0 +1,281 @@
+use super::prelude::*;
+use crate::arch::Arch;
+use crate::protocol::commands::ext::Tracepoints;
+use crate::protocol::commands::_QTDP::QTDP;
+use crate::target::ext::tracepoints::NewTracepoint;
+use crate::target::ext::tracepoints::TraceExperimentStatus;
+use crate::target::ext::tracepoints::TraceStateVariable;
+use crate::target::ext::tracepoints::TraceStateVariablesOps;
+use crate::target::ext::tracepoints::Tracepoint;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    /// Write the next trace state variable in an in-progress `qTfV` / `qTsV`
+    /// sequence (as tracked by `self.trace_state_variable_cursor`), or `l`
+    /// once all variables have been reported.
+    fn write_next_trace_state_variable(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        ops: TraceStateVariablesOps<'_, T>,
+    ) -> Result<(), Error<T::Error, C::Error>> {
+        let cursor = match self.trace_state_variable_cursor {
+            Some(cursor) => cursor,
+            None => {
+                res.write_str("l")?;
+                return Ok(());
+            }
+        };
+
+        let mut idx = 0;
+        let mut found = false;
+        let mut err: Result<_, Error<T::Error, C::Error>> = Ok(());
+        ops.trace_state_variables(&mut |tsv: TraceStateVariable<'_>| {
+            let i = idx;
+            idx += 1;
+            if i != cursor || found {
+                return;
+            }
+            found = true;
+
+            // TODO: replace this with a try block (once stabilized)
+            let e = (|| {
+                res.write_num(tsv.number)?;
+                res.write_str(":")?;
+                res.write_num(tsv.initial_value as u64)?;
+                res.write_str(if tsv.builtin { ":1:" } else { ":0:" })?;
+                res.write_hex_buf(tsv.name)?;
+                Ok(())
+            })();
+
+            if let Err(e) = e {
+                err = Err(e)
+            }
+        })
+        .handle_error()?;
+        err?;
+
+        if found {
+            self.trace_state_variable_cursor = Some(cursor + 1);
+        } else {
+            self.trace_state_variable_cursor = None;
+            res.write_str("l")?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn handle_tracepoints(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: Tracepoints<'_>,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_tracepoints() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("tracepoints", "impl");
+
+        let handler_status = match command {
+            Tracepoints::QTinit(_) => {
+                ops.tracepoints_init().handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+            Tracepoints::QTDP(cmd) => {
+                let cmd = match cmd {
+                    // only plain, non-collecting tracepoints are supported
+                    QTDP::Define(cmd) if !cmd.more && cmd.options.is_empty() => cmd,
+                    _ => return Err(Error::NonFatalError(1)),
+                };
+
+                let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
+                    .ok_or(Error::TargetMismatch)?;
+
+                ops.tracepoint_define(NewTracepoint {
+                    number: Tracepoint(cmd.number),
+                    addr,
+                    enabled: cmd.enabled,
+                    step_count: cmd.step,
+                    pass_count: cmd.pass,
+                })
+                .handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+            Tracepoints::QTStart(_) => {
+                ops.trace_experiment_start().handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+            Tracepoints::QTStop(_) => {
+                ops.trace_experiment_stop().handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+            Tracepoints::qTStatus(_) => {
+                match ops.trace_experiment_status().handle_error()? {
+                    TraceExperimentStatus::Running => res.write_str("T1")?,
+                    TraceExperimentStatus::NotRun => res.write_str("T0;tnotrun:0")?,
+                    TraceExperimentStatus::Stopped => res.write_str("T0;tstop:0")?,
+                    TraceExperimentStatus::BufferFull => res.write_str("T0;tfull:0")?,
+                    TraceExperimentStatus::PassCount(tp) => {
+                        res.write_str("T0;tpasscount:")?;
+                        res.write_num(tp.0)?;
+                    }
+                }
+                HandlerStatus::Handled
+            }
+            Tracepoints::QTDV(cmd) => {
+                let ops = match ops.support_trace_state_variables() {
+                    Some(ops) => ops,
+                    None => return Ok(HandlerStatus::Handled),
+                };
+
+                ops.trace_state_variable_define(TraceStateVariable {
+                    number: cmd.number,
+                    initial_value: cmd.value as i64,
+                    builtin: cmd.builtin,
+                    name: cmd.name,
+                })
+                .handle_error()?;
+                HandlerStatus::NeedsOk
+            }
+            Tracepoints::qTfV(_) | Tracepoints::qTsV(_) => {
+                let ops = match ops.support_trace_state_variables() {
+                    Some(ops) => ops,
+                    None => return Ok(HandlerStatus::Handled),
+                };
+
+                if matches!(command, Tracepoints::qTfV(_)) {
+                    self.trace_state_variable_cursor = Some(0);
+                }
+                self.write_next_trace_state_variable(res, ops)?;
+                HandlerStatus::Handled
+            }
+            Tracepoints::qTV(cmd) => {
+                let ops = match ops.support_trace_state_variables() {
+                    Some(ops) => ops,
+                    None => return Ok(HandlerStatus::Handled),
+                };
+
+                match ops.trace_state_variable_value(cmd.number).handle_error()? {
+                    Some(value) => {
+                        res.write_str("V")?;
+                        res.write_num(value as u64)?;
+                    }
+                    None => res.write_str("U")?,
+                }
+                HandlerStatus::Handled
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+    use crate::target::ext::tracepoints::NewTracepoint;
+    use crate::target::ext::tracepoints::TraceExperimentStatus;
+    use crate::target::ext::tracepoints::TraceStateVariable;
+    use crate::target::ext::tracepoints::TraceStateVariables;
+    use crate::target::ext::tracepoints::TraceStateVariablesOps;
+    use crate::target::ext::tracepoints::Tracepoints;
+    use crate::target::ext::tracepoints::TracepointsOps;
+    use crate::target::TargetResult;
+
+    /// A target which only keeps track of its trace state variables, as
+    /// `(number, value, name)`.
+    #[derive(Default)]
+    struct TraceTarget {
+        trace_state_variables: Vec<(usize, i64, Vec<u8>)>,
+    }
+
+    mock_target!(TraceTarget: SingleThread, support_tracepoints => TracepointsOps);
+    zeroed_single_thread_base!(TraceTarget);
+
+    impl Tracepoints for TraceTarget {
+        fn tracepoints_init(&mut self) -> TargetResult<(), Self> {
+            self.trace_state_variables.clear();
+            Ok(())
+        }
+
+        fn tracepoint_define(&mut self, _tp: NewTracepoint<u32>) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn trace_experiment_start(&mut self) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn trace_experiment_stop(&mut self) -> TargetResult<(), Self> {
+            Ok(())
+        }
+
+        fn trace_experiment_status(&mut self) -> TargetResult<TraceExperimentStatus, Self> {
+            Ok(TraceExperimentStatus::NotRun)
+        }
+
+        fn support_trace_state_variables(&mut self) -> Option<TraceStateVariablesOps<'_, Self>> {
+            Some(self)
+        }
+    }
+
+    impl TraceStateVariables for TraceTarget {
+        fn trace_state_variable_define(
+            &mut self,
+            tsv: TraceStateVariable<'_>,
+        ) -> TargetResult<(), Self> {
+            self.trace_state_variables
+                .push((tsv.number, tsv.initial_value, tsv.name.to_vec()));
+            Ok(())
+        }
+
+        fn trace_state_variable_value(&mut self, number: usize) -> TargetResult<Option<i64>, Self> {
+            Ok(self
+                .trace_state_variables
+                .iter()
+                .find(|(n, _, _)| *n == number)
+                .map(|(_, value, _)| *value))
+        }
+
+        fn trace_state_variables(
+            &mut self,
+            report: &mut dyn FnMut(TraceStateVariable<'_>),
+        ) -> TargetResult<(), Self> {
+            for (number, value, name) in &self.trace_state_variables {
+                report(TraceStateVariable {
+                    number: *number,
+                    initial_value: *value,
+                    builtin: false,
+                    name,
+                });
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn trace_state_variables() {
+        let mut target = TraceTarget::default();
+
+        let gdb = start(&mut target);
+        let gdb = send(
+            gdb,
+            &mut target,
+            &packet("QTDV:1:00000000000000a5:0:706b7473"),
+        );
+        let mut gdb = send(gdb, &mut target, &packet("QTDV:2:1234:0:65727273"));
+        assert_eq!(take_output(&mut gdb), packet("OK").repeat(2));
+
+        let gdb = send(gdb, &mut target, &packet("qTfV"));
+        let gdb = send(gdb, &mut target, &packet("qTsV"));
+        let mut gdb = send(gdb, &mut target, &packet("qTsV"));
+        assert_eq!(
+            take_output(&mut gdb),
+            packet("01:a5:0:706b7473") + &packet("02:1234:0:65727273") + &packet("l")
+        );
+
+        let gdb = send(gdb, &mut target, &packet("qTV:1"));
+        let mut gdb = send(gdb, &mut target, &packet("qTV:3"));
+        assert_eq!(take_output(&mut gdb), packet("Va5") + &packet("U"));
+    }
+}
@@ -0,0 +1,83 @@
+use super::prelude::*;
+use crate::protocol::commands::ext::WindowsThreadInfo;
+
+impl<T: Target, C: Connection> GdbStubImpl<T, C> {
+    pub(crate) fn handle_windows_thread_info(
+        &mut self,
+        res: &mut ResponseWriter<'_, C>,
+        target: &mut T,
+        command: WindowsThreadInfo,
+    ) -> Result<HandlerStatus, Error<T::Error, C::Error>> {
+        let ops = match target.support_windows_thread_info() {
+            Some(ops) => ops,
+            None => return Ok(HandlerStatus::Handled),
+        };
+
+        crate::__dead_code_marker!("windows_thread_info", "impl");
+
+        let handler_status = match command {
+            WindowsThreadInfo::qGetTIBAddr(cmd) => {
+                // TODO: plumb through PID when true multi-process support is added
+                let _pid = cmd.id.pid;
+
+                let addr = ops.get_tib_addr(cmd.id.tid).handle_error()?;
+
+                res.write_num(addr)?;
+
+                HandlerStatus::Handled
+            }
+        };
+
+        Ok(handler_status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Tid;
+    use crate::stub::mock::*;
+    use crate::target::ext::windows_thread_info::WindowsThreadInfo;
+    use crate::target::ext::windows_thread_info::WindowsThreadInfoOps;
+    use crate::target::TargetError;
+    use crate::target::TargetResult;
+
+    struct WindowsTarget;
+
+    mock_target!(WindowsTarget: MultiThread, support_windows_thread_info => WindowsThreadInfoOps);
+    zeroed_multi_thread_base!(WindowsTarget);
+
+    impl WindowsThreadInfo for WindowsTarget {
+        fn get_tib_addr(&mut self, tid: Tid) -> TargetResult<u32, Self> {
+            if tid.get() > 2 {
+                return Err(TargetError::NonFatal);
+            }
+            Ok(0x7ffd_e000 + tid.get() as u32)
+        }
+    }
+
+    #[test]
+    fn qgettibaddr_unsupported() {
+        let mut target = MockTarget {
+            threads: vec![tid(1), tid(2)],
+            ..Default::default()
+        };
+        let gdb = start(&mut target);
+
+        // targets without the extension reply with an empty packet
+        let mut gdb = send(gdb, &mut target, &packet("qGetTIBAddr:2"));
+        assert_eq!(take_output(&mut gdb), packet(""));
+    }
+
+    #[test]
+    fn qgettibaddr() {
+        let mut target = WindowsTarget;
+        let gdb = start(&mut target);
+
+        // the address is reported as a big-endian hex number
+        let mut gdb = send(gdb, &mut target, &packet("qGetTIBAddr:2"));
+        assert_eq!(take_output(&mut gdb), packet("7ffde002"));
+
+        let mut gdb = send(gdb, &mut target, &packet("qGetTIBAddr:3"));
+        assert_eq!(take_output(&mut gdb), packet("E79"));
+    }
+}
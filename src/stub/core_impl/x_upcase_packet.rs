@@ -21,6 +21,11 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
                 let addr = <T::Arch as Arch>::Usize::from_be_bytes(cmd.addr)
                     .ok_or(Error::TargetMismatch)?;
 
+                // GDB probes for `X` support using an empty write, which must always succeed
+                if cmd.val.is_empty() {
+                    return Ok(HandlerStatus::NeedsOk);
+                }
+
                 match target.base_ops() {
                     BaseOps::SingleThread(ops) => ops.write_addrs(addr, cmd.val),
                     BaseOps::MultiThread(ops) => {
@@ -35,3 +40,39 @@ impl<T: Target, C: Connection> GdbStubImpl<T, C> {
         Ok(handler_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::stub::mock::*;
+
+    #[test]
+    fn x_upcase_packet() {
+        let mut target = MockSingleTarget::default();
+        let gdb = start(&mut target);
+
+        // the empty write probe doesn't reach the target
+        let mut gdb = send(gdb, &mut target, &packet("X1000,0:"));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+        assert!(target.calls.is_empty());
+
+        // `#`, `$` and `}` are escaped as `}` followed by the byte XOR 0x20
+        let mut gdb = send(gdb, &mut target, &packet("X1000,5:a}\x03}\x04}]b"));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+        assert_eq!(target.calls, ["write_addrs 0x1000 [61, 23, 24, 7d, 62]"]);
+    }
+
+    #[test]
+    fn x_upcase_packet_escaped_bytes() {
+        let mut target = MockSingleTarget::default();
+        let gdb = start(&mut target);
+
+        // `*` (the run-length encoding marker) must be escaped too, and must not be
+        // confused with a run-length encoded sequence
+        let mut gdb = send(gdb, &mut target, &packet("X2000,6:}\n}\x03x}\x04}]*"));
+        assert_eq!(take_output(&mut gdb), packet("OK"));
+        assert_eq!(
+            target.calls,
+            ["write_addrs 0x2000 [2a, 23, 78, 24, 7d, 2a]"]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::internal::BeBytes;
+use num_traits::PrimInt;
+
+/// Writes a (lazily generated) document into `buf`, only keeping the bytes
+/// that fall within the window requested by the client.
+pub(crate) struct XferWindow<'a> {
+    buf: &'a mut [u8],
+    offset: u64,
+    pos: u64,
+    written: usize,
+}
+
+impl<'a> XferWindow<'a> {
+    pub fn new(buf: &'a mut [u8], offset: u64) -> Self {
+        XferWindow {
+            buf,
+            offset,
+            pos: 0,
+            written: 0,
+        }
+    }
+
+    pub fn write(&mut self, data: &[u8]) {
+        for &b in data {
+            if self.pos >= self.offset {
+                if let Some(slot) = self.buf.get_mut(self.written) {
+                    *slot = b;
+                    self.written += 1;
+                }
+            }
+            self.pos += 1;
+        }
+    }
+
+    pub fn write_num(&mut self, mut n: usize, radix: usize) {
+        let mut digits = [0; 32];
+        let mut i = digits.len();
+        loop {
+            i -= 1;
+            digits[i] = b"0123456789abcdef"[n % radix];
+            n /= radix;
+            if n == 0 {
+                break;
+            }
+        }
+        self.write(&digits[i..]);
+    }
+
+    pub fn write_escaped(&mut self, data: &[u8]) {
+        for &b in data {
+            match b {
+                b'&' => self.write(b"&amp;"),
+                b'<' => self.write(b"&lt;"),
+                b'>' => self.write(b"&gt;"),
+                b'"' => self.write(b"&quot;"),
+                b'\'' => self.write(b"&apos;"),
+                _ => self.write(&[b]),
+            }
+        }
+    }
+
+    /// Write a number as a `0x`-prefixed hex string.
+    pub fn write_hex_num<D: BeBytes + PrimInt>(&mut self, n: D) {
+        self.write(b"0x");
+        if n.is_zero() {
+            self.write(b"0");
+            return;
+        }
+
+        let mut buf = [0; 16];
+        // infallible (unless n is a >128 bit number)
+        let len = n.to_be_bytes(&mut buf).unwrap_or(0);
+        let mut digits = buf[..len]
+            .iter()
+            .flat_map(|b| [b >> 4, b & 0xf])
+            .skip_while(|&d| d == 0);
+        for d in &mut digits {
+            self.write(&[b"0123456789abcdef"[d as usize]]);
+        }
+    }
+
+    /// The number of bytes written into the window.
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Whether the entire remainder of the document fit in the window.
+    pub fn is_last(&self) -> bool {
+        self.offset.saturating_add(self.written as u64) >= self.pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate(offset: u64, buf: &mut [u8]) -> (usize, bool) {
+        let mut xml = XferWindow::new(buf, offset);
+        xml.write(b"<thread id=\"");
+        xml.write_num(0x1f, 16);
+        xml.write(b"\" core=\"");
+        xml.write_num(12, 10);
+        xml.write(b"\" name=\"");
+        xml.write_escaped(b"a<b>&\"c\"");
+        xml.write(b"\"/>");
+        (xml.written(), xml.is_last())
+    }
+
+    const EXPECTED: &[u8] =
+        b"<thread id=\"1f\" core=\"12\" name=\"a&lt;b&gt;&amp;&quot;c&quot;\"/>";
+
+    #[test]
+    fn xfer_window_hex_num() {
+        let mut buf = [0; 32];
+        let mut xml = XferWindow::new(&mut buf, 0);
+        xml.write_hex_num(0u32);
+        xml.write(b" ");
+        xml.write_hex_num(0x800u32);
+        xml.write(b" ");
+        xml.write_hex_num(0xdead_beefu64);
+        let n = xml.written();
+        assert_eq!(&buf[..n], b"0x0 0x800 0xdeadbeef");
+    }
+
+    #[test]
+    fn xfer_window_whole() {
+        let mut buf = [0; 256];
+        let (n, last) = generate(0, &mut buf);
+        assert_eq!(&buf[..n], EXPECTED);
+        assert!(last);
+    }
+
+    #[test]
+    fn xfer_window_chunked() {
+        let mut out = alloc::vec::Vec::new();
+        let mut offset = 0;
+        loop {
+            let mut buf = [0; 7];
+            let (n, last) = generate(offset, &mut buf);
+            out.extend_from_slice(&buf[..n]);
+            offset += n as u64;
+            if last {
+                break;
+            }
+        }
+        assert_eq!(out, EXPECTED);
+    }
+
+    #[test]
+    fn xfer_window_past_end() {
+        let mut buf = [0; 16];
+        let (n, last) = generate(EXPECTED.len() as u64, &mut buf);
+        assert_eq!(n, 0);
+        assert!(last);
+    }
+}
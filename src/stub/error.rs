@@ -7,7 +7,7 @@ use core::fmt::{self};
 
 /// An error that may occur while interacting with a
 /// [`Connection`](crate::conn::Connection).
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectionErrorKind {
     /// Error initializing the session.
     Init,
@@ -24,7 +24,6 @@ pub(crate) enum InternalError<T, C> {
     /// Target encountered a fatal error.
     TargetError(T),
 
-    ClientSentNack,
     PacketBufferOverflow,
     PacketParse(PacketParseError),
     PacketUnexpected,
@@ -37,6 +36,7 @@ pub(crate) enum InternalError<T, C> {
     // I'll find the time to cut a breaking release of gdbstub, I'd prefer to
     // push out this feature as a non-breaking change now.
     MissingCurrentActivePidImpl,
+    StopQueueFull,
 
     // Internal - A non-fatal error occurred (with errno-style error code)
     //
@@ -128,8 +128,7 @@ where
             Connection(e, ConnectionErrorKind::Init) => write!(f, "Connection Error while initializing the session: {}", e),
             Connection(e, ConnectionErrorKind::Read) => write!(f, "Connection Error while reading request: {}", e),
             Connection(e, ConnectionErrorKind::Write) => write!(f, "Connection Error while writing response: {}", e),
-            ClientSentNack => write!(f, "Client nack'd the last packet, but `gdbstub` doesn't implement re-transmission."),
-            PacketBufferOverflow => write!(f, "Received an oversized packet (did not fit in provided packet buffer)"),
+            PacketBufferOverflow => write!(f, "Received an oversized packet (did not fit in provided packet buffer, or exceeded the configured `packet_size`)"),
             PacketParse(e) => write!(f, "Failed to parse packet into a valid command: {:?}", e),
             PacketUnexpected => write!(f, "Client sent an unexpected packet. This should never happen! Please re-run with `log` trace-level logging enabled and file an issue at https://github.com/daniel5151/gdbstub/issues"),
             TargetMismatch => write!(f, "Received a packet with too much data for the given target"),
@@ -138,6 +137,7 @@ where
             UnexpectedStepPacket => write!(f, "{} {}", unexpected_step_packet!(), CONTEXT),
 
             ImplicitSwBreakpoints => write!(f, "Warning: The target has not opted into using implicit software breakpoints. See `Target::guard_rail_implicit_sw_breakpoints` for more information"),
+            StopQueueFull => write!(f, "Too many stop events were reported in non-stop mode before the GDB client could acknowledge them"),
             MissingCurrentActivePidImpl => write!(f, "GDB client attempted to attach to a new process, but the target has not implemented support for `ExtendedMode::support_current_active_pid`"),
 
             NonFatalError(_) => write!(f, "Internal non-fatal error. You should never see this! Please file an issue if you do!"),
@@ -172,6 +172,15 @@ impl<T, C> GdbStubError<T, C> {
         matches!(self.kind, InternalError::Connection(..))
     }
 
+    /// If the error was due to a connection error, return what kind of
+    /// operation failed.
+    pub(crate) fn connection_error_kind(&self) -> Option<ConnectionErrorKind> {
+        match self.kind {
+            InternalError::Connection(_, kind) => Some(kind),
+            _ => None,
+        }
+    }
+
     /// If the error was due to a connection error, return the concrete error
     /// type.
     pub fn into_connection_error(self) -> Option<(C, ConnectionErrorKind)> {
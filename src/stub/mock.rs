@@ -0,0 +1,749 @@
+//! Minimal in-memory target and connection, used to exercise the stub
+//! end-to-end in unit tests.
+#![allow(clippy::panic)]
+
+use crate::arch::Arch;
+use crate::arch::RegId;
+use crate::arch::Registers;
+use crate::common::Pid;
+use crate::common::Signal;
+use crate::common::Tid;
+use crate::conn::Connection;
+use crate::conn::ConnectionExt;
+use crate::stub::state_machine::GdbStubStateMachine;
+use crate::stub::ConnectionErrorKind;
+use crate::stub::DisconnectReason;
+use crate::stub::GdbStub;
+use crate::target::ext::base::multithread::MultiThreadBase;
+use crate::target::ext::base::multithread::MultiThreadNonStop;
+use crate::target::ext::base::multithread::MultiThreadNonStopOps;
+use crate::target::ext::base::multithread::MultiThreadResume;
+use crate::target::ext::base::multithread::MultiThreadResumeOps;
+use crate::target::ext::base::single_register_access::SingleRegisterAccess;
+use crate::target::ext::base::single_register_access::SingleRegisterAccessOps;
+use crate::target::ext::base::singlethread::SingleThreadBase;
+use crate::target::ext::base::singlethread::SingleThreadResume;
+use crate::target::ext::base::singlethread::SingleThreadResumeOps;
+use crate::target::ext::base::singlethread::SingleThreadSingleStep;
+use crate::target::ext::base::singlethread::SingleThreadSingleStepOps;
+use crate::target::ext::base::BaseOps;
+use crate::target::ext::extended_mode::Args;
+use crate::target::ext::extended_mode::AttachKind;
+use crate::target::ext::extended_mode::ConfigureAslr;
+use crate::target::ext::extended_mode::ConfigureAslrOps;
+use crate::target::ext::extended_mode::CurrentActivePid;
+use crate::target::ext::extended_mode::CurrentActivePidOps;
+use crate::target::ext::extended_mode::ExtendedMode;
+use crate::target::ext::extended_mode::ExtendedModeOps;
+use crate::target::ext::extended_mode::ShouldTerminate;
+use crate::target::ext::interrupt::Interrupt;
+use crate::target::ext::interrupt::InterruptOps;
+use crate::target::ext::process_list::ProcessList;
+use crate::target::ext::process_list::ProcessListOps;
+use crate::target::ext::protocol_callbacks::ClientFeatures;
+use crate::target::ext::protocol_callbacks::ProtocolCallbacks;
+use crate::target::ext::protocol_callbacks::ProtocolCallbacksOps;
+use crate::target::ext::thread_events::ThreadEvents;
+use crate::target::ext::thread_events::ThreadEventsOps;
+use crate::target::Target;
+use crate::target::TargetError;
+use crate::target::TargetResult;
+use core::convert::TryInto;
+use core::num::NonZeroUsize;
+use std::collections::VecDeque;
+
+pub enum MockArch {}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MockRegs {
+    pub pc: u32,
+}
+
+impl Registers for MockRegs {
+    type ProgramCounter = u32;
+
+    fn pc(&self) -> u32 {
+        self.pc
+    }
+
+    fn gdb_serialize(&self, mut write_byte: impl FnMut(Option<u8>)) {
+        for b in self.pc.to_le_bytes() {
+            write_byte(Some(b))
+        }
+    }
+
+    fn gdb_deserialize(&mut self, bytes: &[u8]) -> Result<(), ()> {
+        let bytes: [u8; 4] = bytes.try_into().map_err(drop)?;
+        self.pc = u32::from_le_bytes(bytes);
+        Ok(())
+    }
+}
+
+/// Every register is 4 bytes wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockRegId(pub usize);
+
+impl RegId for MockRegId {
+    fn from_raw_id(id: usize) -> Option<(Self, Option<NonZeroUsize>)> {
+        Some((MockRegId(id), NonZeroUsize::new(4)))
+    }
+}
+
+impl Arch for MockArch {
+    type Usize = u32;
+    type Registers = MockRegs;
+    type BreakpointKind = usize;
+    type RegId = MockRegId;
+}
+
+pub fn tid(n: usize) -> Tid {
+    Tid::new(n).unwrap()
+}
+
+pub fn pid(n: usize) -> Pid {
+    Pid::new(n).unwrap()
+}
+
+/// Implement `Target` for a test-local target, using [`MockArch`].
+///
+/// `$base` selects the target's base ops (`SingleThread` or `MultiThread`),
+/// and each `support_foo => FooOps` pair advertises a `Target`-level
+/// extension.
+macro_rules! mock_target {
+    ($target:ident: $base:ident $(, $support:ident => $ops:ident)* $(,)?) => {
+        impl $crate::target::Target for $target {
+            type Arch = $crate::stub::mock::MockArch;
+            type Error = ();
+
+            fn base_ops(&mut self) -> $crate::target::ext::base::BaseOps<'_, Self::Arch, ()> {
+                $crate::target::ext::base::BaseOps::$base(self)
+            }
+
+            fn guard_rail_implicit_sw_breakpoints(&self) -> bool {
+                true
+            }
+
+            $(
+                fn $support(&mut self) -> Option<$ops<'_, Self>> {
+                    Some(self)
+                }
+            )*
+        }
+    };
+}
+pub(crate) use mock_target;
+
+/// Implement `SingleThreadBase` for a test-local target, where memory reads
+/// as zero and writes are ignored.
+///
+/// Each `support_foo => FooOps` pair advertises a base extension.
+macro_rules! zeroed_single_thread_base {
+    ($target:ident $(, $support:ident => $ops:ident)* $(,)?) => {
+        impl $crate::target::ext::base::singlethread::SingleThreadBase for $target {
+            fn read_registers(
+                &mut self,
+                _regs: &mut $crate::stub::mock::MockRegs,
+            ) -> $crate::target::TargetResult<(), Self> {
+                Ok(())
+            }
+
+            fn write_registers(
+                &mut self,
+                _regs: &$crate::stub::mock::MockRegs,
+            ) -> $crate::target::TargetResult<(), Self> {
+                Ok(())
+            }
+
+            fn read_addrs(
+                &mut self,
+                _start_addr: u32,
+                data: &mut [u8],
+            ) -> $crate::target::TargetResult<usize, Self> {
+                data.fill(0);
+                Ok(data.len())
+            }
+
+            fn write_addrs(
+                &mut self,
+                _start_addr: u32,
+                _data: &[u8],
+            ) -> $crate::target::TargetResult<(), Self> {
+                Ok(())
+            }
+
+            $(
+                fn $support(&mut self) -> Option<$ops<'_, Self>> {
+                    Some(self)
+                }
+            )*
+        }
+    };
+}
+pub(crate) use zeroed_single_thread_base;
+
+/// Implement `MultiThreadBase` for a test-local target with two threads (1 and
+/// 2), where memory reads as zero and writes are ignored.
+///
+/// Each `support_foo => FooOps` pair advertises a base extension.
+macro_rules! zeroed_multi_thread_base {
+    ($target:ident $(, $support:ident => $ops:ident)* $(,)?) => {
+        impl $crate::target::ext::base::multithread::MultiThreadBase for $target {
+            fn read_registers(
+                &mut self,
+                _regs: &mut $crate::stub::mock::MockRegs,
+                _tid: $crate::common::Tid,
+            ) -> $crate::target::TargetResult<(), Self> {
+                Ok(())
+            }
+
+            fn write_registers(
+                &mut self,
+                _regs: &$crate::stub::mock::MockRegs,
+                _tid: $crate::common::Tid,
+            ) -> $crate::target::TargetResult<(), Self> {
+                Ok(())
+            }
+
+            fn read_addrs(
+                &mut self,
+                _start_addr: u32,
+                data: &mut [u8],
+                _tid: $crate::common::Tid,
+            ) -> $crate::target::TargetResult<usize, Self> {
+                data.fill(0);
+                Ok(data.len())
+            }
+
+            fn write_addrs(
+                &mut self,
+                _start_addr: u32,
+                _data: &[u8],
+                _tid: $crate::common::Tid,
+            ) -> $crate::target::TargetResult<(), Self> {
+                Ok(())
+            }
+
+            fn list_active_threads(
+                &mut self,
+                thread_is_active: &mut dyn FnMut($crate::common::Tid),
+            ) -> Result<(), ()> {
+                thread_is_active($crate::stub::mock::tid(1));
+                thread_is_active($crate::stub::mock::tid(2));
+                Ok(())
+            }
+
+            $(
+                fn $support(&mut self) -> Option<$ops<'_, Self>> {
+                    Some(self)
+                }
+            )*
+        }
+    };
+}
+pub(crate) use zeroed_multi_thread_base;
+
+/// A multi-threaded target which records every call made into it.
+///
+/// Tests of specific extensions should use a target local to their module
+/// instead (see [`mock_target!`]).
+#[derive(Default)]
+pub struct MockTarget {
+    pub threads: Vec<Tid>,
+    pub non_stop: bool,
+    /// When non-empty, the target supports extended mode.
+    pub processes: Vec<Pid>,
+    /// Processes which were spawned by the target, rather than attached to.
+    pub spawned: Vec<Pid>,
+    /// When non-empty, the target reports the threads of every process.
+    pub process_threads: Vec<(Pid, Vec<Tid>)>,
+    pub current_thread: Option<Tid>,
+    /// The processor core each thread last ran on.
+    pub thread_cores: Vec<(Tid, usize)>,
+    /// Threads which are currently running (in non-stop mode).
+    pub running: Vec<Tid>,
+    pub calls: Vec<String>,
+}
+
+impl Target for MockTarget {
+    type Arch = MockArch;
+    type Error = ();
+
+    fn base_ops(&mut self) -> BaseOps<'_, MockArch, ()> {
+        BaseOps::MultiThread(self)
+    }
+
+    fn guard_rail_implicit_sw_breakpoints(&self) -> bool {
+        true
+    }
+
+    fn support_extended_mode(&mut self) -> Option<ExtendedModeOps<'_, Self>> {
+        if self.processes.is_empty() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+
+    fn support_interrupt(&mut self) -> Option<InterruptOps<'_, Self>> {
+        Some(self)
+    }
+}
+
+impl Interrupt for MockTarget {
+    fn interrupt(&mut self) -> TargetResult<(), Self> {
+        self.calls.push("interrupt".into());
+        Ok(())
+    }
+}
+
+impl ExtendedMode for MockTarget {
+    fn run(&mut self, _filename: Option<&[u8]>, _args: Args<'_, '_>) -> TargetResult<Pid, Self> {
+        Err(TargetError::NonFatal)
+    }
+
+    fn attach(&mut self, _pid: Pid) -> TargetResult<(), Self> {
+        Err(TargetError::NonFatal)
+    }
+
+    fn query_if_attached(&mut self, pid: Pid) -> TargetResult<AttachKind, Self> {
+        if !self.processes.contains(&pid) {
+            return Err(TargetError::NonFatal);
+        }
+        Ok(if self.spawned.contains(&pid) {
+            AttachKind::Run
+        } else {
+            AttachKind::Attach
+        })
+    }
+
+    fn kill(&mut self, pid: Option<Pid>) -> TargetResult<ShouldTerminate, Self> {
+        match pid {
+            Some(pid) => {
+                let idx = self
+                    .processes
+                    .iter()
+                    .position(|p| *p == pid)
+                    .ok_or(TargetError::NonFatal)?;
+                self.processes.remove(idx);
+                self.calls.push(format!("kill {}", pid));
+            }
+            None => {
+                self.processes.clear();
+                self.calls.push("kill all".into());
+            }
+        }
+        Ok(if self.processes.is_empty() {
+            ShouldTerminate::Yes
+        } else {
+            ShouldTerminate::No
+        })
+    }
+
+    fn detach(&mut self, pid: Option<Pid>) -> TargetResult<ShouldTerminate, Self> {
+        match pid {
+            Some(pid) => {
+                let idx = self
+                    .processes
+                    .iter()
+                    .position(|p| *p == pid)
+                    .ok_or(TargetError::NonFatal)?;
+                self.processes.remove(idx);
+                self.calls.push(format!("detach {}", pid));
+            }
+            None => {
+                self.processes.clear();
+                self.calls.push("detach all".into());
+            }
+        }
+        Ok(if self.processes.is_empty() {
+            ShouldTerminate::Yes
+        } else {
+            ShouldTerminate::No
+        })
+    }
+
+    fn restart(&mut self) -> Result<(), ()> {
+        self.calls.push("restart".into());
+        Ok(())
+    }
+
+    fn support_current_active_pid(&mut self) -> Option<CurrentActivePidOps<'_, Self>> {
+        Some(self)
+    }
+
+    fn support_configure_aslr(&mut self) -> Option<ConfigureAslrOps<'_, Self>> {
+        Some(self)
+    }
+}
+
+impl ConfigureAslr for MockTarget {
+    fn cfg_disable_randomization(&mut self, disabled: bool) -> TargetResult<(), Self> {
+        self.calls
+            .push(format!("cfg_disable_randomization {}", disabled));
+        Ok(())
+    }
+}
+
+impl CurrentActivePid for MockTarget {
+    fn current_active_pid(&mut self) -> Result<Pid, ()> {
+        self.processes.first().copied().ok_or(())
+    }
+}
+
+impl MultiThreadBase for MockTarget {
+    /// Each thread's PC is `0x12340000` plus its TID.
+    fn read_registers(&mut self, regs: &mut MockRegs, tid: Tid) -> TargetResult<(), Self> {
+        regs.pc = 0x12340000 + tid.get() as u32;
+        Ok(())
+    }
+
+    fn write_registers(&mut self, regs: &MockRegs, tid: Tid) -> TargetResult<(), Self> {
+        self.calls
+            .push(format!("write_registers {} {:#x}", tid, regs.pc));
+        Ok(())
+    }
+
+    fn read_addrs(
+        &mut self,
+        _start_addr: u32,
+        data: &mut [u8],
+        _tid: Tid,
+    ) -> TargetResult<usize, Self> {
+        data.fill(0);
+        Ok(data.len())
+    }
+
+    fn write_addrs(&mut self, _start_addr: u32, _data: &[u8], _tid: Tid) -> TargetResult<(), Self> {
+        Ok(())
+    }
+
+    fn current_thread(&mut self) -> Result<Option<Tid>, ()> {
+        Ok(self.current_thread)
+    }
+
+    fn thread_core(&mut self, tid: Tid) -> Result<Option<usize>, ()> {
+        Ok(self
+            .thread_cores
+            .iter()
+            .find(|(t, _)| *t == tid)
+            .map(|(_, core)| *core))
+    }
+
+    fn list_active_threads(&mut self, thread_is_active: &mut dyn FnMut(Tid)) -> Result<(), ()> {
+        for tid in &self.threads {
+            thread_is_active(*tid);
+        }
+        Ok(())
+    }
+
+    fn support_resume(&mut self) -> Option<MultiThreadResumeOps<'_, Self>> {
+        Some(self)
+    }
+
+    fn support_single_register_access(&mut self) -> Option<SingleRegisterAccessOps<'_, Tid, Self>> {
+        Some(self)
+    }
+
+    fn support_thread_events(&mut self) -> Option<ThreadEventsOps<'_, Self>> {
+        Some(self)
+    }
+
+    fn support_process_list(&mut self) -> Option<ProcessListOps<'_, Self>> {
+        if self.process_threads.is_empty() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+}
+
+impl SingleRegisterAccess<Tid> for MockTarget {
+    /// Every register of a thread reads as that thread's PC.
+    fn read_register(
+        &mut self,
+        tid: Tid,
+        _reg_id: MockRegId,
+        buf: &mut [u8],
+    ) -> TargetResult<usize, Self> {
+        buf.copy_from_slice(&(0x12340000 + tid.get() as u32).to_le_bytes());
+        Ok(buf.len())
+    }
+
+    fn write_register(
+        &mut self,
+        tid: Tid,
+        reg_id: MockRegId,
+        val: &[u8],
+    ) -> TargetResult<(), Self> {
+        self.calls
+            .push(format!("write_register {} {} {:02x?}", tid, reg_id.0, val));
+        Ok(())
+    }
+}
+
+impl ThreadEvents for MockTarget {
+    fn set_thread_events(&mut self, enabled: bool) -> TargetResult<(), Self> {
+        self.calls.push(format!("set_thread_events {}", enabled));
+        Ok(())
+    }
+}
+
+impl ProcessList for MockTarget {
+    fn list_processes(&mut self, process_is_active: &mut dyn FnMut(Pid)) -> Result<(), ()> {
+        for (pid, _) in &self.process_threads {
+            process_is_active(*pid);
+        }
+        Ok(())
+    }
+
+    fn list_process_threads(
+        &mut self,
+        pid: Pid,
+        thread_is_active: &mut dyn FnMut(Tid),
+    ) -> Result<(), ()> {
+        let (_, threads) = self
+            .process_threads
+            .iter()
+            .find(|(p, _)| *p == pid)
+            .ok_or(())?;
+        for tid in threads {
+            thread_is_active(*tid);
+        }
+        Ok(())
+    }
+}
+
+impl MultiThreadResume for MockTarget {
+    fn resume(&mut self) -> Result<(), ()> {
+        self.calls.push("resume".into());
+        Ok(())
+    }
+
+    fn clear_resume_actions(&mut self) -> Result<(), ()> {
+        Ok(())
+    }
+
+    fn set_resume_action_continue(&mut self, tid: Tid, _signal: Option<Signal>) -> Result<(), ()> {
+        self.calls.push(format!("continue {}", tid));
+        Ok(())
+    }
+
+    fn support_non_stop(&mut self) -> Option<MultiThreadNonStopOps<'_, Self>> {
+        if self.non_stop {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+impl MultiThreadNonStop for MockTarget {
+    fn set_non_stop(&mut self, enabled: bool) -> TargetResult<(), Self> {
+        self.calls.push(format!("set_non_stop {}", enabled));
+        Ok(())
+    }
+
+    fn set_resume_action_stop(&mut self, tid: Tid) -> Result<(), ()> {
+        self.calls.push(format!("stop {}", tid));
+        Ok(())
+    }
+
+    fn set_resume_action_stop_all(&mut self) -> Result<(), ()> {
+        self.calls.push("stop_all".into());
+        Ok(())
+    }
+
+    fn set_resume_action_continue_all(&mut self) -> Result<(), ()> {
+        self.calls.push("continue_all".into());
+        Ok(())
+    }
+
+    fn is_thread_stopped(&mut self, tid: Tid) -> Result<bool, ()> {
+        Ok(!self.running.contains(&tid))
+    }
+}
+
+/// A single-threaded target, where each single step advances the PC by 4.
+///
+/// Tests of specific extensions should use a target local to their module
+/// instead (see [`mock_target!`]).
+#[derive(Default)]
+pub struct MockSingleTarget {
+    pub pc: u32,
+    pub protocol_callbacks: bool,
+    pub calls: Vec<String>,
+}
+
+impl Target for MockSingleTarget {
+    type Arch = MockArch;
+    type Error = ();
+
+    fn base_ops(&mut self) -> BaseOps<'_, MockArch, ()> {
+        BaseOps::SingleThread(self)
+    }
+
+    fn guard_rail_implicit_sw_breakpoints(&self) -> bool {
+        true
+    }
+
+    fn support_protocol_callbacks(&mut self) -> Option<ProtocolCallbacksOps<'_, Self>> {
+        if self.protocol_callbacks {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+impl ProtocolCallbacks for MockSingleTarget {
+    fn on_no_ack_mode_enabled(&mut self) {
+        self.calls.push("on_no_ack_mode_enabled".into());
+    }
+
+    fn on_client_features(&mut self, features: ClientFeatures) {
+        self.calls.push(format!(
+            "on_client_features multiprocess={} packet_size={:?}",
+            features.multiprocess(),
+            features.packet_size()
+        ));
+    }
+
+    fn on_disconnect(&mut self, reason: DisconnectReason) {
+        self.calls.push(format!("on_disconnect {:?}", reason));
+    }
+
+    fn on_connection_error(&mut self, kind: ConnectionErrorKind) {
+        self.calls.push(format!("on_connection_error {:?}", kind));
+    }
+}
+
+impl SingleThreadBase for MockSingleTarget {
+    fn read_registers(&mut self, regs: &mut MockRegs) -> TargetResult<(), Self> {
+        regs.pc = self.pc;
+        Ok(())
+    }
+
+    fn write_registers(&mut self, regs: &MockRegs) -> TargetResult<(), Self> {
+        self.pc = regs.pc;
+        Ok(())
+    }
+
+    fn read_addrs(&mut self, _start_addr: u32, data: &mut [u8]) -> TargetResult<usize, Self> {
+        data.fill(0);
+        Ok(data.len())
+    }
+
+    fn write_addrs(&mut self, start_addr: u32, data: &[u8]) -> TargetResult<(), Self> {
+        self.calls
+            .push(format!("write_addrs {:#x} {:02x?}", start_addr, data));
+        Ok(())
+    }
+
+    fn support_resume(&mut self) -> Option<SingleThreadResumeOps<'_, Self>> {
+        Some(self)
+    }
+}
+
+impl SingleThreadResume for MockSingleTarget {
+    fn resume(&mut self, _signal: Option<Signal>) -> Result<(), ()> {
+        self.calls.push("resume".into());
+        Ok(())
+    }
+
+    fn support_single_step(&mut self) -> Option<SingleThreadSingleStepOps<'_, Self>> {
+        Some(self)
+    }
+}
+
+impl SingleThreadSingleStep for MockSingleTarget {
+    fn step(&mut self, _signal: Option<Signal>) -> Result<(), ()> {
+        self.calls.push(format!("step {:#x}", self.pc));
+        self.pc += 4;
+        Ok(())
+    }
+}
+
+/// A connection which records everything written to it.
+#[derive(Default)]
+pub struct MockConnection {
+    /// Bytes returned by `read` / `peek`.
+    pub input: VecDeque<u8>,
+    pub output: Vec<u8>,
+    /// Number of times `flush` was called.
+    pub flushes: usize,
+}
+
+impl MockConnection {
+    /// Return (and clear) everything written so far, as a string.
+    pub fn take_output(&mut self) -> String {
+        String::from_utf8(std::mem::take(&mut self.output)).unwrap()
+    }
+}
+
+impl Connection for MockConnection {
+    type Error = ();
+
+    fn write(&mut self, byte: u8) -> Result<(), ()> {
+        self.output.push(byte);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ()> {
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+impl ConnectionExt for MockConnection {
+    fn read(&mut self) -> Result<u8, ()> {
+        self.input.pop_front().ok_or(())
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, ()> {
+        Ok(self.input.front().copied())
+    }
+}
+
+pub type MockStateMachine<T = MockTarget> = GdbStubStateMachine<'static, T, MockConnection>;
+
+/// Frame `body` as a GDB packet.
+pub fn packet(body: &str) -> String {
+    let checksum = body.bytes().fold(0u8, |a, b| a.wrapping_add(b));
+    format!("${}#{:02x}", body, checksum)
+}
+
+/// Start a session in no-ack mode, so that responses are easier to inspect.
+pub fn start<T: Target<Error = ()>>(target: &mut T) -> MockStateMachine<T> {
+    let gdb = GdbStub::new(MockConnection::default())
+        .run_state_machine(target)
+        .unwrap();
+    let mut gdb = send(gdb, target, &packet("QStartNoAckMode"));
+    take_output(&mut gdb);
+    gdb
+}
+
+/// Feed each byte of `data` into the state machine.
+pub fn send<T: Target<Error = ()>>(
+    mut gdb: MockStateMachine<T>,
+    target: &mut T,
+    data: &str,
+) -> MockStateMachine<T> {
+    for byte in data.bytes() {
+        gdb = match gdb {
+            GdbStubStateMachine::Idle(gdb) => gdb.incoming_data(target, byte),
+            GdbStubStateMachine::Running(gdb) => gdb.incoming_data(target, byte),
+            _ => panic!("state machine cannot accept data"),
+        }
+        .unwrap();
+    }
+    gdb
+}
+
+pub fn take_output<T: Target<Error = ()>>(gdb: &mut MockStateMachine<T>) -> String {
+    match gdb {
+        GdbStubStateMachine::Idle(gdb) => gdb.borrow_conn().take_output(),
+        GdbStubStateMachine::Running(gdb) => gdb.borrow_conn().take_output(),
+        GdbStubStateMachine::CtrlCInterrupt(gdb) => gdb.borrow_conn().take_output(),
+        GdbStubStateMachine::Disconnected(gdb) => gdb.borrow_conn().take_output(),
+    }
+}
@@ -4,6 +4,8 @@
 pub use builder::GdbStubBuilder;
 pub use builder::GdbStubBuilderError;
 pub use core_impl::DisconnectReason;
+pub use core_impl::ProtocolError;
+pub use error::ConnectionErrorKind;
 pub use error::GdbStubError;
 pub use stop_reason::BaseStopReason;
 pub use stop_reason::IntoStopReason;
@@ -13,11 +15,17 @@ pub use stop_reason::SingleThreadStopReason;
 mod builder;
 mod core_impl;
 mod error;
+#[cfg(test)]
+pub(crate) mod mock;
 mod stop_reason;
 
 pub mod state_machine;
 
 use self::error::InternalError;
+#[cfg(feature = "alloc")]
+use crate::conn::AsyncAdapter;
+#[cfg(feature = "alloc")]
+use crate::conn::AsyncConnection;
 use crate::conn::Connection;
 use crate::conn::ConnectionExt;
 use crate::target::Target;
@@ -106,6 +114,133 @@ pub mod run_blocking {
     }
 }
 
+/// Types and traits related to the [`GdbStub::run_async`] interface.
+///
+/// # Example
+///
+/// A session driven by a `tokio` [`TcpStream`], where the target signals
+/// that it has stopped via a `tokio::sync::Notify`:
+///
+/// ```ignore
+/// use gdbstub::conn::{AsyncAdapter, AsyncConnection};
+/// use gdbstub::stub::run_async::{AsyncEventLoop, Event, WaitForStopReasonError};
+/// use gdbstub::stub::{GdbStub, SingleThreadStopReason};
+/// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+/// use tokio::net::TcpStream;
+///
+/// struct Conn(TcpStream);
+///
+/// impl AsyncConnection for Conn {
+///     type Error = std::io::Error;
+///
+///     async fn read(&mut self) -> std::io::Result<u8> {
+///         self.0.read_u8().await
+///     }
+///
+///     async fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+///         self.0.write_all(buf).await
+///     }
+///
+///     async fn flush(&mut self) -> std::io::Result<()> {
+///         self.0.flush().await
+///     }
+///
+///     async fn on_session_start(&mut self) -> std::io::Result<()> {
+///         self.0.set_nodelay(true)
+///     }
+/// }
+///
+/// enum EmuEventLoop {}
+///
+/// impl AsyncEventLoop for EmuEventLoop {
+///     type Target = Emu;
+///     type Connection = Conn;
+///     type StopReason = SingleThreadStopReason<u32>;
+///
+///     async fn wait_for_stop_reason(
+///         target: &mut Emu,
+///         conn: &mut Conn,
+///     ) -> Result<Event<Self::StopReason>, WaitForStopReasonError<EmuError, std::io::Error>> {
+///         tokio::select! {
+///             reason = target.stopped() => Ok(Event::TargetStopped(reason)),
+///             byte = conn.read() => byte
+///                 .map(Event::IncomingData)
+///                 .map_err(WaitForStopReasonError::Connection),
+///         }
+///     }
+///
+///     async fn on_interrupt(target: &mut Emu) -> Result<Option<Self::StopReason>, EmuError> {
+///         target.pause();
+///         Ok(Some(SingleThreadStopReason::Signal(gdbstub::common::Signal::SIGINT)))
+///     }
+/// }
+///
+/// async fn debug(stream: TcpStream, emu: &mut Emu) {
+///     let gdb = GdbStub::new(AsyncAdapter::new(Conn(stream)));
+///     match gdb.run_async::<EmuEventLoop>(emu).await {
+///         Ok(reason) => println!("session ended: {:?}", reason),
+///         Err(e) => eprintln!("gdbstub error: {}", e),
+///     }
+/// }
+/// ```
+///
+/// [`TcpStream`]: https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html
+#[cfg(feature = "alloc")]
+pub mod run_async {
+    use super::*;
+    use crate::conn::AsyncConnection;
+    use core::future::Future;
+
+    pub use super::run_blocking::Event;
+    pub use super::run_blocking::WaitForStopReasonError;
+
+    /// A set of user-provided methods required to run a GDB debugging session
+    /// using the [`GdbStub::run_async`] method.
+    ///
+    /// This is the async counterpart to
+    /// [`BlockingEventLoop`](super::run_blocking::BlockingEventLoop).
+    pub trait AsyncEventLoop {
+        /// The Target being driven.
+        type Target: Target;
+        /// Connection being used to drive the target.
+        type Connection: AsyncConnection;
+
+        /// Which variant of the `StopReason` type should be used. Single
+        /// threaded targets should use [`SingleThreadStopReason`], whereas
+        /// multi threaded targets should use [`MultiThreadStopReason`].
+        ///
+        /// [`SingleThreadStopReason`]: crate::stub::SingleThreadStopReason
+        /// [`MultiThreadStopReason`]: crate::stub::MultiThreadStopReason
+        type StopReason: IntoStopReason<Self::Target>;
+
+        /// Invoked immediately after the target's `resume` method has been
+        /// called. The returned future should resolve once either the target
+        /// reports a stop reason, or new data was sent over the connection.
+        fn wait_for_stop_reason(
+            target: &mut Self::Target,
+            conn: &mut Self::Connection,
+        ) -> impl Future<
+            Output = Result<
+                Event<Self::StopReason>,
+                WaitForStopReasonError<
+                    <Self::Target as Target>::Error,
+                    <Self::Connection as AsyncConnection>::Error,
+                >,
+            >,
+        >;
+
+        /// Invoked when the GDB client sends a Ctrl-C interrupt.
+        ///
+        /// See [`BlockingEventLoop::on_interrupt`] for details.
+        ///
+        /// [`BlockingEventLoop::on_interrupt`]:
+        /// super::run_blocking::BlockingEventLoop::on_interrupt
+        fn on_interrupt(
+            target: &mut Self::Target,
+        ) -> impl Future<Output = Result<Option<Self::StopReason>, <Self::Target as Target>::Error>>;
+    }
+}
+
 /// Debug a [`Target`] using the GDB Remote Serial Protocol over a given
 /// [`Connection`].
 pub struct GdbStub<'a, T: Target, C: Connection> {
@@ -167,7 +302,10 @@ impl<'a, T: Target, C: Connection> GdbStub<'a, T, C> {
             gdb = match gdb {
                 state_machine::GdbStubStateMachine::Idle(mut gdb) => {
                     // needs more data, so perform a blocking read on the connection
-                    let byte = gdb.borrow_conn().read().map_err(InternalError::conn_read)?;
+                    let byte = match gdb.borrow_conn().read() {
+                        Ok(byte) => byte,
+                        Err(e) => break Err(conn_error(target, InternalError::conn_read(e))),
+                    };
                     gdb.incoming_data(target, byte)?
                 }
 
@@ -188,8 +326,24 @@ impl<'a, T: Target, C: Connection> GdbStub<'a, T, C> {
                     use run_blocking::Event as BlockingEventLoopEvent;
                     use run_blocking::WaitForStopReasonError;
 
-                    // block waiting for the target to return a stop reason
-                    let event = E::wait_for_stop_reason(target, gdb.borrow_conn());
+                    let stop_queue_full = gdb.is_stop_queue_full();
+                    let conn = gdb.borrow_conn();
+                    let pending = match conn.peek() {
+                        Ok(byte) => byte.is_some(),
+                        Err(e) => break Err(conn_error(target, InternalError::conn_read(e))),
+                    };
+                    let event = if pending || stop_queue_full {
+                        // handle data that's already pending (e.g: a Ctrl-C interrupt) without
+                        // blocking on the event loop. If the client has yet to acknowledge the
+                        // stop events reported so far, wait for it to catch up before polling
+                        // the target for more.
+                        conn.read()
+                            .map(BlockingEventLoopEvent::IncomingData)
+                            .map_err(WaitForStopReasonError::Connection)
+                    } else {
+                        // block waiting for the target to return a stop reason
+                        E::wait_for_stop_reason(target, conn)
+                    };
                     match event {
                         Ok(BlockingEventLoopEvent::TargetStopped(stop_reason)) => {
                             gdb.report_stop(target, stop_reason)?
@@ -203,7 +357,7 @@ impl<'a, T: Target, C: Connection> GdbStub<'a, T, C> {
                             break Err(InternalError::TargetError(e).into());
                         }
                         Err(WaitForStopReasonError::Connection(e)) => {
-                            break Err(InternalError::conn_read(e).into());
+                            break Err(conn_error(target, InternalError::conn_read(e)));
                         }
                     }
                 }
@@ -242,3 +396,324 @@ impl<'a, T: Target, C: Connection> GdbStub<'a, T, C> {
         Ok(state_machine::GdbStubStateMachineInner::from_plain_gdbstub(self).into())
     }
 }
+
+/// Notify the target of a connection error that occurred outside of the state
+/// machine (e.g: while waiting for incoming data).
+fn conn_error<T: Target, C>(
+    target: &mut T,
+    e: InternalError<T::Error, C>,
+) -> GdbStubError<T::Error, C> {
+    state_machine::notify_connection_error(target, e.into())
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Target, C: AsyncConnection> GdbStub<'a, T, AsyncAdapter<C>> {
+    /// Start a GDB remote debugging session over an [`AsyncConnection`],
+    /// using an async event loop.
+    ///
+    /// This is the async counterpart to [`GdbStub::run_blocking`]. Responses
+    /// generated by the stub are queued in the [`AsyncAdapter`], and written
+    /// to the underlying connection in between state-machine transitions.
+    ///
+    /// See the [`run_async`] module for an example.
+    pub async fn run_async<E>(
+        mut self,
+        target: &mut T,
+    ) -> Result<DisconnectReason, GdbStubError<T::Error, C::Error>>
+    where
+        E: run_async::AsyncEventLoop<Target = T, Connection = C>,
+    {
+        use state_machine::GdbStubStateMachine;
+
+        self.conn
+            .get_mut()
+            .on_session_start()
+            .await
+            .map_err(InternalError::conn_init)?;
+
+        let mut gdb = self.run_state_machine(target)?;
+        loop {
+            // write out any responses queued up by the previous transition
+            let conn = match &mut gdb {
+                GdbStubStateMachine::Idle(gdb) => gdb.borrow_conn(),
+                GdbStubStateMachine::Running(gdb) => gdb.borrow_conn(),
+                GdbStubStateMachine::CtrlCInterrupt(gdb) => gdb.borrow_conn(),
+                GdbStubStateMachine::Disconnected(gdb) => gdb.borrow_conn(),
+            };
+            if let Err(e) = conn.drain().await {
+                break Err(conn_error(target, InternalError::conn_write(e)));
+            }
+
+            gdb = match gdb {
+                GdbStubStateMachine::Idle(mut gdb) => {
+                    let byte = match gdb.borrow_conn().get_mut().read().await {
+                        Ok(byte) => byte,
+                        Err(e) => break Err(conn_error(target, InternalError::conn_read(e))),
+                    };
+                    gdb.incoming_data(target, byte)?
+                }
+
+                GdbStubStateMachine::Disconnected(gdb) => break Ok(gdb.get_reason()),
+
+                GdbStubStateMachine::CtrlCInterrupt(gdb) => {
+                    let stop_reason = E::on_interrupt(target)
+                        .await
+                        .map_err(InternalError::TargetError)?;
+                    gdb.interrupt_handled(target, stop_reason)?
+                }
+
+                GdbStubStateMachine::Running(mut gdb) => {
+                    use run_blocking::Event as AsyncEventLoopEvent;
+                    use run_blocking::WaitForStopReasonError;
+
+                    let event = if gdb.is_stop_queue_full() {
+                        // wait for the client to acknowledge the stop events reported so far
+                        // before polling the target for more
+                        gdb.borrow_conn()
+                            .get_mut()
+                            .read()
+                            .await
+                            .map(AsyncEventLoopEvent::IncomingData)
+                            .map_err(WaitForStopReasonError::Connection)
+                    } else {
+                        E::wait_for_stop_reason(target, gdb.borrow_conn().get_mut()).await
+                    };
+                    match event {
+                        Ok(AsyncEventLoopEvent::TargetStopped(stop_reason)) => {
+                            gdb.report_stop(target, stop_reason)?
+                        }
+
+                        Ok(AsyncEventLoopEvent::IncomingData(byte)) => {
+                            gdb.incoming_data(target, byte)?
+                        }
+
+                        Err(WaitForStopReasonError::Target(e)) => {
+                            break Err(InternalError::TargetError(e).into());
+                        }
+                        Err(WaitForStopReasonError::Connection(e)) => {
+                            break Err(conn_error(target, InternalError::conn_read(e)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run_blocking::*;
+    use super::*;
+    use crate::common::Signal;
+    use crate::stub::mock::*;
+
+    struct InterruptOnly;
+
+    impl BlockingEventLoop for InterruptOnly {
+        type Target = MockSingleTarget;
+        type Connection = MockConnection;
+        type StopReason = SingleThreadStopReason<u32>;
+
+        #[allow(clippy::panic)]
+        fn wait_for_stop_reason(
+            _target: &mut MockSingleTarget,
+            _conn: &mut MockConnection,
+        ) -> Result<Event<Self::StopReason>, WaitForStopReasonError<(), ()>> {
+            panic!("pending data should be handled without blocking")
+        }
+
+        fn on_interrupt(target: &mut MockSingleTarget) -> Result<Option<Self::StopReason>, ()> {
+            target.calls.push("interrupt".into());
+            Ok(Some(SingleThreadStopReason::Signal(Signal::SIGINT)))
+        }
+    }
+
+    #[test]
+    fn pending_interrupt_is_handled_without_blocking() {
+        let mut target = MockSingleTarget::default();
+        let mut conn = MockConnection::default();
+        let input = format!("{}\x03{}", packet("c"), packet("D"));
+        conn.input.extend(input.bytes());
+
+        let reason = GdbStub::new(conn)
+            .run_blocking::<InterruptOnly>(&mut target)
+            .unwrap();
+        assert_eq!(reason, DisconnectReason::Disconnect);
+        assert_eq!(target.calls, ["resume", "interrupt"]);
+    }
+
+    #[test]
+    fn connection_error_notifies_target() {
+        let mut target = MockSingleTarget {
+            protocol_callbacks: true,
+            ..Default::default()
+        };
+        let mut conn = MockConnection::default();
+        // the connection drops before GDB detaches
+        conn.input.extend(packet("?").bytes());
+
+        let err = GdbStub::new(conn).run_blocking::<InterruptOnly>(&mut target);
+        assert!(err.unwrap_err().is_connection_error());
+        assert_eq!(target.calls.last().unwrap(), "on_connection_error Read");
+        assert!(!target.calls.iter().any(|c| c.starts_with("on_disconnect")));
+    }
+
+    /// A connection which never reports pending data, ensuring that
+    /// `run_blocking` only reads from it when it isn't polling the target.
+    #[derive(Default)]
+    struct NoPeekConnection(MockConnection);
+
+    impl Connection for NoPeekConnection {
+        type Error = ();
+
+        fn write(&mut self, byte: u8) -> Result<(), ()> {
+            self.0.write(byte)
+        }
+
+        fn flush(&mut self) -> Result<(), ()> {
+            self.0.flush()
+        }
+    }
+
+    impl ConnectionExt for NoPeekConnection {
+        fn read(&mut self) -> Result<u8, ()> {
+            self.0.read()
+        }
+
+        fn peek(&mut self) -> Result<Option<u8>, ()> {
+            Ok(None)
+        }
+    }
+
+    const NON_STOP_EVENTS: usize = 12;
+
+    struct ManyStops;
+
+    impl BlockingEventLoop for ManyStops {
+        type Target = MockTarget;
+        type Connection = NoPeekConnection;
+        type StopReason = MultiThreadStopReason<u32>;
+
+        fn wait_for_stop_reason(
+            target: &mut MockTarget,
+            conn: &mut NoPeekConnection,
+        ) -> Result<Event<Self::StopReason>, WaitForStopReasonError<(), ()>> {
+            let stops = target.calls.iter().filter(|c| *c == "stop").count();
+            if stops == NON_STOP_EVENTS {
+                return conn
+                    .read()
+                    .map(Event::IncomingData)
+                    .map_err(WaitForStopReasonError::Connection);
+            }
+
+            target.calls.push("stop".into());
+            Ok(Event::TargetStopped(
+                MultiThreadStopReason::SignalWithThread {
+                    tid: tid(1),
+                    signal: Signal::SIGTRAP,
+                },
+            ))
+        }
+
+        fn on_interrupt(_target: &mut MockTarget) -> Result<Option<Self::StopReason>, ()> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn full_stop_queue_waits_for_client() {
+        let mut target = MockTarget {
+            threads: vec![tid(1)],
+            non_stop: true,
+            ..Default::default()
+        };
+        let mut conn = NoPeekConnection::default();
+        let mut input = packet("QNonStop:1") + &packet("vCont;c");
+        for _ in 0..NON_STOP_EVENTS {
+            input += &packet("vStopped");
+        }
+        input += &packet("D");
+        conn.0.input.extend(input.bytes());
+
+        let reason = GdbStub::new(conn)
+            .run_blocking::<ManyStops>(&mut target)
+            .unwrap();
+        assert_eq!(reason, DisconnectReason::Disconnect);
+        let stops = target.calls.iter().filter(|c| *c == "stop").count();
+        assert_eq!(stops, NON_STOP_EVENTS);
+    }
+
+    /// An in-memory `AsyncConnection`, fed from a fixed input stream.
+    #[cfg(feature = "alloc")]
+    #[derive(Default)]
+    struct AsyncMemConnection {
+        input: std::collections::VecDeque<u8>,
+        // shared, so that it can be inspected once `run_async` consumes the connection
+        output: std::rc::Rc<core::cell::RefCell<Vec<u8>>>,
+    }
+
+    #[cfg(feature = "alloc")]
+    impl AsyncConnection for AsyncMemConnection {
+        type Error = ();
+
+        async fn read(&mut self) -> Result<u8, ()> {
+            self.input.pop_front().ok_or(())
+        }
+
+        async fn write_all(&mut self, buf: &[u8]) -> Result<(), ()> {
+            self.output.borrow_mut().extend_from_slice(buf);
+            Ok(())
+        }
+
+        async fn flush(&mut self) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    struct AsyncInterruptOnly;
+
+    #[cfg(feature = "alloc")]
+    impl run_async::AsyncEventLoop for AsyncInterruptOnly {
+        type Target = MockSingleTarget;
+        type Connection = AsyncMemConnection;
+        type StopReason = SingleThreadStopReason<u32>;
+
+        async fn wait_for_stop_reason(
+            _target: &mut MockSingleTarget,
+            conn: &mut AsyncMemConnection,
+        ) -> Result<Event<Self::StopReason>, WaitForStopReasonError<(), ()>> {
+            conn.read()
+                .await
+                .map(Event::IncomingData)
+                .map_err(WaitForStopReasonError::Connection)
+        }
+
+        async fn on_interrupt(
+            target: &mut MockSingleTarget,
+        ) -> Result<Option<Self::StopReason>, ()> {
+            target.calls.push("interrupt".into());
+            Ok(Some(SingleThreadStopReason::Signal(Signal::SIGINT)))
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn run_async_end_to_end() {
+        use crate::conn::block_on;
+
+        let mut target = MockSingleTarget::default();
+        let mut conn = AsyncMemConnection::default();
+        let input = format!("{}\x03{}", packet("c"), packet("D"));
+        conn.input.extend(input.bytes());
+        let output = conn.output.clone();
+
+        let gdb = GdbStub::new(AsyncAdapter::new(conn));
+        let reason = block_on(gdb.run_async::<AsyncInterruptOnly>(&mut target)).unwrap();
+        assert_eq!(reason, DisconnectReason::Disconnect);
+        assert_eq!(target.calls, ["resume", "interrupt"]);
+
+        let output = String::from_utf8(output.take()).unwrap();
+        assert_eq!(output, format!("+{}+{}", packet("S02"), packet("OK")));
+    }
+}
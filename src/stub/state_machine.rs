@@ -1,35 +1,74 @@
 //! Low-level state-machine interface that underpins [`GdbStub`].
-//
-// TODO: write some proper documentation + examples of how to interface with
-// this API.
 //!
-//! # Hey, what gives? Where are all the docs!?
+//! Unlike [`GdbStub::run_blocking`], which takes over the current thread until
+//! the session ends, the state-machine API hands control back to the caller
+//! after every byte of input. This makes it possible to integrate `gdbstub`
+//! into an existing event loop, an `async` runtime, or a cooperatively
+//! scheduled `no_std` project, with the caller remaining in charge of all I/O.
 //!
-//! Yep, sorry about that!
+//! A session is started via [`GdbStub::run_state_machine`], which returns a
+//! [`GdbStubStateMachine`]. Each variant of the state machine exposes a
+//! different set of methods, corresponding to the actions that are valid in
+//! that state:
 //!
-//! `gdbstub` 0.6 turned out ot be a pretty massive release, and documenting
-//! everything has proven to be a somewhat gargantuan task that's kept delaying
-//! the release data further and further back...
+//! - [`Idle`](GdbStubStateMachine::Idle): the target is stopped, and the stub
+//!   is waiting for input from the client (via `incoming_data`).
+//! - [`Running`](GdbStubStateMachine::Running): the target is running. The
+//!   caller should keep feeding the stub incoming data, and call `report_stop`
+//!   once the target stops.
+//! - [`CtrlCInterrupt`](GdbStubStateMachine::CtrlCInterrupt): the client has
+//!   requested the target be interrupted. The caller should stop the target
+//!   (unless the target implements the
+//!   [`Interrupt`](crate::target::ext::interrupt::Interrupt) extension, in
+//!   which case the stop has already been requested), and then call
+//!   `interrupt_handled`.
+//! - [`Disconnected`](GdbStubStateMachine::Disconnected): the session has
+//!   ended, for the reason returned by `get_reason`.
 //!
-//! To avoid blocking the release any further, I've decided to leave this bit of
-//! the API sparsely documented.
+//! Every method consumes the current state, and returns the next one.
 //!
-//! If you're interested in using this API directly (e.g: to integrate `gdbstub`
-//! into a `no_std` project, or to use `gdbstub` in a non-blocking manner
-//! alongside `async/await` / a project specific event loop), your best bet
-//! would be to review the following bits of code to get a feel for the API:
+//! # Example
 //!
-//! - The implementation of [`GdbStub::run_blocking`]
-//! - Implementations of [`BlockingEventLoop`] used alongside
-//!   `GdbStub::run_blocking` (e.g: the in-tree `armv4t` / `armv4t_multicore`
-//!   examples)
-//! - Real-world projects using the API (see the repo's README.md)
+//! Feeding a buffer of bytes received from the client into the state
+//! machine:
 //!
-//! If you have any questions, feel free to open a discussion thread over at the
-//! [`gdbstub` GitHub repo](https://github.com/daniel5151/gdbstub/).
+//! ```
+//! use gdbstub::arch::Arch;
+//! use gdbstub::conn::Connection;
+//! use gdbstub::stub::state_machine::GdbStubStateMachine;
+//! use gdbstub::stub::GdbStubError;
+//! use gdbstub::stub::MultiThreadStopReason;
+//! use gdbstub::target::Target;
+//!
+//! type Usize<T> = <<T as Target>::Arch as Arch>::Usize;
+//!
+//! fn feed<'a, T: Target, C: Connection>(
+//!     mut gdb: GdbStubStateMachine<'a, T, C>,
+//!     target: &mut T,
+//!     incoming: &[u8],
+//! ) -> Result<GdbStubStateMachine<'a, T, C>, GdbStubError<T::Error, C::Error>> {
+//!     for &byte in incoming {
+//!         gdb = match gdb {
+//!             GdbStubStateMachine::Idle(gdb) => gdb.incoming_data(target, byte)?,
+//!             GdbStubStateMachine::Running(gdb) => gdb.incoming_data(target, byte)?,
+//!             // a real implementation would stop the target here, and report
+//!             // an appropriate stop reason
+//!             GdbStubStateMachine::CtrlCInterrupt(gdb) => {
+//!                 gdb.interrupt_handled(target, None::<MultiThreadStopReason<Usize<T>>>)?
+//!             }
+//!             // any further data is meaningless once the client disconnects
+//!             GdbStubStateMachine::Disconnected(gdb) => return Ok(gdb.into()),
+//!         };
+//!     }
+//!     Ok(gdb)
+//! }
+//! ```
+//!
+//! For a more complete example, refer to the implementation of
+//! [`GdbStub::run_blocking`], which is built on top of this API.
 //!
-//! [`BlockingEventLoop`]: super::run_blocking::BlockingEventLoop
 //! [`GdbStub::run_blocking`]: super::GdbStub::run_blocking
+//! [`GdbStub::run_state_machine`]: super::GdbStub::run_state_machine
 
 use super::core_impl::FinishExecStatus;
 use super::core_impl::GdbStubImpl;
@@ -44,6 +83,7 @@ use crate::protocol::ResponseWriter;
 use crate::stub::error::GdbStubError;
 use crate::stub::error::InternalError;
 use crate::stub::stop_reason::IntoStopReason;
+use crate::stub::MultiThreadStopReason;
 use crate::target::Target;
 use managed::ManagedSlice;
 
@@ -161,6 +201,31 @@ where
     }
 }
 
+impl<'a, S, T: Target, C: Connection> GdbStubStateMachineInner<'a, S, T, C> {
+    /// Notify the target that the session is ending, and transition into the
+    /// `Disconnected` state.
+    fn disconnect(self, target: &mut T, reason: DisconnectReason) -> GdbStubStateMachine<'a, T, C> {
+        if let Some(ops) = target.support_protocol_callbacks() {
+            ops.on_disconnect(reason);
+        }
+        self.transition(state::Disconnected { reason }).into()
+    }
+}
+
+/// Give the target a chance to clean up if the session was aborted due to a
+/// connection error.
+pub(crate) fn notify_connection_error<T: Target, C>(
+    target: &mut T,
+    e: GdbStubError<T::Error, C>,
+) -> GdbStubError<T::Error, C> {
+    if let Some(kind) = e.connection_error_kind() {
+        if let Some(ops) = target.support_protocol_callbacks() {
+            ops.on_connection_error(kind);
+        }
+    }
+    e
+}
+
 // split off `GdbStubStateMachineInner`'s non state-dependant data into separate
 // struct for code bloat optimization (i.e: `transition` will generate better
 // code when the struct is cleaved this way).
@@ -198,7 +263,7 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Idle<T>,
             i: GdbStubStateMachineReallyInner {
                 conn: stub.conn,
                 packet_buffer: stub.packet_buffer,
-                recv_packet: RecvPacketStateMachine::new(),
+                recv_packet: RecvPacketStateMachine::new(stub.inner.packet_size()),
                 inner: stub.inner,
             },
             state: state::Idle {
@@ -209,6 +274,15 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Idle<T>,
 
     /// Pass a byte to the GDB stub.
     pub fn incoming_data(
+        self,
+        target: &mut T,
+        byte: u8,
+    ) -> Result<GdbStubStateMachine<'a, T, C>, GdbStubError<T::Error, C::Error>> {
+        self.incoming_data_inner(target, byte)
+            .map_err(|e| notify_connection_error(target, e))
+    }
+
+    fn incoming_data_inner(
         mut self,
         target: &mut T,
         byte: u8,
@@ -218,14 +292,19 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Idle<T>,
             None => return Ok(self.into()),
         };
 
-        let packet = Packet::from_buf(target, packet_buffer).map_err(InternalError::PacketParse)?;
-        let state = self
-            .i
-            .inner
-            .handle_packet(target, &mut self.i.conn, packet)?;
+        let state = match Packet::from_buf(target, packet_buffer) {
+            Ok(packet) => self
+                .i
+                .inner
+                .handle_packet(target, &mut self.i.conn, packet)?,
+            Err(e) => {
+                warn!("failed to parse packet: {:?}", e);
+                State::Disconnect(DisconnectReason::ProtocolError(e.into()))
+            }
+        };
         Ok(match state {
             State::Pump => self.into(),
-            State::Disconnect(reason) => self.transition(state::Disconnected { reason }).into(),
+            State::Disconnect(reason) => self.disconnect(target, reason),
             State::DeferredStopReason => {
                 match self.state.deferred_ctrlc_stop_reason {
                     // if we were interrupted while idle, immediately report the deferred stop
@@ -233,7 +312,7 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Idle<T>,
                     Some(reason) => {
                         return self
                             .transition(state::Running {})
-                            .report_stop(target, reason)
+                            .report_stop_inner(target, reason)
                     }
                     // otherwise, just transition into the running state as usual
                     None => self.transition(state::Running {}).into(),
@@ -250,13 +329,72 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Idle<T>,
 /// [`GdbStubStateMachine::Running`] state.
 impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Running, T, C> {
     /// Report a target stop reason back to GDB.
+    ///
+    /// Resume packets (e.g: `c`, `s`, `vCont`) are not responded to until the
+    /// target reports a stop reason, so this method may be called at any point
+    /// after resuming the target (e.g: once an asynchronously resumed target
+    /// finally stops), writing the corresponding `S`/`T`/`W`/`X` packet.
+    ///
+    /// Thread events reported while the client has not enabled them (see
+    /// [`ThreadEvents`](crate::target::ext::thread_events::ThreadEvents)) are
+    /// dropped, leaving the target running.
+    ///
+    /// In non-stop mode, stop events reported while the client has yet to
+    /// acknowledge an earlier notification are queued. Once that queue is full
+    /// (see [`is_stop_queue_full`](Self::is_stop_queue_full)), reporting
+    /// another stop event results in a fatal error.
     pub fn report_stop(
-        mut self,
+        self,
         target: &mut T,
         reason: impl IntoStopReason<T>,
     ) -> Result<GdbStubStateMachine<'a, T, C>, GdbStubError<T::Error, C::Error>> {
+        self.report_stop_inner(target, reason.into())
+            .map_err(|e| notify_connection_error(target, e))
+    }
+
+    /// Whether the stub is unable to accept any more stop events.
+    ///
+    /// This can only happen in non-stop mode, when the GDB client has yet to
+    /// acknowledge previously reported stop events. Callers should stop
+    /// polling the target for stop events, and instead feed incoming data to
+    /// the stub until the client catches up.
+    pub fn is_stop_queue_full(&self) -> bool {
+        self.i.inner.is_stop_queue_full()
+    }
+
+    fn report_stop_inner(
+        mut self,
+        target: &mut T,
+        reason: MultiThreadStopReason<<T::Arch as Arch>::Usize>,
+    ) -> Result<GdbStubStateMachine<'a, T, C>, GdbStubError<T::Error, C::Error>> {
+        // an emulated range step may swallow intermediate steps, keeping the
+        // target running without involving the client
+        if self.i.inner.continue_emulated_range_step(target, &reason)? {
+            return Ok(self.into());
+        }
+
+        // likewise, thread events the client didn't ask for keep the target running
+        if self.i.inner.is_unwanted_thread_event(target, &reason) {
+            return Ok(self.into());
+        }
+
+        // in non-stop mode, stop events are reported via asynchronous notifications,
+        // with the target remaining in the running state (as other threads may still
+        // be running).
+        if self.i.inner.is_non_stop() {
+            let event = self
+                .i
+                .inner
+                .report_stop_non_stop(&mut self.i.conn, target, reason)?;
+
+            return Ok(match event {
+                FinishExecStatus::Handled => self.into(),
+                FinishExecStatus::Disconnect(reason) => self.disconnect(target, reason),
+            });
+        }
+
         let mut res = ResponseWriter::new(&mut self.i.conn, target.use_rle());
-        let event = self.i.inner.finish_exec(&mut res, target, reason.into())?;
+        let event = self.i.inner.finish_exec(&mut res, target, reason)?;
         res.flush().map_err(InternalError::from)?;
 
         Ok(match event {
@@ -265,14 +403,21 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Running,
                     deferred_ctrlc_stop_reason: None,
                 })
                 .into(),
-            FinishExecStatus::Disconnect(reason) => {
-                self.transition(state::Disconnected { reason }).into()
-            }
+            FinishExecStatus::Disconnect(reason) => self.disconnect(target, reason),
         })
     }
 
     /// Pass a byte to the GDB stub.
     pub fn incoming_data(
+        self,
+        target: &mut T,
+        byte: u8,
+    ) -> Result<GdbStubStateMachine<'a, T, C>, GdbStubError<T::Error, C::Error>> {
+        self.incoming_data_inner(target, byte)
+            .map_err(|e| notify_connection_error(target, e))
+    }
+
+    fn incoming_data_inner(
         mut self,
         target: &mut T,
         byte: u8,
@@ -282,14 +427,19 @@ impl<'a, T: Target, C: Connection> GdbStubStateMachineInner<'a, state::Running,
             None => return Ok(self.into()),
         };
 
-        let packet = Packet::from_buf(target, packet_buffer).map_err(InternalError::PacketParse)?;
-        let state = self
-            .i
-            .inner
-            .handle_packet(target, &mut self.i.conn, packet)?;
+        let state = match Packet::from_buf(target, packet_buffer) {
+            Ok(packet) => self
+                .i
+                .inner
+                .handle_packet(target, &mut self.i.conn, packet)?,
+            Err(e) => {
+                warn!("failed to parse packet: {:?}", e);
+                State::Disconnect(DisconnectReason::ProtocolError(e.into()))
+            }
+        };
         Ok(match state {
             State::Pump => self.transition(state::Running {}).into(),
-            State::Disconnect(reason) => self.transition(state::Disconnected { reason }).into(),
+            State::Disconnect(reason) => self.disconnect(target, reason),
             State::DeferredStopReason => self.transition(state::Running {}).into(),
             State::CtrlCInterrupt => self
                 .transition(state::CtrlCInterrupt { from_idle: false })
@@ -1,6 +1,7 @@
 //! Stop reasons reported back to the GDB client.
 
 use crate::arch::Arch;
+use crate::common::Pid;
 use crate::common::Signal;
 use crate::common::Tid;
 use crate::target::ext::base::reverse_exec::ReplayLogPosition;
@@ -31,7 +32,10 @@ pub enum BaseStopReason<Tid, U> {
     /// Completed the single-step request.
     DoneStep,
     /// The process exited with the specified exit status.
-    Exited(u8),
+    ///
+    /// Exit statuses are not limited to 8 bits, and are reported to the client
+    /// in full.
+    Exited(u32),
     /// The process terminated with the specified signal number.
     Terminated(Signal),
     /// The program received a signal.
@@ -101,6 +105,86 @@ pub enum BaseStopReason<Tid, U> {
         /// The location the event occurred at.
         position: CatchSyscallPosition,
     },
+    /// A thread called `fork`, creating a new child process.
+    ///
+    /// Requires: [`ForkEvents`].
+    ///
+    /// If the GDB client did not negotiate support for fork events, this is
+    /// reported as a plain `SIGTRAP` on the parent thread.
+    ///
+    /// [`ForkEvents`]: crate::target::ext::fork_events::ForkEvents
+    Fork {
+        /// Tid of the thread which called `fork`.
+        tid: Tid,
+        /// Pid of the new child process.
+        child_pid: Pid,
+        /// Tid of the (sole) thread in the new child process.
+        child_tid: Tid,
+    },
+    /// A thread called `vfork`, creating a new child process which shares its
+    /// parent's address space.
+    ///
+    /// Requires: [`VforkEvents`].
+    ///
+    /// If the GDB client did not negotiate support for vfork events, this is
+    /// reported as a plain `SIGTRAP` on the parent thread.
+    ///
+    /// [`VforkEvents`]: crate::target::ext::fork_events::VforkEvents
+    Vfork {
+        /// Tid of the thread which called `vfork`.
+        tid: Tid,
+        /// Pid of the new child process.
+        child_pid: Pid,
+        /// Tid of the (sole) thread in the new child process.
+        child_tid: Tid,
+    },
+    /// The child of a previously reported [`Vfork`](Self::Vfork) has called
+    /// `exec` or exited, and no longer shares its parent's address space.
+    ///
+    /// Requires: [`VforkEvents`].
+    ///
+    /// The `Tid` must be that of the thread which originally called `vfork`
+    /// (i.e: the `tid` reported alongside the corresponding `Vfork` event),
+    /// _not_ a thread in the child process.
+    ///
+    /// [`VforkEvents`]: crate::target::ext::fork_events::VforkEvents
+    VforkDone(Tid),
+    /// A thread called `exec`, replacing the process's executable image.
+    ///
+    /// Requires: [`ExecEvents`].
+    ///
+    /// The pathname of the new executable is queried using
+    /// [`ExecEvents::get_exec_event_path`].
+    ///
+    /// If the GDB client did not negotiate support for exec events, this is
+    /// reported as a plain `SIGTRAP`.
+    ///
+    /// [`ExecEvents`]: crate::target::ext::exec_events::ExecEvents
+    /// [`ExecEvents::get_exec_event_path`]: crate::target::ext::exec_events::ExecEvents::get_exec_event_path
+    Exec(Tid),
+    /// A new thread was created.
+    ///
+    /// Requires: [`ThreadEvents`].
+    ///
+    /// If the GDB client did not enable thread events, this is not reported
+    /// at all, and the target remains running.
+    ///
+    /// [`ThreadEvents`]: crate::target::ext::thread_events::ThreadEvents
+    ThreadCreated(Tid),
+    /// A thread exited with the given status code.
+    ///
+    /// Requires: [`ThreadEvents`].
+    ///
+    /// If the GDB client did not enable thread events, this is not reported
+    /// at all, and the target remains running.
+    ///
+    /// [`ThreadEvents`]: crate::target::ext::thread_events::ThreadEvents
+    ThreadExited {
+        /// Tid of the thread which exited.
+        tid: Tid,
+        /// The thread's exit status.
+        code: u32,
+    },
 }
 
 /// A stop reason for a single threaded target.
@@ -140,6 +224,25 @@ impl<U> From<BaseStopReason<(), U>> for BaseStopReason<Tid, U> {
                 number,
                 position,
             },
+            BaseStopReason::Fork { child_pid, .. } => BaseStopReason::Fork {
+                tid: crate::SINGLE_THREAD_TID,
+                child_pid,
+                child_tid: crate::SINGLE_THREAD_TID,
+            },
+            BaseStopReason::Vfork { child_pid, .. } => BaseStopReason::Vfork {
+                tid: crate::SINGLE_THREAD_TID,
+                child_pid,
+                child_tid: crate::SINGLE_THREAD_TID,
+            },
+            BaseStopReason::VforkDone(_) => BaseStopReason::VforkDone(crate::SINGLE_THREAD_TID),
+            BaseStopReason::Exec(_) => BaseStopReason::Exec(crate::SINGLE_THREAD_TID),
+            BaseStopReason::ThreadCreated(_) => {
+                BaseStopReason::ThreadCreated(crate::SINGLE_THREAD_TID)
+            }
+            BaseStopReason::ThreadExited { code, .. } => BaseStopReason::ThreadExited {
+                tid: crate::SINGLE_THREAD_TID,
+                code,
+            },
         }
     }
 }
@@ -0,0 +1,17 @@
+//! Enable or disable the target's in-process agent.
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Enable or disable the target's in-process agent.
+///
+/// Targets which ship a debugging agent (e.g: for evaluating fast tracepoints
+/// or breakpoint conditions without involving the GDB client) can implement
+/// this extension to allow the GDB client to control it via `set agent
+/// on/off`. See the [GDB documentation](https://sourceware.org/gdb/onlinedocs/gdb/In_002dProcess-Agent.html)
+/// for further details.
+pub trait Agent: Target {
+    /// Enable or disable the in-process agent.
+    fn set_agent(&mut self, enabled: bool) -> TargetResult<(), Self>;
+}
+
+define_ext!(AgentOps, Agent);
@@ -0,0 +1,23 @@
+//! Access the target’s auxiliary vector.
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Access the target’s auxiliary vector.
+pub trait Auxv: Target {
+    /// Get auxiliary vector from the target.
+    ///
+    /// Return the number of bytes written into `buf` (which may be less than
+    /// `length`).
+    ///
+    /// If `offset` is greater than the length of the underlying data, return
+    /// `Ok(0)`.
+    ///
+    /// Large auxiliary vectors are transferred across multiple calls, with the
+    /// GDB client advancing `offset` until `Ok(0)` is returned. Targets which
+    /// have the entire auxiliary vector on hand can use
+    /// [`copy_range_to_buf`](crate::target::ext::copy_range_to_buf) to
+    /// implement this method.
+    fn get_auxv(&self, offset: u64, length: usize, buf: &mut [u8]) -> TargetResult<usize, Self>;
+}
+
+define_ext!(AuxvOps, Auxv);
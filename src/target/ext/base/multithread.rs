@@ -0,0 +1,420 @@
+//! Base debugging operations for multi threaded targets.
+
+use crate::arch::Arch;
+use crate::common::Signal;
+use crate::common::Tid;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Base required debugging operations for multi threaded targets.
+pub trait MultiThreadBase: Target {
+    /// Read the target's registers.
+    ///
+    /// If the registers could not be accessed, an appropriate non-fatal error
+    /// should be returned.
+    fn read_registers(
+        &mut self,
+        regs: &mut <Self::Arch as Arch>::Registers,
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// Write the target's registers.
+    ///
+    /// If the registers could not be accessed, an appropriate non-fatal error
+    /// should be returned.
+    fn write_registers(
+        &mut self,
+        regs: &<Self::Arch as Arch>::Registers,
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// Support for single-register access.
+    /// See [`SingleRegisterAccess`] for more details.
+    ///
+    /// While this is an optional feature, it is **highly recommended** to
+    /// implement it when possible, as it can significantly improve performance
+    /// on certain architectures.
+    ///
+    /// Implementing this extension also enables `QThreadSuffixSupported`,
+    /// which lets clients (e.g: LLDB) specify which thread a register access
+    /// applies to directly, instead of via a separate thread selection packet.
+    ///
+    /// [`SingleRegisterAccess`]:
+    /// super::single_register_access::SingleRegisterAccess
+    #[inline(always)]
+    fn support_single_register_access(
+        &mut self,
+    ) -> Option<super::single_register_access::SingleRegisterAccessOps<'_, Tid, Self>> {
+        None
+    }
+
+    /// Read bytes from the specified address range and return the number of
+    /// bytes that were read.
+    ///
+    /// Implementations may return a number `n` that is less than `data.len()`
+    /// to indicate that memory starting at `start_addr + n` cannot be
+    /// accessed.
+    ///
+    /// Implemenations may also return an appropriate non-fatal error if the
+    /// requested address range could not be accessed (e.g: due to MMU
+    /// protection, unhanded page fault, etc...).
+    ///
+    /// Implementations must guarantee that the returned number is less than or
+    /// equal `data.len()`.
+    fn read_addrs(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        data: &mut [u8],
+        tid: Tid,
+    ) -> TargetResult<usize, Self>;
+
+    /// Write bytes to the specified address range.
+    ///
+    /// If the requested address range could not be accessed (e.g: due to
+    /// MMU protection, unhanded page fault, etc...), an appropriate non-fatal
+    /// error should be returned.
+    fn write_addrs(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        data: &[u8],
+        tid: Tid,
+    ) -> TargetResult<(), Self>;
+
+    /// List all currently active threads.
+    ///
+    /// See [the section above](#bare-metal-targets) on implementing
+    /// thread-related methods on bare-metal (threadless) targets.
+    ///
+    /// _Note_: Implementors should mark this method as `#[inline(always)]`, as
+    /// this will result in better codegen (namely, by sidestepping any of the
+    /// `dyn FnMut` closure machinery).
+    fn list_active_threads(
+        &mut self,
+        thread_is_active: &mut dyn FnMut(Tid),
+    ) -> Result<(), Self::Error>;
+
+    /// Check if the specified thread is alive.
+    ///
+    /// As a convenience, this method provides a default implementation which
+    /// uses `list_active_threads` to do a linear-search through all active
+    /// threads. On thread-heavy systems, it may be more efficient
+    /// to override this method with a more direct query.
+    #[allow(clippy::wrong_self_convention)] // requires breaking change to fix
+    fn is_thread_alive(&mut self, tid: Tid) -> Result<bool, Self::Error> {
+        let mut found = false;
+        self.list_active_threads(&mut |active_tid| {
+            if tid == active_tid {
+                found = true;
+            }
+        })?;
+        Ok(found)
+    }
+
+    /// (optional) Return the thread the target considers "current", e.g: the
+    /// thread which most recently stopped.
+    ///
+    /// This is reported to GDB in response to the `qC` packet. By default,
+    /// `gdbstub` reports the thread most recently selected by GDB / reported
+    /// in a stop reason, which may be stale if the target stopped without
+    /// specifying a thread (e.g: on a spontaneous breakpoint hit).
+    ///
+    /// The default implementation returns `Ok(None)`, deferring to
+    /// `gdbstub`'s internal bookkeeping.
+    fn current_thread(&mut self) -> Result<Option<Tid>, Self::Error> {
+        Ok(None)
+    }
+
+    /// (optional) Return the processor core the specified thread last ran on.
+    ///
+    /// When `Some`, the core is included in stop replies for the thread (via
+    /// the `core:<n>;` tag), allowing GDB to report which core e.g: hit a
+    /// breakpoint.
+    ///
+    /// The default implementation returns `Ok(None)`, in which case no core is
+    /// reported.
+    fn thread_core(&mut self, tid: Tid) -> Result<Option<usize>, Self::Error> {
+        let _ = tid;
+        Ok(None)
+    }
+
+    /// Support for resuming the target (e.g: via `continue` or `step`)
+    #[inline(always)]
+    fn support_resume(&mut self) -> Option<MultiThreadResumeOps<'_, Self>> {
+        None
+    }
+
+    /// Support for providing thread extra information.
+    #[inline(always)]
+    fn support_thread_extra_info(
+        &mut self,
+    ) -> Option<crate::target::ext::thread_extra_info::ThreadExtraInfoOps<'_, Self>> {
+        None
+    }
+
+    /// Support for reporting thread creation and exit events.
+    #[inline(always)]
+    fn support_thread_events(
+        &mut self,
+    ) -> Option<crate::target::ext::thread_events::ThreadEventsOps<'_, Self>> {
+        None
+    }
+
+    /// Support for providing a detailed list of threads (e.g: thread names).
+    #[inline(always)]
+    fn support_thread_list(
+        &mut self,
+    ) -> Option<crate::target::ext::thread_list::ThreadListOps<'_, Self>> {
+        None
+    }
+
+    /// Support for enumerating the threads of every process being debugged.
+    #[inline(always)]
+    fn support_process_list(
+        &mut self,
+    ) -> Option<crate::target::ext::process_list::ProcessListOps<'_, Self>> {
+        None
+    }
+}
+
+/// Target extension - support for resuming multi threaded targets.
+pub trait MultiThreadResume: Target {
+    /// Resume execution on the target.
+    ///
+    /// Prior to calling `resume`, `gdbstub` will call `clear_resume_actions`,
+    /// followed by zero or more calls to the `set_resume_action_XXX` methods,
+    /// specifying any thread-specific resume actions.
+    ///
+    /// Upon returning from the `resume` method, the target being debugged
+    /// should be configured to run according to whatever resume actions the
+    /// GDB client had specified using any of the `set_resume_action_XXX`
+    /// methods.
+    ///
+    /// Any thread that wasn't explicitly resumed by a `set_resume_action_XXX`
+    /// method should be resumed as though it was resumed with
+    /// `set_resume_action_continue`.
+    ///
+    /// A basic target implementation only needs to implement support for
+    /// `set_resume_action_continue`, with all other resume actions requiring
+    /// their corresponding protocol extension to be implemented:
+    ///
+    /// Action                      | Protocol Extension
+    /// ----------------------------|------------------------------
+    /// Optimized [Single Stepping] | See [`support_single_step()`]
+    /// Optimized [Range Stepping]  | See [`support_range_step()`]
+    /// "Stop"                      | See [`support_non_stop()`] \*
+    ///
+    /// \* "Stop" is only used in "Non-Stop" mode, which also changes how
+    /// threads without an explicit resume action should be treated. See
+    /// [`MultiThreadNonStop`] for details.
+    ///
+    /// [Single stepping]: https://sourceware.org/gdb/current/onlinedocs/gdb/Continuing-and-Stepping.html#index-stepi
+    /// [Range Stepping]: https://sourceware.org/gdb/current/onlinedocs/gdb/Continuing-and-Stepping.html#range-stepping
+    /// [`support_single_step()`]: Self::support_single_step
+    /// [`support_range_step()`]: Self::support_range_step
+    /// [`support_non_stop()`]: Self::support_non_stop
+    ///
+    /// # Additional Considerations
+    ///
+    /// ### Adjusting PC after a breakpoint is hit
+    ///
+    /// The [GDB remote serial protocol documentation](https://sourceware.org/gdb/current/onlinedocs/gdb/Stop-Reply-Packets.html#swbreak-stop-reason)
+    /// notes the following:
+    ///
+    /// > On some architectures, such as x86, at the architecture level, when a
+    /// > breakpoint instruction executes the program counter points at the
+    /// > breakpoint address plus an offset. On such targets, the stub is
+    /// > responsible for adjusting the PC to point back at the breakpoint
+    /// > address.
+    ///
+    /// Omitting PC adjustment may result in unexpected execution flow and/or
+    /// breakpoints not appearing to work correctly.
+    ///
+    /// ### Bare-Metal Targets
+    ///
+    /// On bare-metal targets (such as microcontrollers or emulators), it's
+    /// common to treat individual _CPU cores_ as a separate "threads". e.g:
+    /// in a dual-core system, [CPU0, CPU1] might be mapped to [TID1, TID2]
+    /// (note that TIDs cannot be zero).
+    ///
+    /// In this case, the `Tid` argument of `read/write_addrs` becomes quite
+    /// relevant, as different cores may have different memory maps.
+    fn resume(&mut self) -> Result<(), Self::Error>;
+
+    /// Clear all previously set resume actions.
+    fn clear_resume_actions(&mut self) -> Result<(), Self::Error>;
+
+    /// Continue the specified thread.
+    ///
+    /// See the [`resume`](Self::resume) docs for information on when this is
+    /// called.
+    ///
+    /// The GDB client may also include a `signal` which should be passed to the
+    /// target.
+    fn set_resume_action_continue(
+        &mut self,
+        tid: Tid,
+        signal: Option<Signal>,
+    ) -> Result<(), Self::Error>;
+
+    /// Support for optimized [single stepping].
+    ///
+    /// [single stepping]: https://sourceware.org/gdb/current/onlinedocs/gdb/Continuing-and-Stepping.html#index-stepi
+    #[inline(always)]
+    fn support_single_step(&mut self) -> Option<MultiThreadSingleStepOps<'_, Self>> {
+        None
+    }
+
+    /// Support for optimized [range stepping].
+    ///
+    /// [range stepping]: https://sourceware.org/gdb/current/onlinedocs/gdb/Continuing-and-Stepping.html#range-stepping
+    #[inline(always)]
+    fn support_range_step(&mut self) -> Option<MultiThreadRangeSteppingOps<'_, Self>> {
+        None
+    }
+
+    /// Support for [reverse stepping] a target.
+    ///
+    /// [reverse stepping]: https://sourceware.org/gdb/current/onlinedocs/gdb/Reverse-Execution.html
+    #[inline(always)]
+    fn support_reverse_step(
+        &mut self,
+    ) -> Option<super::reverse_exec::ReverseStepOps<'_, Tid, Self>> {
+        None
+    }
+
+    /// Support for [reverse continuing] a target.
+    ///
+    /// [reverse continuing]: https://sourceware.org/gdb/current/onlinedocs/gdb/Reverse-Execution.html
+    #[inline(always)]
+    fn support_reverse_cont(
+        &mut self,
+    ) -> Option<super::reverse_exec::ReverseContOps<'_, Tid, Self>> {
+        None
+    }
+
+    /// Support for [non-stop mode].
+    ///
+    /// [non-stop mode]: https://sourceware.org/gdb/current/onlinedocs/gdb/Non_002dStop-Mode.html
+    #[inline(always)]
+    fn support_non_stop(&mut self) -> Option<MultiThreadNonStopOps<'_, Self>> {
+        None
+    }
+}
+
+define_ext!(MultiThreadResumeOps, MultiThreadResume);
+
+/// Target Extension - Optimized single stepping for multi threaded targets.
+/// See [`MultiThreadResume::support_single_step`].
+pub trait MultiThreadSingleStep: Target + MultiThreadResume {
+    /// [Single step] the specified target thread.
+    ///
+    /// Single stepping will step the target a single "step" - typically a
+    /// single instruction.
+    ///
+    /// The GDB client may also include a `signal` which should be passed to the
+    /// target.
+    ///
+    /// If your target does not support signals (e.g: the target is a bare-metal
+    /// microcontroller / emulator), the recommended behavior is to return a
+    /// target-specific fatal error
+    ///
+    /// [Single step]: https://sourceware.org/gdb/current/onlinedocs/gdb/Continuing-and-Stepping.html#index-stepi
+    fn set_resume_action_step(
+        &mut self,
+        tid: Tid,
+        signal: Option<Signal>,
+    ) -> Result<(), Self::Error>;
+}
+
+define_ext!(MultiThreadSingleStepOps, MultiThreadSingleStep);
+
+/// Target Extension - Optimized range stepping for multi threaded targets.
+/// See [`MultiThreadResume::support_range_step`].
+pub trait MultiThreadRangeStepping: Target + MultiThreadResume {
+    /// [Range step] the specified target thread.
+    ///
+    /// Range Stepping will step the target once, and keep stepping the target
+    /// as long as execution remains between the specified start (inclusive)
+    /// and end (exclusive) addresses, or another stop condition is met
+    /// (e.g: a breakpoint it hit).
+    ///
+    /// If the range is empty (`start` == `end`), then the action becomes
+    /// equivalent to the ‘s’ action. In other words, single-step once, and
+    /// report the stop (even if the stepped instruction jumps to start).
+    ///
+    /// _Note:_ A stop reply may be sent at any point even if the PC is still
+    /// within the stepping range; for example, it is valid to implement range
+    /// stepping in a degenerate way as a single instruction step operation.
+    ///
+    /// [Range step]: https://sourceware.org/gdb/current/onlinedocs/gdb/Continuing-and-Stepping.html#range-stepping
+    fn set_resume_action_range_step(
+        &mut self,
+        tid: Tid,
+        start: <Self::Arch as Arch>::Usize,
+        end: <Self::Arch as Arch>::Usize,
+    ) -> Result<(), Self::Error>;
+}
+
+define_ext!(MultiThreadRangeSteppingOps, MultiThreadRangeStepping);
+
+/// Target Extension - Support for [non-stop mode] on multi threaded targets.
+/// See [`MultiThreadResume::support_non_stop`].
+///
+/// In non-stop mode, each thread is resumed and stopped independently, with
+/// the GDB client remaining responsive while (some) threads are running.
+///
+/// While non-stop mode is enabled, the semantics of
+/// [`MultiThreadResume::resume`] change slightly: any thread that wasn't given
+/// an explicit resume action must be left in its current state (i.e: running
+/// threads keep running, and stopped threads stay stopped), unless
+/// [`set_resume_action_continue_all`](Self::set_resume_action_continue_all) was
+/// called.
+///
+/// Stop events should be reported as they occur (e.g: via
+/// `GdbStubStateMachineInner::report_stop`). `gdbstub` takes care of sending
+/// them to the client as asynchronous notifications, queueing any events
+/// that occur before the client has acknowledged the previous notification.
+///
+/// In non-stop mode, the client interrupts the target using the `vCtrlC`
+/// packet, which `gdbstub` surfaces as a regular Ctrl-C interrupt (i.e: via
+/// `GdbStubStateMachine::CtrlCInterrupt`). Unlike in all-stop mode, the target
+/// doesn't need to stop every thread: it is enough to request that (at least)
+/// one thread stops, and to report a stop reason once it actually does.
+///
+/// [non-stop mode]: https://sourceware.org/gdb/current/onlinedocs/gdb/Non_002dStop-Mode.html
+pub trait MultiThreadNonStop: Target + MultiThreadResume {
+    /// Enable or disable non-stop mode.
+    ///
+    /// Called in response to the client sending `QNonStop`, which typically
+    /// occurs early in the debugging session, while the target is stopped.
+    fn set_non_stop(&mut self, enabled: bool) -> TargetResult<(), Self>;
+
+    /// Stop the specified thread.
+    ///
+    /// Once the thread has stopped, the target should report a
+    /// `SignalWithThread` stop reason with `Signal(0)`.
+    fn set_resume_action_stop(&mut self, tid: Tid) -> Result<(), Self::Error>;
+
+    /// Stop all threads.
+    ///
+    /// See [`set_resume_action_stop`](Self::set_resume_action_stop).
+    fn set_resume_action_stop_all(&mut self) -> Result<(), Self::Error>;
+
+    /// Continue all threads that weren't given an explicit resume action.
+    fn set_resume_action_continue_all(&mut self) -> Result<(), Self::Error>;
+
+    /// Check if the specified thread is currently stopped.
+    ///
+    /// In non-stop mode, the client may attempt to access the registers of
+    /// any thread, including those which are still running. `gdbstub` uses
+    /// this method to reject such accesses with an error (instead of
+    /// reporting potentially stale register values).
+    ///
+    /// The default implementation assumes every thread is stopped.
+    fn is_thread_stopped(&mut self, tid: Tid) -> Result<bool, Self::Error> {
+        let _ = tid;
+        Ok(true)
+    }
+}
+
+define_ext!(MultiThreadNonStopOps, MultiThreadNonStop);
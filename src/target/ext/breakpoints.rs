@@ -0,0 +1,278 @@
+//! Add/Remove various kinds of breakpoints.
+
+use crate::arch::Arch;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Set/Remove Breakpoints.
+pub trait Breakpoints: Target {
+    /// Support for setting / removing software breakpoints.
+    #[inline(always)]
+    fn support_sw_breakpoint(&mut self) -> Option<SwBreakpointOps<'_, Self>> {
+        None
+    }
+
+    /// Support for setting / removing hardware breakpoints.
+    #[inline(always)]
+    fn support_hw_breakpoint(&mut self) -> Option<HwBreakpointOps<'_, Self>> {
+        None
+    }
+
+    /// Support for setting / removing hardware watchpoints.
+    #[inline(always)]
+    fn support_hw_watchpoint(&mut self) -> Option<HwWatchpointOps<'_, Self>> {
+        None
+    }
+
+    /// Support for evaluating breakpoint conditions on the target.
+    #[inline(always)]
+    fn support_conditional_breakpoints(&mut self) -> Option<ConditionalBreakpointsOps<'_, Self>> {
+        None
+    }
+
+    /// Support for running breakpoint command lists on the target.
+    #[inline(always)]
+    fn support_breakpoint_commands(&mut self) -> Option<BreakpointCommandsOps<'_, Self>> {
+        None
+    }
+}
+
+define_ext!(BreakpointsOps, Breakpoints);
+
+/// Nested Target Extension - Set/Remove Software Breakpoints.
+///
+/// See [this stackoverflow discussion](https://stackoverflow.com/questions/8878716/what-is-the-difference-between-hardware-and-software-breakpoints)
+/// about the differences between hardware and software breakpoints.
+///
+/// _Recommendation:_ If you're implementing `Target` for an emulator that's
+/// using an _interpreted_ CPU (as opposed to a JIT), the simplest way to
+/// implement "software" breakpoints would be to check the `PC` value after each
+/// CPU cycle, ignoring the specified breakpoint `kind` entirely.
+pub trait SwBreakpoint: Target + Breakpoints {
+    /// Add a new software breakpoint.
+    ///
+    /// Return `Ok(false)` if the operation could not be completed.
+    fn add_sw_breakpoint(
+        &mut self,
+        addr: <Self::Arch as Arch>::Usize,
+        kind: <Self::Arch as Arch>::BreakpointKind,
+    ) -> TargetResult<bool, Self>;
+
+    /// Remove an existing software breakpoint.
+    ///
+    /// Return `Ok(false)` if the operation could not be completed.
+    fn remove_sw_breakpoint(
+        &mut self,
+        addr: <Self::Arch as Arch>::Usize,
+        kind: <Self::Arch as Arch>::BreakpointKind,
+    ) -> TargetResult<bool, Self>;
+}
+
+define_ext!(SwBreakpointOps, SwBreakpoint);
+
+/// Nested Target Extension - Set/Remove Hardware Breakpoints.
+///
+/// See [this stackoverflow discussion](https://stackoverflow.com/questions/8878716/what-is-the-difference-between-hardware-and-software-breakpoints)
+/// about the differences between hardware and software breakpoints.
+///
+/// _Recommendation:_ If you're implementing `Target` for an emulator that's
+/// using an _interpreted_ CPU (as opposed to a JIT), there shouldn't be any
+/// reason to implement this extension (as software breakpoints are likely to be
+/// just-as-fast).
+pub trait HwBreakpoint: Target + Breakpoints {
+    /// Add a new hardware breakpoint.
+    ///
+    /// Return `Ok(false)` if the operation could not be completed.
+    fn add_hw_breakpoint(
+        &mut self,
+        addr: <Self::Arch as Arch>::Usize,
+        kind: <Self::Arch as Arch>::BreakpointKind,
+    ) -> TargetResult<bool, Self>;
+
+    /// Remove an existing hardware breakpoint.
+    ///
+    /// Return `Ok(false)` if the operation could not be completed.
+    fn remove_hw_breakpoint(
+        &mut self,
+        addr: <Self::Arch as Arch>::Usize,
+        kind: <Self::Arch as Arch>::BreakpointKind,
+    ) -> TargetResult<bool, Self>;
+
+    /// Return the maximum number of hardware breakpoints which can be set at
+    /// once (e.g: the number of available debug registers).
+    ///
+    /// When a limit is returned, `gdbstub` keeps track of which hardware
+    /// breakpoints are currently set, and rejects any new ones beyond the limit
+    /// with a non-fatal error, without invoking `add_hw_breakpoint`.
+    /// Re-adding an existing breakpoint (which GDB does when updating its
+    /// conditions) doesn't count against the limit, and all breakpoints are
+    /// considered cleared once the target is restarted, killed, or detached
+    /// from.
+    ///
+    /// Limits above 16 are not enforced by `gdbstub`, and should be handled by
+    /// the target instead (e.g: by returning `Ok(false)`).
+    ///
+    /// Defaults to `None` (i.e: no limit enforced by `gdbstub`).
+    #[inline(always)]
+    fn hw_breakpoint_count(&self) -> Option<usize> {
+        None
+    }
+}
+
+define_ext!(HwBreakpointOps, HwBreakpoint);
+
+/// The kind of watchpoint that should be set/removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchKind {
+    /// Fire when the memory location is written to (`Z2`).
+    Write,
+    /// Fire when the memory location is read from (`Z3`).
+    Read,
+    /// Fire when the memory location is written to and/or read from (`Z4`).
+    ReadWrite,
+}
+
+/// Nested Target Extension - Set/Remove Hardware Watchpoints.
+///
+/// See the [GDB documentation](https://sourceware.org/gdb/current/onlinedocs/gdb/Set-Watchpoints.html)
+/// regarding watchpoints for how they're supposed to work.
+///
+/// _Note:_ If this extension isn't implemented, GDB will default to using
+/// _software watchpoints_, which tend to be excruciatingly slow (as hey are
+/// implemented by single-stepping the system, and reading the watched memory
+/// location after each step).
+pub trait HwWatchpoint: Target + Breakpoints {
+    /// Add a new hardware watchpoint.
+    /// The number of bytes to watch is specified by `len`.
+    ///
+    /// Return `Ok(false)` if the operation could not be completed.
+    fn add_hw_watchpoint(
+        &mut self,
+        addr: <Self::Arch as Arch>::Usize,
+        len: <Self::Arch as Arch>::Usize,
+        kind: WatchKind,
+    ) -> TargetResult<bool, Self>;
+
+    /// Remove an existing hardware watchpoint.
+    /// The number of bytes to watch is specified by `len`.
+    ///
+    /// Return `Ok(false)` if the operation could not be completed.
+    fn remove_hw_watchpoint(
+        &mut self,
+        addr: <Self::Arch as Arch>::Usize,
+        len: <Self::Arch as Arch>::Usize,
+        kind: WatchKind,
+    ) -> TargetResult<bool, Self>;
+
+    /// Return the maximum number of hardware watchpoints which can be set at
+    /// once.
+    ///
+    /// See [`HwBreakpoint::hw_breakpoint_count`] for details.
+    #[inline(always)]
+    fn hw_watchpoint_count(&self) -> Option<usize> {
+        None
+    }
+}
+
+define_ext!(HwWatchpointOps, HwWatchpoint);
+
+/// Iterator over the raw bytecode of a list of agent expressions attached to
+/// a breakpoint (used in
+/// [`ConditionalBreakpoints::set_breakpoint_conditions`] and
+/// [`BreakpointCommands::set_breakpoint_commands`]).
+pub struct AgentExprs<'a, 'expr> {
+    inner: &'a mut dyn Iterator<Item = &'expr [u8]>,
+}
+
+impl core::fmt::Debug for AgentExprs<'_, '_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "AgentExprs {{ .. }}")
+    }
+}
+
+impl<'a, 'expr> AgentExprs<'a, 'expr> {
+    pub(crate) fn new(inner: &'a mut dyn Iterator<Item = &'expr [u8]>) -> AgentExprs<'a, 'expr> {
+        AgentExprs { inner }
+    }
+}
+
+impl<'expr> Iterator for AgentExprs<'_, 'expr> {
+    type Item = &'expr [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// Nested Target Extension - Evaluate breakpoint conditions on the target.
+///
+/// By default, GDB evaluates breakpoint conditions itself, which requires the
+/// target to stop and report _every_ breakpoint hit, only for GDB to
+/// immediately resume the target if the condition is false. Implementing this
+/// extension allows GDB to offload conditions to the target (as [agent
+/// expressions]), so that the target only needs to report a stop when a
+/// condition holds.
+///
+/// Conditions are only offloaded for software and hardware breakpoints, and
+/// only when `set breakpoint condition-evaluation` is `auto` (the default) or
+/// `target`.
+///
+/// [agent expressions]: https://sourceware.org/gdb/current/onlinedocs/gdb/Agent-Expressions.html
+pub trait ConditionalBreakpoints: Target + Breakpoints {
+    /// Set the conditions of the (just added) breakpoint at `addr`, replacing
+    /// any conditions which were previously associated with it.
+    ///
+    /// `conds` yields the raw agent expression bytecode of each condition.
+    /// When the breakpoint is hit, the target should evaluate each condition,
+    /// and only report the stop if _any_ of them evaluate to a non-zero value.
+    /// Otherwise, the target should silently resume execution.
+    ///
+    /// If `conds` is empty, the breakpoint is unconditional, and every hit
+    /// should be reported.
+    ///
+    /// This method is called right after the breakpoint has been (re)added via
+    /// the corresponding [`SwBreakpoint`] / [`HwBreakpoint`] method. Note that
+    /// GDB updates a breakpoint's conditions by re-sending the request to add
+    /// it, so adding a breakpoint which already exists must not fail.
+    fn set_breakpoint_conditions(
+        &mut self,
+        addr: <Self::Arch as Arch>::Usize,
+        kind: <Self::Arch as Arch>::BreakpointKind,
+        conds: AgentExprs<'_, '_>,
+    ) -> TargetResult<(), Self>;
+}
+
+define_ext!(ConditionalBreakpointsOps, ConditionalBreakpoints);
+
+/// Nested Target Extension - Run breakpoint command lists on the target.
+///
+/// GDB can attach a list of commands (as [agent expressions]) to a breakpoint,
+/// which the target runs each time the breakpoint is hit, without requiring a
+/// round-trip to GDB. This is primarily used by GDB's `dprintf` command (when
+/// `set dprintf-style agent` is in effect).
+///
+/// [agent expressions]: https://sourceware.org/gdb/current/onlinedocs/gdb/Agent-Expressions.html
+pub trait BreakpointCommands: Target + Breakpoints {
+    /// Set the commands of the (just added) breakpoint at `addr`, replacing
+    /// any commands which were previously associated with it.
+    ///
+    /// `cmds` yields the raw agent expression bytecode of each command, which
+    /// should be run (in order) every time the breakpoint is hit. If `cmds` is
+    /// empty, any previous commands should be removed.
+    ///
+    /// If `persist` is true, the breakpoint and its commands should remain
+    /// installed even after GDB disconnects.
+    ///
+    /// This method is called right after the breakpoint has been (re)added via
+    /// the corresponding [`SwBreakpoint`] / [`HwBreakpoint`] method, and after
+    /// any [`ConditionalBreakpoints::set_breakpoint_conditions`] call.
+    fn set_breakpoint_commands(
+        &mut self,
+        addr: <Self::Arch as Arch>::Usize,
+        kind: <Self::Arch as Arch>::BreakpointKind,
+        cmds: AgentExprs<'_, '_>,
+        persist: bool,
+    ) -> TargetResult<(), Self>;
+}
+
+define_ext!(BreakpointCommandsOps, BreakpointCommands);
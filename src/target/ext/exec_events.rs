@@ -0,0 +1,39 @@
+//! Report `exec` events to the GDB client.
+use crate::common::Tid;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Report `exec` events to the GDB client.
+///
+/// Implementing this extension allows the target to report
+/// [`Exec`](crate::stub::BaseStopReason::Exec) stop reasons, which GDB uses to
+/// discard the symbols of the old executable image and load those of the new
+/// one (see `set follow-exec-mode`).
+///
+/// Exec events are only reported to GDB clients which negotiate support for
+/// them (via `exec-events+` in `qSupported`). When reported to a GDB client
+/// which did _not_ negotiate exec events, an `Exec` stop reason is reported as
+/// a plain `SIGTRAP`.
+pub trait ExecEvents: Target {
+    /// Get the absolute pathname of the executable that thread `tid` has just
+    /// `exec`'d.
+    ///
+    /// The pathname is treated as an opaque sequence of bytes (i.e: it is not
+    /// required to be valid UTF-8), and is hex-encoded in its entirety when
+    /// reported to GDB.
+    ///
+    /// Return the number of bytes written into `buf` (which may be less than
+    /// `length`).
+    ///
+    /// If `offset` is greater than the length of the underlying data, return
+    /// `Ok(0)`.
+    fn get_exec_event_path(
+        &mut self,
+        tid: Tid,
+        offset: u64,
+        length: usize,
+        buf: &mut [u8],
+    ) -> TargetResult<usize, Self>;
+}
+
+define_ext!(ExecEventsOps, ExecEvents);
@@ -0,0 +1,62 @@
+//! Provide flash programming operations on the target.
+//!
+//! GDB only uses these operations (via the `vFlashErase`, `vFlashWrite` and
+//! `vFlashDone` packets, sent during `load`) when writing to memory regions
+//! which the target's memory map marks as flash. As such, targets implementing
+//! this extension must also implement
+//! [`MemoryMap`](crate::target::ext::memory_map::MemoryMap), and report their
+//! flash regions using `<memory type="flash">` entries (along with the
+//! mandatory `blocksize` property), e.g:
+//!
+//! ```xml
+//! <memory-map>
+//!     <memory type="flash" start="0x08000000" length="0x20000">
+//!         <property name="blocksize">0x800</property>
+//!     </memory>
+//!     <memory type="ram" start="0x20000000" length="0x5000"/>
+//! </memory-map>
+//! ```
+use crate::arch::Arch;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Program the target's flash memory.
+///
+/// See the [module level documentation](self) for details on how GDB decides
+/// to use these operations.
+pub trait FlashOperations: Target {
+    /// Erase `length` bytes of the target's flash memory, starting at
+    /// `start_addr`.
+    ///
+    /// GDB ensures the erased region is aligned to the flash's block size, as
+    /// reported in the target's memory map.
+    fn flash_erase(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        length: <Self::Arch as Arch>::Usize,
+    ) -> TargetResult<(), Self>;
+
+    /// Write `data` to the target's flash memory, starting at `start_addr`.
+    ///
+    /// GDB only writes to regions which have previously been erased with
+    /// [`flash_erase`](Self::flash_erase), and never writes to overlapping
+    /// regions within a single programming sequence.
+    ///
+    /// Writes may be buffered by the target until
+    /// [`flash_done`](Self::flash_done) is called, at which point all pending
+    /// writes must have completed.
+    fn flash_write(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        data: &[u8],
+    ) -> TargetResult<(), Self>;
+
+    /// Signal that the current sequence of flash operations has completed.
+    ///
+    /// Any buffered writes must be committed to flash before returning, as GDB
+    /// assumes the contents of flash memory are unpredictable until this
+    /// point.
+    fn flash_done(&mut self) -> TargetResult<(), Self>;
+}
+
+define_ext!(FlashOperationsOps, FlashOperations);
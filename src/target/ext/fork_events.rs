@@ -0,0 +1,65 @@
+//! Report `fork` (and `vfork`) events to the GDB client.
+use crate::common::Pid;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Report `fork` events to the GDB client.
+///
+/// Implementing this extension allows the target to report
+/// [`Fork`](crate::stub::BaseStopReason::Fork) stop reasons, which are used by
+/// the GDB client to implement `set follow-fork-mode` and `set
+/// detach-on-fork`.
+///
+/// Fork events are only reported to GDB clients which negotiate support for
+/// them (via `fork-events+` in `qSupported`). When reported to a GDB client
+/// which did _not_ negotiate fork events, a `Fork` stop reason is reported as a
+/// plain `SIGTRAP` on the parent thread.
+///
+/// Since fork events refer to the newly created child process by its `Pid`,
+/// targets implementing this extension should also implement
+/// [`CurrentActivePid`](crate::target::ext::extended_mode::CurrentActivePid),
+/// and report thread-ids which are unique across processes.
+pub trait ForkEvents: Target {
+    /// Select the process which subsequent register / memory accesses should
+    /// be directed at.
+    ///
+    /// This is called whenever the GDB client selects a thread using a
+    /// multiprocess thread-id (e.g: `Hgp<pid>.<tid>`). After a fork event has
+    /// been reported, GDB will typically select a thread in the new child
+    /// process in order to inspect (and potentially detach from) it.
+    ///
+    /// It may also be called to briefly select another process (e.g: to check
+    /// whether one of its threads is alive via `T`), in which case the original
+    /// process is re-selected afterwards.
+    ///
+    /// Note that this method may be called with the `pid` of the process which
+    /// is already selected.
+    fn set_current_process(&mut self, pid: Pid) -> TargetResult<(), Self>;
+
+    /// Support for reporting `vfork` events.
+    #[inline(always)]
+    fn support_vfork_events(&mut self) -> Option<VforkEventsOps<'_, Self>> {
+        None
+    }
+}
+
+define_ext!(ForkEventsOps, ForkEvents);
+
+/// Nested Target Extension - Report `vfork` events to the GDB client.
+///
+/// Implementing this extension allows the target to report
+/// [`Vfork`](crate::stub::BaseStopReason::Vfork) and
+/// [`VforkDone`](crate::stub::BaseStopReason::VforkDone) stop reasons. Unlike
+/// `fork`, the child of a `vfork` shares its parent's address space until it
+/// calls `exec` or exits, at which point the target should report `VforkDone`
+/// on the thread which originally called `vfork`.
+///
+/// As with fork events, vfork events are only reported to GDB clients which
+/// negotiate support for them (via `vfork-events+` in `qSupported`), and are
+/// otherwise reported as a plain `SIGTRAP`.
+///
+/// This extension has no methods of its own, as the child process is selected
+/// via [`ForkEvents::set_current_process`].
+pub trait VforkEvents: ForkEvents {}
+
+define_ext!(VforkEventsOps, VforkEvents);
@@ -0,0 +1,48 @@
+//! Let the target handle Ctrl-C interrupts itself.
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Request that the target stop when the client sends a
+/// Ctrl-C interrupt.
+///
+/// Without this extension, stopping the target in response to an interrupt
+/// is left entirely up to the code driving the
+/// [`GdbStubStateMachine`](crate::stub::state_machine::GdbStubStateMachine).
+/// With it, `gdbstub` calls [`interrupt`](Self::interrupt) as soon as the
+/// interrupt is received (via either the raw `\x03` byte, or the non-stop
+/// mode `vCtrlC` packet), right before transitioning into the
+/// `CtrlCInterrupt` state.
+///
+/// # Delivering the stop reply
+///
+/// `interrupt` only _requests_ that the target stop - it does not report a
+/// stop reason. Once the target has actually stopped, the stop reply is
+/// delivered as usual:
+///
+/// - If the target stops synchronously (i.e: before `interrupt` returns), pass
+///   the resulting stop reason to `interrupt_handled`. When in doubt,
+///   `Signal::SIGINT` is a sensible stop reason to report.
+/// - Otherwise, pass `None` to `interrupt_handled`, and report the stop reason
+///   via `report_stop` once the target comes to a halt.
+///
+/// In either case, exactly one stop reason should be reported per interrupt.
+/// [`GdbStub::run_blocking`](crate::stub::GdbStub::run_blocking) users report
+/// the stop reason from `BlockingEventLoop::on_interrupt`, or from
+/// `wait_for_stop_reason` (if the stop is deferred).
+pub trait Interrupt: Target {
+    /// Request that the target stop.
+    ///
+    /// In all-stop mode, every running thread should be stopped. In non-stop
+    /// mode, it is enough to stop (at least) one thread.
+    ///
+    /// This method may also be called while the target is already stopped
+    /// (e.g: if the client sends an interrupt right as the target halts). In
+    /// that case, it should be a no-op.
+    ///
+    /// Returning a non-fatal error in response to `vCtrlC` results in an
+    /// error reply being sent to the client. As the raw `\x03` byte doesn't
+    /// receive a reply, non-fatal errors are ignored in that case.
+    fn interrupt(&mut self) -> TargetResult<(), Self>;
+}
+
+define_ext!(InterruptOps, Interrupt);
@@ -0,0 +1,55 @@
+//! Report information about the loaded shared libraries for targets where there
+//! are possibly multiple files to be debugged mapped into the same address
+//! space.
+
+use crate::arch::Arch;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// A shared library loaded on an SVR4 (System-V/Unix) target, corresponding
+/// to an entry in the dynamic linker's `link_map` list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LibrarySvr4<'a, U> {
+    /// The library's pathname.
+    ///
+    /// Not required to be valid UTF-8.
+    pub name: &'a [u8],
+    /// Address of the library's `link_map` structure.
+    pub lm: U,
+    /// The difference between the addresses in the library's ELF file and
+    /// the addresses in memory.
+    pub l_addr: U,
+    /// Address of the library's `PT_DYNAMIC` segment.
+    pub l_ld: U,
+    /// (optional) The link-map namespace the library was loaded into.
+    ///
+    /// Targets with a single (default) link-map namespace can leave this as
+    /// `None`.
+    pub lmid: Option<U>,
+}
+
+/// Target Extension - List an SVR4 (System-V/Unix) target's libraries.
+///
+/// `gdbstub` takes care of serializing the reported libraries into the
+/// library list XML document described in the [GDB Documentation], and of
+/// transferring it to the GDB client in chunks.
+///
+/// [GDB Documentation]: https://sourceware.org/gdb/current/onlinedocs/gdb.html/Library-List-Format-for-SVR4-Targets.html
+pub trait LibrariesSvr4: Target {
+    /// Report the address of the main executable's `link_map` entry (if known).
+    fn main_lm(&self) -> TargetResult<Option<<Self::Arch as Arch>::Usize>, Self>;
+
+    /// Report each of the target's loaded libraries using the `report`
+    /// callback.
+    ///
+    /// This method may be called multiple times while transferring the library
+    /// list to the GDB client, and must report the same libraries (in the same
+    /// order) each time it is called, as long as the target has not been
+    /// resumed.
+    fn list_libraries_svr4(
+        &self,
+        report: &mut dyn FnMut(LibrarySvr4<'_, <Self::Arch as Arch>::Usize>),
+    ) -> TargetResult<(), Self>;
+}
+
+define_ext!(LibrariesSvr4Ops, LibrariesSvr4);
@@ -0,0 +1,25 @@
+//! Search the target's memory for a byte pattern.
+use crate::arch::Arch;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Search the target's memory for a byte pattern.
+///
+/// Corresponds to the `qSearch:memory` packet, which is sent by GDB's `find`
+/// command. If this extension is not implemented, GDB falls back to reading
+/// memory over the wire and searching it client-side.
+pub trait MemorySearch: Target {
+    /// Search `len` bytes of memory starting at `start_addr` for the first
+    /// occurrence of `pattern`.
+    ///
+    /// Return `Ok(Some(addr))` with the address of the first match, or
+    /// `Ok(None)` if the pattern was not found in the given range.
+    fn search_memory(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        len: <Self::Arch as Arch>::Usize,
+        pattern: &[u8],
+    ) -> TargetResult<Option<<Self::Arch as Arch>::Usize>, Self>;
+}
+
+define_ext!(MemorySearchOps, MemorySearch);
@@ -0,0 +1,41 @@
+//! Read and write memory allocation tags (e.g: ARM's Memory Tagging
+//! Extension).
+use crate::arch::Arch;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Read and write memory allocation tags.
+///
+/// Corresponds to the `qMemTags` and `QMemTags` packets. GDB will only send
+/// these packets once the stub has advertised `memory-tagging+`, which
+/// `gdbstub` does automatically when this extension is implemented.
+///
+/// The meaning of `ty` is architecture specific. e.g: on AArch64, a `ty` of
+/// `1` refers to the allocation tags of the Memory Tagging Extension.
+pub trait MemoryTagging: Target {
+    /// Read the tags associated with the `len` bytes of memory starting at
+    /// `start_addr` into `buf`.
+    ///
+    /// Return the number of tag bytes written into `buf`.
+    fn read_memory_tags(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        len: usize,
+        ty: i32,
+        buf: &mut [u8],
+    ) -> TargetResult<usize, Self>;
+
+    /// Write `tags` to the `len` bytes of memory starting at `start_addr`.
+    ///
+    /// If `tags` is shorter than the number of tags covering the range, GDB
+    /// expects the pattern to be repeated until the range is filled.
+    fn write_memory_tags(
+        &mut self,
+        start_addr: <Self::Arch as Arch>::Usize,
+        len: usize,
+        ty: i32,
+        tags: &[u8],
+    ) -> TargetResult<(), Self>;
+}
+
+define_ext!(MemoryTaggingOps, MemoryTagging);
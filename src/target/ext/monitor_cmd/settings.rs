@@ -0,0 +1,503 @@
+//! A generic `set` / `show` configuration namespace, layered on top of the
+//! [`MonitorCmd`](super::MonitorCmd) extension.
+//!
+//! Many targets expose a handful of tunables (e.g: a log level, whether to
+//! trace syscalls, where to write a trace file) via ad-hoc `monitor`
+//! commands. Instead of hand-rolling a parser for each one, targets can
+//! describe their settings as a table of typed getters / setters, and forward
+//! incoming commands to [`handle_settings_cmd`], which implements the
+//! following commands:
+//!
+//! - `monitor show`: list every setting, along with its current value.
+//! - `monitor show <name>`: show the current value of a single setting.
+//! - `monitor set <name> <value>` (or `monitor set <name>=<value>`): update a
+//!   setting.
+//!
+//! Values are parsed according to the setting's type:
+//!
+//! - integers: decimal, or hex with a `0x` prefix (optionally negative).
+//! - booleans: `on` / `off`, `true` / `false`, `yes` / `no`, or `1` / `0`.
+//! - strings: the remainder of the command, with any surrounding double quotes
+//!   stripped.
+//!
+//! Malformed commands (e.g: an unknown setting, or a value of the wrong type)
+//! print an error message to the GDB console, and are reported as having
+//! failed.
+//!
+//! # Example
+//!
+//! ```rust
+//! use gdbstub::outputln;
+//! use gdbstub::target::ext::monitor_cmd::settings::{handle_settings_cmd, Setting};
+//! use gdbstub::target::ext::monitor_cmd::{ConsoleOutput, MonitorCmd};
+//! use gdbstub::target::TargetResult;
+//! use std::convert::TryFrom;
+//! # use gdbstub::target::ext::base::BaseOps;
+//! # use gdbstub::target::Target;
+//!
+//! struct MyTarget {
+//!     log_level: u8,
+//!     trace_syscalls: bool,
+//! }
+//!
+//! # impl Target for MyTarget {
+//! #     type Error = ();
+//! #     type Arch = gdbstub_arch::arm::Armv4t; // as an example
+//! #     fn base_ops(&mut self) -> BaseOps<Self::Arch, Self::Error> { todo!() }
+//! # }
+//! #
+//! const SETTINGS: &[Setting<MyTarget>] = &[
+//!     Setting::int(
+//!         "log-level",
+//!         "verbosity of the target's logs (0-3)",
+//!         |t| t.log_level as i64,
+//!         |t, v| {
+//!             t.log_level = u8::try_from(v).map_err(|_| "out of range")?;
+//!             Ok(())
+//!         },
+//!     ),
+//!     Setting::bool(
+//!         "trace-syscalls",
+//!         "log every syscall",
+//!         |t| t.trace_syscalls,
+//!         |t, v| {
+//!             t.trace_syscalls = v;
+//!             Ok(())
+//!         },
+//!     ),
+//! ];
+//!
+//! impl MonitorCmd for MyTarget {
+//!     fn handle_monitor_cmd(
+//!         &mut self,
+//!         cmd: &[u8],
+//!         mut out: ConsoleOutput<'_>,
+//!     ) -> TargetResult<(), Self> {
+//!         if let Some(res) = handle_settings_cmd(self, SETTINGS, cmd, &mut out) {
+//!             return res;
+//!         }
+//!
+//!         // ...handle other commands...
+//!         outputln!(out, "unknown command");
+//!         Ok(())
+//!     }
+//! }
+//! ```
+
+use super::ConsoleOutput;
+use crate::outputln;
+use crate::target::Target;
+use crate::target::TargetError;
+use crate::target::TargetResult;
+use core::convert::TryFrom;
+use core::fmt;
+
+/// Setters return a short, human readable reason when rejecting a value (e.g:
+/// `"out of range"`), which is included in the error printed to the console.
+pub type SetResult = Result<(), &'static str>;
+
+/// A single setting, accessible via `monitor set` / `monitor show`.
+pub struct Setting<T: ?Sized> {
+    name: &'static str,
+    help: &'static str,
+    kind: Kind<T>,
+}
+
+enum Kind<T: ?Sized> {
+    Int {
+        get: fn(&T) -> i64,
+        set: fn(&mut T, i64) -> SetResult,
+    },
+    Bool {
+        get: fn(&T) -> bool,
+        set: fn(&mut T, bool) -> SetResult,
+    },
+    Str {
+        get: for<'a> fn(&'a T) -> &'a str,
+        set: fn(&mut T, &str) -> SetResult,
+    },
+}
+
+impl<T: ?Sized> Setting<T> {
+    /// An integer setting.
+    pub const fn int(
+        name: &'static str,
+        help: &'static str,
+        get: fn(&T) -> i64,
+        set: fn(&mut T, i64) -> SetResult,
+    ) -> Setting<T> {
+        Setting {
+            name,
+            help,
+            kind: Kind::Int { get, set },
+        }
+    }
+
+    /// A boolean setting.
+    pub const fn bool(
+        name: &'static str,
+        help: &'static str,
+        get: fn(&T) -> bool,
+        set: fn(&mut T, bool) -> SetResult,
+    ) -> Setting<T> {
+        Setting {
+            name,
+            help,
+            kind: Kind::Bool { get, set },
+        }
+    }
+
+    /// A string setting.
+    pub const fn str(
+        name: &'static str,
+        help: &'static str,
+        get: for<'a> fn(&'a T) -> &'a str,
+        set: fn(&mut T, &str) -> SetResult,
+    ) -> Setting<T> {
+        Setting {
+            name,
+            help,
+            kind: Kind::Str { get, set },
+        }
+    }
+
+    /// The setting's name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// A short description of the setting, included in the output of `monitor
+    /// show`.
+    pub fn help(&self) -> &'static str {
+        self.help
+    }
+}
+
+/// Handle a `set` / `show` command, as described in the [module-level
+/// docs](self).
+///
+/// Returns `None` if `cmd` isn't a `set` / `show` command, leaving the target
+/// free to handle it some other way. Otherwise, returns the result of the
+/// command, which should be returned from `handle_monitor_cmd` as-is.
+pub fn handle_settings_cmd<T: Target>(
+    target: &mut T,
+    settings: &[Setting<T>],
+    cmd: &[u8],
+    out: &mut ConsoleOutput<'_>,
+) -> Option<TargetResult<(), T>> {
+    let cmd = core::str::from_utf8(cmd).ok()?.trim();
+    let (verb, args) = split_word(cmd);
+
+    let res = match verb {
+        "set" => set(target, settings, args),
+        "show" => show(target, settings, args, out),
+        _ => return None,
+    };
+
+    Some(res.map_err(|e| {
+        outputln!(out, "{}", e);
+        TargetError::NonFatal
+    }))
+}
+
+fn set<'a, T>(target: &mut T, settings: &[Setting<T>], args: &'a str) -> Result<(), Error<'a>> {
+    let end = args
+        .find(|c: char| c == '=' || c.is_whitespace())
+        .unwrap_or(args.len());
+    let (name, value) = args.split_at(end);
+    let value = value.trim_start();
+    let value = value.strip_prefix('=').unwrap_or(value).trim();
+
+    if name.is_empty() {
+        return Err(Error::Usage);
+    }
+    let setting = find(settings, name)?;
+    if value.is_empty() {
+        return Err(Error::MissingValue(setting.name));
+    }
+
+    let res = match setting.kind {
+        Kind::Int { set, .. } => {
+            let value = parse_int(value).ok_or(Error::InvalidValue {
+                name: setting.name,
+                expected: "an integer",
+            })?;
+            set(target, value)
+        }
+        Kind::Bool { set, .. } => {
+            let value = parse_bool(value).ok_or(Error::InvalidValue {
+                name: setting.name,
+                expected: "`on` or `off`",
+            })?;
+            set(target, value)
+        }
+        Kind::Str { set, .. } => {
+            let value = value
+                .strip_prefix('"')
+                .and_then(|s| s.strip_suffix('"'))
+                .unwrap_or(value);
+            set(target, value)
+        }
+    };
+
+    res.map_err(|reason| Error::Rejected {
+        name: setting.name,
+        reason,
+    })
+}
+
+fn show<'a, T>(
+    target: &T,
+    settings: &[Setting<T>],
+    args: &'a str,
+    out: &mut ConsoleOutput<'_>,
+) -> Result<(), Error<'a>> {
+    if args.is_empty() {
+        for setting in settings {
+            if setting.help.is_empty() {
+                outputln!(out, "{}", Current(target, setting));
+            } else {
+                outputln!(out, "{} -- {}", Current(target, setting), setting.help);
+            }
+        }
+    } else {
+        outputln!(out, "{}", Current(target, find(settings, args)?));
+    }
+
+    Ok(())
+}
+
+fn find<'a, 'b, T>(settings: &'b [Setting<T>], name: &'a str) -> Result<&'b Setting<T>, Error<'a>> {
+    settings
+        .iter()
+        .find(|s| s.name == name)
+        .ok_or(Error::UnknownSetting(name))
+}
+
+fn split_word(s: &str) -> (&str, &str) {
+    match s.find(char::is_whitespace) {
+        Some(idx) => (&s[..idx], s[idx..].trim_start()),
+        None => (s, ""),
+    }
+}
+
+fn parse_int(s: &str) -> Option<i64> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+    // `from_str_radix` would otherwise accept a (second) sign
+    if s.starts_with(['+', '-']) {
+        return None;
+    }
+
+    // parse the magnitude unsigned, so that `i64::MIN` can be represented
+    let val = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => s.parse::<u64>().ok()?,
+    };
+    let val = i128::from(val);
+    i64::try_from(if negative { -val } else { val }).ok()
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s {
+        "on" | "true" | "yes" | "1" => Some(true),
+        "off" | "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Formats a setting as `name = value`.
+struct Current<'a, T>(&'a T, &'a Setting<T>);
+
+impl<T> fmt::Display for Current<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Current(target, setting) = self;
+        write!(f, "{} = ", setting.name)?;
+        match setting.kind {
+            Kind::Int { get, .. } => write!(f, "{}", get(target)),
+            Kind::Bool { get, .. } => write!(f, "{}", if get(target) { "on" } else { "off" }),
+            Kind::Str { get, .. } => write!(f, "\"{}\"", get(target)),
+        }
+    }
+}
+
+enum Error<'a> {
+    Usage,
+    UnknownSetting(&'a str),
+    MissingValue(&'static str),
+    InvalidValue {
+        name: &'static str,
+        expected: &'static str,
+    },
+    Rejected {
+        name: &'static str,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for Error<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage => write!(f, "usage: set <name> <value>"),
+            Error::UnknownSetting(name) => write!(f, "unknown setting `{}`", name),
+            Error::MissingValue(name) => write!(f, "missing value for `{}`", name),
+            Error::InvalidValue { name, expected } => {
+                write!(f, "invalid value for `{}`: expected {}", name, expected)
+            }
+            Error::Rejected { name, reason } => write!(f, "cannot set `{}`: {}", name, reason),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub::mock::mock_target;
+    use crate::stub::mock::zeroed_single_thread_base;
+    use core::convert::TryFrom;
+
+    #[derive(Default)]
+    struct SettingsTarget {
+        pc: u32,
+        stop_registers: bool,
+        memory_map: Option<String>,
+    }
+
+    mock_target!(SettingsTarget: SingleThread);
+    zeroed_single_thread_base!(SettingsTarget);
+
+    const SETTINGS: &[Setting<SettingsTarget>] = &[
+        Setting::int(
+            "pc",
+            "the program counter",
+            |t| t.pc as i64,
+            |t, v| {
+                t.pc = u32::try_from(v).map_err(|_| "out of range")?;
+                Ok(())
+            },
+        ),
+        Setting::bool(
+            "stop-registers",
+            "",
+            |t| t.stop_registers,
+            |t, v| {
+                t.stop_registers = v;
+                Ok(())
+            },
+        ),
+        Setting::str(
+            "memory-map",
+            "memory map XML",
+            |t| t.memory_map.as_deref().unwrap_or(""),
+            |t, v| {
+                t.memory_map = Some(v.into());
+                Ok(())
+            },
+        ),
+    ];
+
+    /// Run `cmd`, returning whether it succeeded (if it was handled at all),
+    /// alongside any console output.
+    fn run(target: &mut SettingsTarget, cmd: &str) -> (Option<bool>, String) {
+        let mut buf = Vec::new();
+        let mut callback = |b: &[u8]| buf.extend_from_slice(b);
+        let res = {
+            let mut out = ConsoleOutput::new(&mut callback);
+            handle_settings_cmd(target, SETTINGS, cmd.as_bytes(), &mut out)
+        };
+        (res.map(|r| r.is_ok()), String::from_utf8(buf).unwrap())
+    }
+
+    #[test]
+    fn set_and_show() {
+        let mut target = SettingsTarget::default();
+
+        assert_eq!(run(&mut target, "set pc 0x1000"), (Some(true), "".into()));
+        assert_eq!(
+            run(&mut target, "set stop-registers=on"),
+            (Some(true), "".into())
+        );
+        assert_eq!(
+            run(&mut target, "set memory-map = \"<memory-map/>\""),
+            (Some(true), "".into())
+        );
+        assert_eq!(target.pc, 0x1000);
+        assert!(target.stop_registers);
+        assert_eq!(target.memory_map.as_deref(), Some("<memory-map/>"));
+
+        assert_eq!(
+            run(&mut target, "show pc"),
+            (Some(true), "pc = 4096\n".into())
+        );
+        assert_eq!(
+            run(&mut target, "show"),
+            (
+                Some(true),
+                "pc = 4096 -- the program counter\n\
+                 stop-registers = on\n\
+                 memory-map = \"<memory-map/>\" -- memory map XML\n"
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_commands() {
+        let mut target = SettingsTarget::default();
+
+        for (cmd, err) in [
+            ("set", "usage: set <name> <value>\n"),
+            ("set foo 1", "unknown setting `foo`\n"),
+            ("show foo", "unknown setting `foo`\n"),
+            ("set pc", "missing value for `pc`\n"),
+            ("set pc 1O", "invalid value for `pc`: expected an integer\n"),
+            (
+                "set pc --1",
+                "invalid value for `pc`: expected an integer\n",
+            ),
+            ("set pc -1", "cannot set `pc`: out of range\n"),
+            (
+                "set stop-registers maybe",
+                "invalid value for `stop-registers`: expected `on` or `off`\n",
+            ),
+        ] {
+            assert_eq!(run(&mut target, cmd), (Some(false), err.into()), "{}", cmd);
+        }
+        assert_eq!(target.pc, 0);
+    }
+
+    #[test]
+    fn int_bounds() {
+        for (s, val) in [
+            ("9223372036854775807", Some(i64::MAX)),
+            ("0x7fffffffffffffff", Some(i64::MAX)),
+            ("-9223372036854775808", Some(i64::MIN)),
+            ("-0x8000000000000000", Some(i64::MIN)),
+            ("9223372036854775808", None),
+            ("0x8000000000000000", None),
+            ("-9223372036854775809", None),
+            ("-0x8000000000000001", None),
+        ] {
+            assert_eq!(parse_int(s), val, "{}", s);
+        }
+
+        // the boundary parses, but is out of range for `pc`
+        let mut target = SettingsTarget::default();
+        assert_eq!(
+            run(&mut target, "set pc -9223372036854775808"),
+            (Some(false), "cannot set `pc`: out of range\n".into())
+        );
+    }
+
+    #[test]
+    fn other_commands_are_ignored() {
+        let mut target = SettingsTarget::default();
+
+        for cmd in ["", "reset", "settings", "\u{ff}"] {
+            assert_eq!(run(&mut target, cmd), (None, "".into()), "{}", cmd);
+        }
+        assert_eq!(run(&mut target, "  set  pc   -0x10  ").0, Some(false));
+    }
+}
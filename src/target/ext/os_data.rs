@@ -0,0 +1,53 @@
+//! Provide OS-level information about the target (`info os`).
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Provide OS-level information about the target, such as
+/// its process table, open file descriptors, or sockets.
+///
+/// This information is displayed by GDB's `info os [<type>]` command.
+///
+/// Each kind of OS data is exposed as an XML document of the following form:
+///
+/// ```xml
+/// <?xml version="1.0"?>
+/// <!DOCTYPE target SYSTEM "osdata.dtd">
+/// <osdata type="processes">
+///   <item>
+///     <column name="pid">1</column>
+///     <column name="user">root</column>
+///     <column name="command">/sbin/init</column>
+///   </item>
+/// </osdata>
+/// ```
+///
+/// See the GDB docs for [`qXfer:osdata:read`](https://sourceware.org/gdb/current/onlinedocs/gdb.html/General-Query-Packets.html#qXfer-osdata-read)
+/// for more details.
+pub trait OsData: Target {
+    /// Get the OS data document corresponding to `annex` (e.g:
+    /// `b"processes"`).
+    ///
+    /// An empty `annex` requests the list of available OS data types, which
+    /// should be returned as an `<osdata type="types">` document, with one
+    /// `<item>` per type (each containing a `Type` and `Description` column).
+    ///
+    /// Return the number of bytes written into `buf` (which may be less than
+    /// `length`).
+    ///
+    /// If `offset` is greater than the length of the underlying data, return
+    /// `Ok(0)`. Targets which have the entire document on hand can use
+    /// [`copy_range_to_buf`](crate::target::ext::copy_range_to_buf) to
+    /// implement this method.
+    ///
+    /// If `annex` does not correspond to a known type of OS data, a non-fatal
+    /// error should be returned.
+    fn get_os_data(
+        &mut self,
+        annex: &[u8],
+        offset: u64,
+        length: usize,
+        buf: &mut [u8],
+    ) -> TargetResult<usize, Self>;
+}
+
+define_ext!(OsDataOps, OsData);
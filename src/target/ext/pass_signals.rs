@@ -0,0 +1,28 @@
+//! Pass signals straight to the inferior, without reporting them to GDB.
+use crate::common::Signal;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Pass signals straight to the inferior, without stopping
+/// to report them to GDB.
+///
+/// Implementing this extension allows the GDB client to mark signals as
+/// `handle <signal> nostop noprint pass`, which avoids a round-trip to the GDB
+/// client every time the inferior receives a frequently-delivered signal (e.g:
+/// `SIGALRM`). See the [GDB documentation](https://sourceware.org/gdb/onlinedocs/gdb/Signals.html)
+/// for further details.
+///
+/// Corresponds to the `QPassSignals` command.
+pub trait PassSignals: Target {
+    /// Set the list of signals which should be passed directly to the
+    /// inferior, instead of being reported to GDB.
+    ///
+    /// Signals not included in `signals` should be reported as usual.
+    ///
+    /// Note: lists are not combined, subsequent calls to this method should
+    /// replace the existing list of passed signals. An empty list means that
+    /// no signals should be passed without being reported.
+    fn pass_signals(&mut self, signals: &[Signal]) -> TargetResult<(), Self>;
+}
+
+define_ext!(PassSignalsOps, PassSignals);
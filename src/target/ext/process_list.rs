@@ -0,0 +1,36 @@
+//! Enumerate the threads of every process being debugged.
+use crate::common::Pid;
+use crate::common::Tid;
+use crate::target::Target;
+
+/// Target Extension - Enumerate the threads of every process being debugged.
+///
+/// By default, `gdbstub` only reports the threads of the current process
+/// (as returned by
+/// [`MultiThreadBase::list_active_threads`](crate::target::ext::base::multithread::MultiThreadBase::list_active_threads)).
+/// Multi-process targets can implement this extension to have the thread list
+/// sent in response to `qfThreadInfo` / `qsThreadInfo` include the threads of
+/// every process, using fully-qualified `p<pid>.<tid>` thread-ids.
+///
+/// This extension is only used once the GDB client has negotiated support for
+/// the multiprocess protocol extensions.
+pub trait ProcessList: Target {
+    /// Report the ID of each process being debugged by calling
+    /// `process_is_active` once per process.
+    ///
+    /// Processes must be reported in a consistent order across calls, as
+    /// `gdbstub` reports the thread list one process at a time, and relies on
+    /// each process' position to resume enumeration.
+    fn list_processes(&mut self, process_is_active: &mut dyn FnMut(Pid))
+        -> Result<(), Self::Error>;
+
+    /// Report the ID of each active thread within the process `pid` by calling
+    /// `thread_is_active` once per thread.
+    fn list_process_threads(
+        &mut self,
+        pid: Pid,
+        thread_is_active: &mut dyn FnMut(Tid),
+    ) -> Result<(), Self::Error>;
+}
+
+define_ext!(ProcessListOps, ProcessList);
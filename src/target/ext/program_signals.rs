@@ -0,0 +1,27 @@
+//! Control which signals are delivered to the inferior.
+use crate::common::Signal;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Control which signals are delivered to the inferior.
+///
+/// Implementing this extension allows the GDB client to communicate the
+/// signal dispositions configured via `handle <signal> pass` / `handle
+/// <signal> nopass`. See the [GDB documentation](https://sourceware.org/gdb/onlinedocs/gdb/Signals.html)
+/// for further details.
+///
+/// Corresponds to the `QProgramSignals` command.
+pub trait ProgramSignals: Target {
+    /// Set the complete list of signals which the inferior is allowed to
+    /// receive.
+    ///
+    /// Any signal that is not included in `signals` should be discarded,
+    /// instead of being delivered to the inferior.
+    ///
+    /// Note: lists are not combined, subsequent calls to this method should
+    /// replace the existing list of program signals. An empty list means that
+    /// no signals should be delivered to the inferior.
+    fn program_signals(&mut self, signals: &[Signal]) -> TargetResult<(), Self>;
+}
+
+define_ext!(ProgramSignalsOps, ProgramSignals);
@@ -0,0 +1,120 @@
+//! Get notified about changes to the state of the GDB protocol session.
+use crate::stub::ConnectionErrorKind;
+use crate::stub::DisconnectReason;
+use crate::target::Target;
+
+/// Target Extension - Get notified about changes to the state of the GDB
+/// protocol session.
+///
+/// Unlike most other extensions, these callbacks do not correspond to any
+/// specific feature the target supports. Instead, they give the target
+/// visibility into protocol-level transitions, e.g: to collect stats, or to
+/// adjust the underlying connection's buffering / timeouts.
+///
+/// Every callback has a default no-op implementation, so targets only need to
+/// override the ones they are interested in.
+pub trait ProtocolCallbacks: Target {
+    /// Invoked once the client has switched the session into no-ack mode (via
+    /// the `QStartNoAckMode` packet), right after the `OK` response has been
+    /// flushed to the connection.
+    ///
+    /// From this point on, neither side will send `+`/`-` acknowledgements.
+    fn on_no_ack_mode_enabled(&mut self) {}
+
+    /// Invoked once the client has advertised the features it supports (via
+    /// the `qSupported` packet), before the stub's own features are reported
+    /// back to the client.
+    fn on_client_features(&mut self, features: ClientFeatures) {
+        let _ = features;
+    }
+
+    /// Invoked exactly once when the session ends (e.g: after the client
+    /// detaches via `D`, or kills the target via `k`), right before the stub
+    /// transitions into the `Disconnected` state.
+    ///
+    /// This is a good place to remove any breakpoints that are still
+    /// installed in the target, as the client will not be around to handle
+    /// them being hit.
+    ///
+    /// NOTE: this callback is _not_ invoked when the underlying connection
+    /// returns an error (e.g: the socket was dropped). See
+    /// [`on_connection_error`](Self::on_connection_error).
+    fn on_disconnect(&mut self, reason: DisconnectReason) {
+        let _ = reason;
+    }
+
+    /// Invoked when the session is aborted due to an error on the underlying
+    /// connection (e.g: the socket was dropped mid-session), right before the
+    /// corresponding [`GdbStubError`](crate::stub::GdbStubError) is returned.
+    ///
+    /// Unlike a `D` / `k` packet, the client never gets a chance to remove
+    /// its breakpoints in this case, leaving the target stuck on them. As
+    /// such, this is the place to perform the same cleanup as in
+    /// [`on_disconnect`](Self::on_disconnect).
+    ///
+    /// This callback is invoked by every
+    /// [`GdbStubStateMachine`](crate::stub::state_machine::GdbStubStateMachine)
+    /// method, as well as by `GdbStub::run_blocking` / `GdbStub::run_async`.
+    /// Integrations which read from the connection themselves (i.e: when
+    /// driving the state machine directly) are responsible for cleaning up
+    /// after their own read errors.
+    fn on_connection_error(&mut self, kind: ConnectionErrorKind) {
+        let _ = kind;
+    }
+}
+
+define_ext!(ProtocolCallbacksOps, ProtocolCallbacks);
+
+/// Features advertised by the GDB client as part of the `qSupported`
+/// handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientFeatures {
+    pub(crate) multiprocess: bool,
+    pub(crate) swbreak: bool,
+    pub(crate) hwbreak: bool,
+    pub(crate) fork_events: bool,
+    pub(crate) vfork_events: bool,
+    pub(crate) exec_events: bool,
+    pub(crate) packet_size: Option<usize>,
+}
+
+impl ClientFeatures {
+    /// Whether the client supports multiprocess extensions.
+    pub fn multiprocess(&self) -> bool {
+        self.multiprocess
+    }
+
+    /// Whether the client supports the `swbreak` stop reason.
+    pub fn swbreak(&self) -> bool {
+        self.swbreak
+    }
+
+    /// Whether the client supports the `hwbreak` stop reason.
+    pub fn hwbreak(&self) -> bool {
+        self.hwbreak
+    }
+
+    /// Whether the client supports `fork` events.
+    pub fn fork_events(&self) -> bool {
+        self.fork_events
+    }
+
+    /// Whether the client supports `vfork` events.
+    pub fn vfork_events(&self) -> bool {
+        self.vfork_events
+    }
+
+    /// Whether the client supports `exec` events.
+    pub fn exec_events(&self) -> bool {
+        self.exec_events
+    }
+
+    /// The largest packet the client is willing to accept, if it specified
+    /// one (via `PacketSize=`).
+    ///
+    /// `gdbstub` takes care to split up console output (e.g: from `monitor`
+    /// commands) to fit within this limit.
+    pub fn packet_size(&self) -> Option<usize> {
+        self.packet_size
+    }
+}
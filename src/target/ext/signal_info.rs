@@ -0,0 +1,40 @@
+//! Provide the `siginfo` of the signal which stopped the target.
+use crate::common::Tid;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Provide the `siginfo` of the signal which stopped the
+/// target.
+///
+/// GDB uses this information to populate the `$_siginfo` convenience
+/// variable, which (among other things) includes the faulting address of a
+/// `SIGSEGV`.
+///
+/// The layout of the returned bytes is target-defined, and must match the
+/// `siginfo_t` layout GDB expects for the target's architecture / OS ABI
+/// (e.g: the layout used by the Linux kernel on the target architecture).
+pub trait SignalInfo: Target {
+    /// Get the raw `siginfo` bytes corresponding to the signal which most
+    /// recently stopped thread `tid` (i.e: the thread most recently selected by
+    /// the GDB client). Single threaded targets can ignore `tid`.
+    ///
+    /// Return the number of bytes written into `buf` (which may be less than
+    /// `length`).
+    ///
+    /// If `offset` is greater than the length of the underlying data, return
+    /// `Ok(0)`. Targets which have the entire `siginfo` on hand can use
+    /// [`copy_range_to_buf`](crate::target::ext::copy_range_to_buf) to
+    /// implement this method.
+    ///
+    /// If the target did not stop due to a signal, a non-fatal error should be
+    /// returned.
+    fn get_siginfo(
+        &mut self,
+        tid: Tid,
+        offset: u64,
+        length: usize,
+        buf: &mut [u8],
+    ) -> TargetResult<usize, Self>;
+}
+
+define_ext!(SignalInfoOps, SignalInfo);
@@ -0,0 +1,31 @@
+//! Include register values in stop replies.
+use crate::common::Tid;
+use crate::target::Target;
+
+/// Target Extension - Include ("expedite") a small set of register values in
+/// stop replies.
+///
+/// After every stop, GDB typically needs to know the value of a few key
+/// registers (e.g: the PC, SP, and FP) in order to display the current
+/// location. By including these registers in the stop reply, the client can
+/// avoid an additional round-trip to read all of the target's registers,
+/// which can significantly speed up single-stepping over slow links.
+pub trait StopRegisters: Target {
+    /// Report the registers which should be included in the stop reply for
+    /// thread `tid`, by invoking `report` with each register's GDB register
+    /// number and value.
+    ///
+    /// Register numbers must match the ordering of registers in the target's
+    /// description XML, and values must be provided in the target's byte
+    /// order (i.e: using the same encoding as when the register is read via
+    /// the `p` packet).
+    ///
+    /// Single threaded targets can safely ignore `tid`.
+    fn stop_registers(
+        &mut self,
+        tid: Tid,
+        report: &mut dyn FnMut(usize, &[u8]),
+    ) -> Result<(), Self::Error>;
+}
+
+define_ext!(StopRegistersOps, StopRegisters);
@@ -0,0 +1,81 @@
+//! Override the target description XML specified by `Target::Arch`.
+use crate::common::Tid;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Override the target description XML specified by
+/// `Target::Arch`.
+///
+/// _Note:_ Unless you're working with a particularly dynamic,
+/// runtime-configurable target, it's unlikely that you'll need to implement
+/// this extension.
+///
+/// Targets whose register set only varies by a handful of optional features
+/// (e.g: x86 with/without AVX, ARM with/without NEON) can assemble their XML
+/// at runtime from static fragments using
+/// [`TargetDescription`](crate::arch::target_xml::TargetDescription).
+pub trait TargetDescriptionXmlOverride: Target {
+    /// Read a target's description XML file at the specified `annex`.
+    ///
+    /// The "root" `annex` will always be `b"target.xml"`, though advanced
+    /// targets may choose to split `target.xml` into multiple files via the
+    /// the `<xi:include href="other_file.xml"/>` XML tag. If the GDB client
+    /// encounter any such tags, it will re-invoke this handler with `annex`
+    /// specified to point to `b"other_file.xml"`.
+    ///
+    /// The contents of each `annex` are entirely up to the target, and may be
+    /// generated on-the-fly (hence `&mut self`, enabling the target to cache
+    /// any lazily generated documents). If `annex` does not correspond to a
+    /// known document, a non-fatal error should be returned.
+    ///
+    /// Refer to the
+    /// [target_description_xml](crate::arch::Arch::target_description_xml)
+    /// docs for more info.
+    ///
+    /// Return the number of bytes written into `buf` (which may be less than
+    /// `length`).
+    ///
+    /// If `offset` is greater than the length of the underlying data, return
+    /// `Ok(0)`.
+    fn target_description_xml(
+        &mut self,
+        annex: &[u8],
+        offset: u64,
+        length: usize,
+        buf: &mut [u8],
+    ) -> TargetResult<usize, Self>;
+
+    /// (optional) Read the target description XML file at the specified
+    /// `annex`, as it applies to thread `tid` (i.e: the thread most recently
+    /// selected by the GDB client).
+    ///
+    /// Heterogeneous targets (e.g: a SoC with both 32-bit and 64-bit cores
+    /// exposed as separate threads / processes) can override this method to
+    /// describe a different register layout for each thread. Such targets
+    /// should use an [`Arch::Registers`](crate::arch::Arch::Registers) type
+    /// which can represent every layout, and serialize whichever layout applies
+    /// to the thread being accessed.
+    ///
+    /// Note that GDB typically only reads the target description once per
+    /// inferior (e.g: when attaching), so heterogeneous cores are best exposed
+    /// to GDB as separate processes.
+    ///
+    /// The default implementation ignores `tid`, and defers to
+    /// [`target_description_xml`](Self::target_description_xml).
+    fn thread_target_description_xml(
+        &mut self,
+        tid: Tid,
+        annex: &[u8],
+        offset: u64,
+        length: usize,
+        buf: &mut [u8],
+    ) -> TargetResult<usize, Self> {
+        let _ = tid;
+        self.target_description_xml(annex, offset, length, buf)
+    }
+}
+
+define_ext!(
+    TargetDescriptionXmlOverrideOps,
+    TargetDescriptionXmlOverride
+);
@@ -0,0 +1,29 @@
+//! Report thread creation and exit events to the GDB client.
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Report thread creation and exit events to the GDB
+/// client.
+///
+/// Implementing this extension allows the target to report
+/// [`ThreadCreated`](crate::stub::BaseStopReason::ThreadCreated) and
+/// [`ThreadExited`](crate::stub::BaseStopReason::ThreadExited) stop reasons,
+/// which keep the GDB client's thread list up to date without it having to
+/// re-query the thread list after every stop.
+///
+/// Thread events are only reported to GDB clients which enable them (via
+/// `QThreadEvents:1`). Any thread event reported while they are disabled is
+/// silently dropped, with the target remaining in the running state, so
+/// targets may avoid stopping for them in the first place by tracking
+/// [`set_thread_events`](Self::set_thread_events).
+pub trait ThreadEvents: Target {
+    /// Called whenever the GDB client enables or disables thread events.
+    ///
+    /// Thread events are disabled at the start of each session.
+    fn set_thread_events(&mut self, enabled: bool) -> TargetResult<(), Self> {
+        let _ = enabled;
+        Ok(())
+    }
+}
+
+define_ext!(ThreadEventsOps, ThreadEvents);
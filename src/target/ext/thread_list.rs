@@ -0,0 +1,34 @@
+//! Provide a detailed list of threads (name, core) to the client.
+use crate::common::Tid;
+use crate::target::Target;
+
+/// Information about a single thread, as reported by
+/// [`ThreadList::list_threads`].
+#[derive(Debug, Clone, Copy)]
+pub struct ThreadInfo<'a> {
+    /// The thread's id.
+    pub tid: Tid,
+    /// A human-readable name for the thread (e.g: an RTOS task name).
+    pub name: Option<&'a [u8]>,
+    /// The processor core the thread was last seen running on.
+    pub core: Option<usize>,
+}
+
+/// Target Extension - Provide a detailed list of threads.
+///
+/// Corresponds to the `qXfer:threads:read` packet. When implemented, GDB will
+/// display the provided thread names as part of the `info threads` command.
+///
+/// `gdbstub` takes care of serializing the list of threads into the XML
+/// document expected by GDB (including splitting it across multiple packets).
+pub trait ThreadList: Target {
+    /// Report information about each active thread by calling `report` once
+    /// per thread.
+    ///
+    /// The list of reported threads should match the list of threads reported
+    /// via `list_active_threads`, and must be reported in the same order
+    /// across repeated calls (as GDB reads the list in chunks).
+    fn list_threads(&mut self, report: &mut dyn FnMut(ThreadInfo<'_>)) -> Result<(), Self::Error>;
+}
+
+define_ext!(ThreadListOps, ThreadList);
@@ -0,0 +1,35 @@
+//! Resolve the addresses of thread-local variables.
+use crate::arch::Arch;
+use crate::common::Tid;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Resolve the addresses of thread-local variables.
+///
+/// GDB uses this extension to evaluate expressions involving thread-local
+/// (e.g: `__thread` / `thread_local`) variables. Without it, attempting to
+/// `print` such a variable will fail.
+///
+/// Implementing this extension requires the target to know the TLS layout
+/// used by the program being debugged (e.g: the location of each thread's
+/// TLS block, and the module IDs assigned by the dynamic linker).
+pub trait ThreadLocalStorage: Target {
+    /// Return the address of the thread-local variable located at `offset`
+    /// within the TLS block of the module described by `load_module`, for
+    /// the thread `tid`.
+    ///
+    /// `load_module` is the address of the module's `link_map` structure (as
+    /// determined by the GDB client), or `0` for statically linked
+    /// executables.
+    ///
+    /// If the address cannot be resolved (e.g: the TLS block for the module
+    /// has not been allocated yet), a non-fatal error should be returned.
+    fn get_tls_addr(
+        &mut self,
+        tid: Tid,
+        offset: <Self::Arch as Arch>::Usize,
+        load_module: <Self::Arch as Arch>::Usize,
+    ) -> TargetResult<<Self::Arch as Arch>::Usize, Self>;
+}
+
+define_ext!(ThreadLocalStorageOps, ThreadLocalStorage);
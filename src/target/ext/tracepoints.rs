@@ -0,0 +1,128 @@
+//! Define tracepoints and run trace experiments.
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// A tracepoint, identified by its GDB-assigned number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tracepoint(pub usize);
+
+/// A new tracepoint, as defined by the GDB client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewTracepoint<U> {
+    /// The tracepoint's number.
+    pub number: Tracepoint,
+    /// The address the tracepoint is placed at.
+    pub addr: U,
+    /// Whether the tracepoint is enabled.
+    pub enabled: bool,
+    /// The number of single-steps to perform (and collect data for) after the
+    /// tracepoint is hit.
+    pub step_count: u64,
+    /// The number of times the tracepoint may be hit before the trace
+    /// experiment is stopped. `0` means there is no limit.
+    pub pass_count: u64,
+}
+
+/// The status of the current trace experiment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceExperimentStatus {
+    /// A trace experiment is currently running.
+    Running,
+    /// No trace experiment has been run yet.
+    NotRun,
+    /// The trace experiment was stopped by the GDB client.
+    Stopped,
+    /// The trace experiment was stopped due to the trace buffer being full.
+    BufferFull,
+    /// The trace experiment was stopped due to the given tracepoint reaching
+    /// its pass count.
+    PassCount(Tracepoint),
+}
+
+/// A trace state variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceStateVariable<'a> {
+    /// The variable's GDB-assigned number.
+    pub number: usize,
+    /// The variable's initial value.
+    pub initial_value: i64,
+    /// Whether the variable is built into the target (as opposed to having
+    /// been created by the user).
+    pub builtin: bool,
+    /// The variable's name (without the leading `$`).
+    pub name: &'a [u8],
+}
+
+/// Target Extension - Define tracepoints and run trace experiments.
+///
+/// Tracepoints allow the GDB client to inspect the state of the program at
+/// specific locations without stopping it. See the [GDB Tracepoints
+/// documentation](https://sourceware.org/gdb/onlinedocs/gdb/Tracepoints.html)
+/// for further details.
+///
+/// NOTE: `gdbstub` currently only supports plain, non-collecting tracepoints.
+/// Attempting to define a tracepoint with actions (e.g: `collect`), a
+/// condition, or a fast tracepoint will result in an error being reported to
+/// the GDB client, without any of this extension's methods being called.
+pub trait Tracepoints: Target {
+    /// Clear the current trace experiment, removing any existing tracepoints.
+    fn tracepoints_init(&mut self) -> TargetResult<(), Self>;
+
+    /// Define a new tracepoint.
+    fn tracepoint_define(
+        &mut self,
+        tp: NewTracepoint<<Self::Arch as crate::arch::Arch>::Usize>,
+    ) -> TargetResult<(), Self>;
+
+    /// Start a new trace experiment, using the tracepoints defined so far.
+    fn trace_experiment_start(&mut self) -> TargetResult<(), Self>;
+
+    /// Stop the current trace experiment.
+    fn trace_experiment_stop(&mut self) -> TargetResult<(), Self>;
+
+    /// Query the status of the current trace experiment.
+    fn trace_experiment_status(&mut self) -> TargetResult<TraceExperimentStatus, Self>;
+
+    /// Support for trace state variables.
+    #[inline(always)]
+    fn support_trace_state_variables(&mut self) -> Option<TraceStateVariablesOps<'_, Self>> {
+        None
+    }
+}
+
+define_ext!(TracepointsOps, Tracepoints);
+
+/// Nested Target Extension - Define and query trace state variables.
+///
+/// Trace state variables are named 64-bit counters which live on the target,
+/// and can be read (e.g: via `tvariables`, or by printing `$name`) while a
+/// trace experiment is running. See the [GDB Trace State Variables
+/// documentation](https://sourceware.org/gdb/onlinedocs/gdb/Trace-State-Variables.html)
+/// for further details.
+pub trait TraceStateVariables: Tracepoints {
+    /// Define a new trace state variable (or redefine an existing one).
+    ///
+    /// Any trace state variables defined by GDB should be discarded when
+    /// [`Tracepoints::tracepoints_init`] is called.
+    fn trace_state_variable_define(
+        &mut self,
+        tsv: TraceStateVariable<'_>,
+    ) -> TargetResult<(), Self>;
+
+    /// Return the current value of the given trace state variable, or `None`
+    /// if the value is not known.
+    fn trace_state_variable_value(&mut self, number: usize) -> TargetResult<Option<i64>, Self>;
+
+    /// Report all trace state variables currently defined on the target
+    /// (including any builtin variables), by invoking `report` once per
+    /// variable.
+    ///
+    /// Variables must be reported in a consistent order, as GDB retrieves
+    /// them one-by-one.
+    fn trace_state_variables(
+        &mut self,
+        report: &mut dyn FnMut(TraceStateVariable<'_>),
+    ) -> TargetResult<(), Self>;
+}
+
+define_ext!(TraceStateVariablesOps, TraceStateVariables);
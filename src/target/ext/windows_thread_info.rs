@@ -0,0 +1,21 @@
+//! Provide Windows-specific per-thread information.
+use crate::arch::Arch;
+use crate::common::Tid;
+use crate::target::Target;
+use crate::target::TargetResult;
+
+/// Target Extension - Provide Windows-specific per-thread information.
+///
+/// When debugging a Windows target, GDB uses the Thread Information Block
+/// (TIB) of each thread to resolve structured exception handlers, as well as
+/// thread-local storage. Without this extension, GDB gracefully degrades to
+/// not showing this information.
+pub trait WindowsThreadInfo: Target {
+    /// Return the address of the Thread Information Block of thread `tid`.
+    ///
+    /// If `tid` does not correspond to a known thread, a non-fatal error
+    /// should be returned.
+    fn get_tib_addr(&mut self, tid: Tid) -> TargetResult<<Self::Arch as Arch>::Usize, Self>;
+}
+
+define_ext!(WindowsThreadInfoOps, WindowsThreadInfo);